@@ -0,0 +1,18 @@
+/// Lifetime counters for the bump allocators backing each scope's render frames.
+///
+/// These only count while the `profile` feature is enabled. Compare `bump_allocations` against `bump_resets` to see
+/// how often frames are reused instead of reallocated over a session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocatorStats {
+    /// The number of times any scope was rendered
+    pub renders: u64,
+
+    /// The number of times a frame's existing bump was reset and reused for a render
+    pub bump_resets: u64,
+
+    /// The number of times a frame needed a fresh bump because it hadn't been used yet
+    pub bump_allocations: u64,
+
+    /// The total size of the frames at the end of each render, in bytes
+    pub bytes_allocated: u64,
+}
@@ -1,7 +1,7 @@
 use std::{marker::PhantomData, panic::AssertUnwindSafe};
 
 use crate::{
-    innerlude::Scoped,
+    innerlude::{RenderPanic, Scoped},
     nodes::{ComponentReturn, RenderReturn},
     scopes::{Scope, ScopeState},
     Element,
@@ -14,6 +14,7 @@ use crate::{
 /// This should not be implemented outside this module
 pub(crate) unsafe trait AnyProps<'a> {
     fn props_ptr(&self) -> *const ();
+    fn props_type_name(&self) -> &'static str;
     fn render(&'a self, bump: &'a ScopeState) -> RenderReturn<'a>;
     unsafe fn memoize(&self, other: &dyn AnyProps) -> bool;
 }
@@ -51,6 +52,10 @@ where
         &self.props as *const _ as *const ()
     }
 
+    fn props_type_name(&self) -> &'static str {
+        std::any::type_name::<P>()
+    }
+
     // Safety:
     // this will downcast the other ptr as our swallowed type!
     // you *must* make this check *before* calling this method
@@ -74,7 +79,18 @@ where
 
         match res {
             Ok(e) => e,
-            Err(_) => RenderReturn::default(),
+
+            // Hand the panic to the nearest error boundary instead of taking down the whole VirtualDom
+            Err(payload) => {
+                let panic = RenderPanic::from_payload(payload.as_ref());
+                log::error!(
+                    "Component {:?} panicked while rendering: {}",
+                    cx.scope_id(),
+                    panic.message
+                );
+                cx.throw(panic);
+                RenderReturn::default()
+            }
         }
     }
 }
@@ -80,12 +80,12 @@ impl VirtualDom {
     pub(crate) fn drop_scope(&mut self, id: ScopeId) {
         self.ensure_drop_safety(id);
 
-        if let Some(root) = self.scopes[id.0].as_ref().try_root_node() {
+        if let Some(root) = self.scopes[id.0].try_root_node() {
             if let RenderReturn::Ready(node) = unsafe { root.extend_lifetime_ref() } {
                 self.drop_scope_inner(node)
             }
         }
-        if let Some(root) = unsafe { self.scopes[id.0].as_ref().previous_frame().try_load_node() } {
+        if let Some(root) = unsafe { self.scopes[id.0].previous_frame().try_load_node() } {
             if let RenderReturn::Ready(node) = unsafe { root.extend_lifetime_ref() } {
                 self.drop_scope_inner(node)
             }
@@ -93,13 +93,67 @@ impl VirtualDom {
 
         self.scopes[id.0].props.take();
 
+        let tasks = self.scopes[id.0].spawned_tasks.take();
+        self.scheduler.cancel_scope(id, tasks);
+
         let scope = &mut self.scopes[id.0];
+        scope.run_unmount_callbacks();
 
         // Drop all the hooks once the children are dropped
         // this means we'll drop hooks bottom-up
         for hook in scope.hook_list.get_mut().drain(..) {
             drop(unsafe { BumpBox::from_raw(hook) });
         }
+        self.drop_retired_hooks(id);
+    }
+
+    /// Drop every hook in a scope so they all run their initializers again on the next render, as if the component
+    /// had just been mounted. The scope keeps its ID and its elements, and is marked dirty so the next render happens
+    /// with the next call to [`VirtualDom::render_immediate`] or [`VirtualDom::render_with_deadline`].
+    ///
+    /// All of the scope's hook state is lost. The last render can still be borrowing from the old hooks, so they're
+    /// set aside and only dropped, running the `Drop` implementation of each hook value, once the next render has been
+    /// diffed against it. Until then, events that reach the scope still run against the old hooks. Callbacks from
+    /// [`crate::ScopeState::on_mount`] and [`crate::ScopeState::on_unmount`] aren't registered or run again, since the
+    /// scope was never unmounted.
+    pub fn reset_scope_hooks(&mut self, id: ScopeId) {
+        let scope = match self.scopes.get_mut(id.0) {
+            Some(scope) => scope,
+            None => return,
+        };
+
+        // Resetting again before the scope re-rendered has no new hooks to set aside
+        if scope.retired_hooks.is_none() {
+            let hooks = std::mem::take(scope.hook_list.get_mut());
+            let arena = std::mem::take(&mut scope.hook_arena);
+            scope.retired_hooks = Some((arena, hooks));
+        }
+
+        scope.hook_idx.set(0);
+        scope.last_hook_count.set(None);
+        scope.hook_types.get_mut().clear();
+
+        #[cfg(feature = "serialize")]
+        scope.persistent_hooks.get_mut().clear();
+
+        self.mark_dirty(id);
+    }
+
+    /// Drop the hooks set aside by [`VirtualDom::reset_scope_hooks`], once nothing can read the render that borrowed
+    /// from them
+    pub(crate) fn drop_retired_hooks(&mut self, id: ScopeId) {
+        let retired = self
+            .scopes
+            .get_mut(id.0)
+            .and_then(|scope| scope.retired_hooks.take());
+
+        // The hooks are dropped before the arena they live in
+        if let Some((arena, hooks)) = retired {
+            for hook in hooks {
+                drop(unsafe { BumpBox::from_raw(hook) });
+            }
+            drop(arena);
+        }
     }
 
     fn drop_scope_inner(&mut self, node: &VNode) {
@@ -141,6 +195,7 @@ impl VirtualDom {
         // run the hooks (which hold an &mut Reference)
         // recursively call ensure_drop_safety on all children
         let mut props = scope.borrowed_props.borrow_mut();
+        let borrowed_props = props.len();
         props.drain(..).for_each(|comp| {
             let comp = unsafe { &*comp };
             match comp.scope.get() {
@@ -154,12 +209,24 @@ impl VirtualDom {
 
         // Now that all the references are gone, we can safely drop our own references in our listeners.
         let mut listeners = scope.listeners.borrow_mut();
+        let cleared_listeners = listeners.len();
         listeners.drain(..).for_each(|listener| {
             let listener = unsafe { &*listener };
             if let AttributeValue::Listener(l) = &listener.value {
                 _ = l.take();
             }
         });
+
+        // A breadcrumb for tracking down references that outlive the frame they point into
+        if borrowed_props > 0 || cleared_listeners > 0 {
+            log::trace!(
+                "Cleared {} borrowed props and {} listeners of component `{}` ({:?}) before reusing its frame",
+                borrowed_props,
+                cleared_listeners,
+                scope.name,
+                scope_id
+            );
+        }
     }
 }
 
@@ -5,6 +5,10 @@ use std::cell::Cell;
 pub(crate) struct BumpFrame {
     pub bump: Bump,
     pub node: Cell<*const RenderReturn<'static>>,
+
+    // How many renders in a row left most of the frame unused, and how many bytes the last render used
+    pub small_renders: Cell<usize>,
+    pub last_used: Cell<usize>,
 }
 
 impl BumpFrame {
@@ -13,6 +17,19 @@ impl BumpFrame {
         Self {
             bump,
             node: Cell::new(std::ptr::null()),
+            small_renders: Cell::new(0),
+            last_used: Cell::new(0),
+        }
+    }
+
+    /// Get the number of bytes rendered into the frame, as opposed to the capacity it's reserved
+    pub(crate) fn used_bytes(&self) -> usize {
+        // safety: the chunks are only read to sum their lengths, and nothing is allocated while we iterate
+        unsafe {
+            self.bump
+                .iter_allocated_chunks_raw()
+                .map(|(_, len)| len)
+                .sum()
         }
     }
 
@@ -27,3 +44,69 @@ impl BumpFrame {
         unsafe { std::mem::transmute(&*node) }
     }
 }
+
+/// When to replace a frame that's much bigger than what its scope renders, set with
+/// [`crate::VirtualDom::with_frame_shrinking`]
+#[derive(Clone, Copy)]
+pub(crate) struct FrameShrinking {
+    pub ratio: usize,
+    pub renders: usize,
+}
+
+impl FrameShrinking {
+    /// Keep track of how much of a frame the render that just finished used
+    pub fn record(&self, frame: &BumpFrame) {
+        let used = frame.used_bytes();
+        frame.last_used.set(used);
+
+        if used.saturating_mul(self.ratio) < frame.bump.allocated_bytes() {
+            frame.small_renders.set(frame.small_renders.get() + 1);
+        } else {
+            frame.small_renders.set(0);
+        }
+    }
+
+    /// Get the capacity to replace a frame with before it's rendered into again, if it should shrink
+    pub fn shrunk_capacity(&self, frame: &BumpFrame) -> Option<usize> {
+        (frame.small_renders.get() >= self.renders).then(|| frame.last_used.get())
+    }
+}
+
+/// The most frames the pool holds on to. Frames freed while the pool is full are dropped.
+const MAX_POOLED_FRAMES: usize = 64;
+
+/// Frames from unmounted scopes, kept so newly mounted scopes don't have to go back to the allocator
+///
+/// Lists that mount and unmount rows constantly would otherwise allocate and free two frames for every row.
+#[derive(Default)]
+pub(crate) struct FramePool {
+    frames: Vec<BumpFrame>,
+}
+
+impl FramePool {
+    /// Take a frame from the pool, or allocate a new one with `capacity` if the pool is empty
+    pub fn take(&mut self, capacity: usize) -> BumpFrame {
+        self.frames
+            .pop()
+            .unwrap_or_else(|| BumpFrame::new(capacity))
+    }
+
+    /// Reset a frame and hand it back to the pool
+    pub fn recycle(&mut self, mut frame: BumpFrame) {
+        if self.frames.len() == MAX_POOLED_FRAMES {
+            return;
+        }
+
+        // Nothing rendered into the frame can be reachable from the next scope that uses it
+        frame.bump.reset();
+        frame.node.set(std::ptr::null());
+        frame.small_renders.set(0);
+        frame.last_used.set(0);
+
+        self.frames.push(frame);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+}
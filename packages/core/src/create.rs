@@ -435,7 +435,26 @@ impl<'b> VirtualDom {
             Text(text) => self.create_dynamic_text(template, text, idx),
             Placeholder(place) => self.create_placeholder(place, template, idx),
             Component(component) => self.create_component_node(template, component, idx),
-            Fragment(frag) => frag.iter().map(|child| self.create(child)).sum(),
+            Fragment(frag) => {
+                self.reserve_sibling_scopes(frag);
+                frag.iter().map(|child| self.create(child)).sum()
+            }
+        }
+    }
+
+    /// Make room for every component directly inside a list of siblings before any of them are created.
+    ///
+    /// The scopes themselves are still created one sibling at a time, since their IDs are handed out depth-first, but
+    /// a large list only grows the scope storage once instead of several times over.
+    pub(crate) fn reserve_sibling_scopes(&mut self, siblings: &[VNode]) {
+        let components = siblings
+            .iter()
+            .flat_map(|sibling| sibling.dynamic_nodes.iter())
+            .filter(|node| matches!(node, DynamicNode::Component(_)))
+            .count();
+
+        if components > 1 {
+            self.scopes.reserve(components);
         }
     }
 
@@ -496,7 +515,7 @@ impl<'b> VirtualDom {
         use RenderReturn::*;
 
         // Load up a ScopeId for this vcomponent
-        let scope = self.load_scope_from_vcomponent(component);
+        let scope = self.load_scope_from_vcomponent(component, template.key);
 
         component.scope.set(Some(scope));
 
@@ -508,13 +527,13 @@ impl<'b> VirtualDom {
     }
 
     /// Load a scope from a vcomponent. If the props don't exist, that means the component is currently "live"
-    fn load_scope_from_vcomponent(&mut self, component: &VComponent) -> ScopeId {
+    fn load_scope_from_vcomponent(&mut self, component: &VComponent, key: Option<&str>) -> ScopeId {
         component
             .props
             .take()
             .map(|props| {
                 let unbounded_props: Box<dyn AnyProps> = unsafe { std::mem::transmute(props) };
-                self.new_scope(unbounded_props, component.name).id
+                self.new_scope(unbounded_props, component.name, key).id
             })
             .unwrap_or_else(|| component.scope.get().unwrap())
     }
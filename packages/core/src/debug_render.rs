@@ -0,0 +1,129 @@
+use crate::{
+    innerlude::{DynamicNode, TemplateAttribute, TemplateNode, VNode},
+    nodes::RenderReturn,
+    AttributeValue, ScopeId, VirtualDom,
+};
+use std::fmt::Write;
+
+impl VirtualDom {
+    /// Render the tree under a scope into an indented, human-readable string.
+    ///
+    /// This is not HTML - it's a dump of the [`VNode`] tree meant for logging and test assertions. Each element,
+    /// text node, and component gets its own line, indented by its depth. Components show up as `component Name`
+    /// with their rendered output nested under them.
+    ///
+    /// Scopes that are waiting on suspense render as `<suspended>` and aborted renders or empty nodes render as
+    /// `<placeholder>`. Returns an empty string if the scope doesn't exist.
+    ///
+    /// ```rust, ignore
+    /// let mut dom = VirtualDom::new(|cx| cx.render(rsx!( div { class: "hello", "world" } )));
+    /// let _ = dom.rebuild();
+    ///
+    /// assert_eq!(dom.debug_render_tree(ScopeId(0)), "<div class=\"hello\">\n  \"world\"\n");
+    /// ```
+    pub fn debug_render_tree(&self, id: ScopeId) -> String {
+        let mut out = String::new();
+        self.debug_render_scope(id, 0, &mut out);
+        out
+    }
+
+    fn debug_render_scope(&self, id: ScopeId, depth: usize, out: &mut String) {
+        let scope = match self.get_scope(id) {
+            Some(scope) => scope,
+            None => return,
+        };
+
+        match scope.try_root_node() {
+            Some(RenderReturn::Ready(node)) => self.debug_render_vnode(node, depth, out),
+            Some(RenderReturn::Aborted(_)) => push_line(out, depth, "<placeholder>"),
+            Some(RenderReturn::Pending(_)) | None => push_line(out, depth, "<suspended>"),
+        }
+    }
+
+    fn debug_render_vnode(&self, vnode: &VNode, depth: usize, out: &mut String) {
+        for root in vnode.template.get().roots {
+            self.debug_render_template_node(vnode, root, depth, out);
+        }
+    }
+
+    fn debug_render_template_node(
+        &self,
+        vnode: &VNode,
+        node: &TemplateNode,
+        depth: usize,
+        out: &mut String,
+    ) {
+        match node {
+            TemplateNode::Element {
+                tag,
+                attrs,
+                children,
+                ..
+            } => {
+                let mut open = format!("<{}", tag);
+
+                for attr in attrs.iter() {
+                    match attr {
+                        TemplateAttribute::Static { name, value, .. } => {
+                            _ = write!(open, " {}={:?}", name, value);
+                        }
+                        TemplateAttribute::Dynamic { id } => {
+                            let attr = &vnode.dynamic_attrs[*id];
+                            _ = write!(open, " {}={}", attr.name, debug_attr_value(&attr.value));
+                        }
+                    }
+                }
+
+                open.push('>');
+                push_line(out, depth, &open);
+
+                for child in children.iter() {
+                    self.debug_render_template_node(vnode, child, depth + 1, out);
+                }
+            }
+            TemplateNode::Text { text } => push_line(out, depth, &format!("{:?}", text)),
+            TemplateNode::Dynamic { id } | TemplateNode::DynamicText { id } => {
+                self.debug_render_dynamic_node(&vnode.dynamic_nodes[*id], depth, out)
+            }
+        }
+    }
+
+    fn debug_render_dynamic_node(&self, node: &DynamicNode, depth: usize, out: &mut String) {
+        match node {
+            DynamicNode::Component(comp) => {
+                push_line(out, depth, &format!("component {}", comp.name));
+
+                if let Some(scope) = comp.scope.get() {
+                    self.debug_render_scope(scope, depth + 1, out);
+                }
+            }
+            DynamicNode::Text(text) => push_line(out, depth, &format!("{:?}", text.value)),
+            DynamicNode::Placeholder(_) => push_line(out, depth, "<placeholder>"),
+            DynamicNode::Fragment(nodes) => {
+                for node in nodes.iter() {
+                    self.debug_render_vnode(node, depth, out);
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn debug_attr_value(value: &AttributeValue) -> String {
+    match value {
+        AttributeValue::Text(text) => format!("{:?}", text),
+        AttributeValue::Float(f) => f.to_string(),
+        AttributeValue::Int(i) => i.to_string(),
+        AttributeValue::Bool(b) => b.to_string(),
+        AttributeValue::Listener(_) => "<listener>".to_string(),
+        AttributeValue::Any(_) => "<any>".to_string(),
+        AttributeValue::None => "<none>".to_string(),
+    }
+}
+
+fn push_line(out: &mut String, depth: usize, contents: &str) {
+    for _ in 0..depth {
+        out.push_str("  ");
+    }
+    out.push_str(contents);
+    out.push('\n');
+}
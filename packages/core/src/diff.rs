@@ -1,7 +1,8 @@
 use crate::{
     any_props::AnyProps,
     arena::ElementId,
-    innerlude::{DirtyScope, VComponent, VPlaceholder, VText},
+    bump_frame::BumpFrame,
+    innerlude::{DiffStrategy, DirtyScope, VComponent, VPlaceholder, VText},
     mutations::Mutation,
     nodes::RenderReturn,
     nodes::{DynamicNode, VNode},
@@ -15,7 +16,11 @@ use DynamicNode::*;
 
 impl<'b> VirtualDom {
     pub(super) fn diff_scope(&mut self, scope: ScopeId) {
+        #[cfg(feature = "profile")]
+        let edits_before = self.mutations.edits.len();
+
         let scope_state = &mut self.scopes[scope.0];
+        let strategy = scope_state.diff_strategy.get();
 
         self.scope_stack.push(scope);
         unsafe {
@@ -33,6 +38,11 @@ impl<'b> VirtualDom {
             use RenderReturn::{Aborted, Pending, Ready};
 
             match (old, new) {
+                // The scope asked for its renders to be swapped out wholesale
+                (Ready(l), Ready(r)) if strategy == DiffStrategy::ReplaceAlways => {
+                    self.replace(l, [r])
+                }
+
                 // Normal pathway
                 (Ready(l), Ready(r)) => self.diff_node(l, r),
 
@@ -59,6 +69,18 @@ impl<'b> VirtualDom {
             };
         }
         self.scope_stack.pop();
+
+        // The old render has been read for the last time, so hooks it borrowed from can go
+        self.drop_retired_hooks(scope);
+
+        // A render that didn't change anything in its subtree could have been skipped with memoization
+        #[cfg(feature = "profile")]
+        if self.mutations.edits.len() == edits_before {
+            let scope_state = &self.scopes[scope.0];
+            scope_state
+                .unchanged_renders
+                .set(scope_state.unchanged_renders.get() + 1);
+        }
     }
 
     fn diff_ok_to_async(&mut self, _new: &'b VNode<'b>, _scope: ScopeId) {
@@ -189,6 +211,12 @@ impl<'b> VirtualDom {
 
         right.scope.set(Some(scope_id));
 
+        // Keyed siblings are only ever matched with the same key, but a list going from unkeyed to keyed is matched by
+        // position, so the scope may need to pick up its new key
+        if self.scopes[scope_id.0].key.as_deref() != right_template.key {
+            self.scopes[scope_id.0].key = right_template.key.map(ToOwned::to_owned);
+        }
+
         // copy out the box for both
         let old = self.scopes[scope_id.0].props.as_ref();
         let new: Box<dyn AnyProps> = right.props.take().unwrap();
@@ -204,6 +232,15 @@ impl<'b> VirtualDom {
         // First, move over the props from the old to the new, dropping old props in the process
         self.scopes[scope_id.0].props = Some(new);
 
+        // Frozen scopes keep the new props but don't render with them until they're unfrozen
+        if self.is_frozen(scope_id) {
+            self.deferred_scopes.insert(DirtyScope {
+                height: self.scopes[scope_id.0].height,
+                id: scope_id,
+            });
+            return;
+        }
+
         // Now run the component and diff it
         self.run_scope(scope_id);
         self.diff_scope(scope_id);
@@ -727,12 +764,14 @@ impl<'b> VirtualDom {
     }
 
     fn create_and_insert_before(&mut self, new: &'b [VNode<'b>], before: &'b VNode<'b>) {
+        self.reserve_sibling_scopes(new);
         let m = self.create_children(new);
         let id = self.find_first_element(before);
         self.mutations.push(Mutation::InsertBefore { id, m })
     }
 
     fn create_and_insert_after(&mut self, new: &'b [VNode<'b>], after: &'b VNode<'b>) {
+        self.reserve_sibling_scopes(new);
         let m = self.create_children(new);
         let id = self.find_last_element(after);
         self.mutations.push(Mutation::InsertAfter { id, m })
@@ -915,7 +954,6 @@ impl<'b> VirtualDom {
                 if let Some(id) = t.id.get() {
                     self.try_reclaim(id);
                 }
-                return;
             }
             _ => todo!(),
         };
@@ -929,9 +967,33 @@ impl<'b> VirtualDom {
 
         *comp.props.borrow_mut() = unsafe { std::mem::transmute(props) };
 
+        // Forget about the scope being frozen so its id doesn't freeze whatever scope reuses it
+        self.frozen_scopes.remove(&scope);
+        self.deferred_scopes.remove(&DirtyScope {
+            height: self.scopes[scope.0].height,
+            id: scope,
+        });
+
+        // Stop its tasks so they're never polled against a scope that's gone
+        let tasks = self.scopes[scope.0].spawned_tasks.take();
+        self.scheduler.cancel_scope(scope, tasks);
+
         // make sure to wipe any of its props and listeners
         self.ensure_drop_safety(scope);
-        self.scopes.remove(scope.0);
+        self.scopes[scope.0].run_unmount_callbacks();
+        self.arena_bytes -= self.scopes[scope.0].arena_bytes.get();
+        self.drop_retired_hooks(scope);
+        let mut scope = self.scopes.remove(scope.0);
+
+        // Hand its frames to the pool so the next scope can reuse them
+        self.frame_pool.recycle(std::mem::replace(
+            &mut scope.node_arena_1,
+            BumpFrame::new(0),
+        ));
+        self.frame_pool.recycle(std::mem::replace(
+            &mut scope.node_arena_2,
+            BumpFrame::new(0),
+        ));
     }
 
     fn find_first_element(&self, node: &'b VNode<'b>) -> ElementId {
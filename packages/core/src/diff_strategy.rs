@@ -0,0 +1,21 @@
+/// How a scope's new render is compared against its last one, set with [`crate::ScopeState::set_diff_strategy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffStrategy {
+    /// Compare the renders node by node and only send what changed to the renderer.
+    ///
+    /// This is the default, and the best choice for almost every component.
+    Diff,
+
+    /// Skip the comparison and replace everything the scope rendered last time with its new render.
+    ///
+    /// This avoids the cost of diffing for content that changes wholesale on every render, like a large table
+    /// that's swapped for a new page of rows. The renderer has to create every node again instead, and any components
+    /// rendered directly by the scope are unmounted and mounted again, losing their state.
+    ReplaceAlways,
+}
+
+impl Default for DiffStrategy {
+    fn default() -> Self {
+        DiffStrategy::Diff
+    }
+}
@@ -2,6 +2,9 @@ use std::hash::Hash;
 
 use crate::ScopeId;
 
+/// A scope waiting to be rendered.
+///
+/// Dirty scopes sort by height first, so the set of dirty scopes always yields the scope closest to the root first.
 #[derive(Debug, Clone, Eq, PartialOrd, Ord)]
 pub struct DirtyScope {
     pub height: u32,
@@ -0,0 +1,25 @@
+/// A snapshot of the size of a [`crate::VirtualDom`], useful for keeping an eye on the health of a running app.
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DomStats {
+    /// The number of mounted components
+    pub scopes: usize,
+
+    /// The number of scopes waiting to be re-rendered
+    pub dirty_scopes: usize,
+
+    /// The number of async components waiting on their future to resolve
+    pub suspended_scopes: usize,
+
+    /// The number of tasks spawned with `cx.spawn` that haven't finished yet
+    pub tasks: usize,
+
+    /// The number of elements the renderer has been told about
+    pub elements: usize,
+
+    /// The number of render frames kept from unmounted scopes for new scopes to reuse
+    pub pooled_frames: usize,
+
+    /// The bytes held by every scope's render frames and hooks
+    pub allocated_bytes: usize,
+}
@@ -2,7 +2,7 @@ use crate::{ScopeId, ScopeState};
 use std::{
     any::{Any, TypeId},
     cell::RefCell,
-    fmt::Debug,
+    fmt::{self, Debug, Display},
 };
 
 /// A boundary that will capture any errors from child components
@@ -32,7 +32,51 @@ impl CapturedError {
     }
 }
 
+/// The error a boundary receives when a component below it panics while rendering
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderPanic {
+    /// The message the component panicked with, or `Box<dyn Any>` if the panic payload wasn't a string
+    pub message: String,
+}
+
+impl RenderPanic {
+    pub(crate) fn from_payload(payload: &(dyn Any + Send)) -> Self {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "Box<dyn Any>".to_string()
+        };
+
+        Self { message }
+    }
+}
+
+/// The error a boundary receives when a component is nested deeper than
+/// [`crate::VirtualDom::with_max_scope_height`] allows. The component isn't rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeTooDeep {
+    /// The name of the component that was too deep
+    pub name: &'static str,
+
+    /// How deep the component was
+    pub height: u32,
+}
+
+impl Display for ScopeTooDeep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Component `{}` is nested {} scopes deep, past the maximum height of the VirtualDom. \
+            It likely renders itself without a base case.",
+            self.name, self.height
+        )
+    }
+}
+
 impl ErrorBoundary {
+    /// Create a new error boundary for the scope that provides it
     pub fn new(id: ScopeId) -> Self {
         Self {
             error: RefCell::new(None),
@@ -44,6 +88,11 @@ impl ErrorBoundary {
     pub fn insert_error(&self, scope: ScopeId, error: Box<dyn Debug + 'static>) {
         self.error.replace(Some(CapturedError { error, scope }));
     }
+
+    /// Take the last error pushed into this Error Boundary, if there is one
+    pub fn take_error(&self) -> Option<CapturedError> {
+        self.error.borrow_mut().take()
+    }
 }
 
 /// A trait to allow results to be thrown upwards to the nearest Error Boundary
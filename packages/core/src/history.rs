@@ -0,0 +1,88 @@
+use crate::{innerlude::Mutations, mutations::Mutation, nodes::Template};
+use bumpalo::Bump;
+use std::collections::VecDeque;
+
+/// A bounded log of the mutations produced by the last few renders.
+///
+/// Renderers that reconnect (like a liveview client) can replay the log to catch up instead of requesting a full
+/// rebuild. Only the most recent `capacity` generations are kept - anything older must be rebuilt from scratch.
+#[derive(Default)]
+pub(crate) struct MutationHistory {
+    capacity: usize,
+    entries: VecDeque<HistoryEntry>,
+}
+
+struct HistoryEntry {
+    generation: usize,
+
+    // The edits in this entry borrow their text from this arena, so the two must live and die together
+    _arena: Bump,
+    templates: Vec<Template<'static>>,
+    edits: Vec<Mutation<'static>>,
+}
+
+impl MutationHistory {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Copy the mutations for a generation into the log, evicting the oldest entry if we're at capacity
+    pub(crate) fn record(&mut self, generation: usize, mutations: &Mutations<'static>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+
+        // The text in the edits points into the scopes' bump arenas which get recycled on the next render, so we
+        // need our own copy
+        let arena = Bump::new();
+        let edits = mutations
+            .edits
+            .iter()
+            .map(|edit| unsafe {
+                std::mem::transmute::<Mutation, Mutation<'static>>(edit.copy_into(&arena))
+            })
+            .collect();
+
+        self.entries.push_back(HistoryEntry {
+            generation,
+            _arena: arena,
+            templates: mutations.templates.clone(),
+            edits,
+        });
+    }
+
+    /// Collect every mutation produced after `generation`, up to and including `current`.
+    ///
+    /// Returns `None` if `generation` is older than the oldest entry we kept.
+    pub(crate) fn since(&self, generation: usize, current: usize) -> Option<Mutations> {
+        if generation > current {
+            return None;
+        }
+
+        let mut out: Mutations<'static> = Mutations::default();
+
+        if generation == current {
+            return Some(out);
+        }
+
+        // We can only replay from the generation just before the first entry we kept
+        let oldest = self.entries.front()?.generation;
+        if generation + 1 < oldest {
+            return None;
+        }
+
+        for entry in self.entries.iter().filter(|e| e.generation > generation) {
+            out.templates.extend(entry.templates.iter().copied());
+            out.edits.extend(entry.edits.iter().cloned());
+        }
+
+        Some(out)
+    }
+}
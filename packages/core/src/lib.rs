@@ -1,27 +1,44 @@
 #![doc = include_str!("../README.md")]
 #![warn(missing_docs)]
 
+mod alloc_stats;
 mod any_props;
 mod arena;
 mod bump_frame;
 mod create;
+mod debug_render;
 mod diff;
+mod diff_strategy;
 mod dirty_scope;
+mod dom_stats;
 mod error_boundary;
 mod events;
 mod fragment;
+mod history;
 mod lazynodes;
 mod mutations;
 mod nodes;
+#[cfg(feature = "serialize")]
+mod persist;
 mod properties;
+mod render_info;
+mod render_snapshot;
+mod render_summary;
 mod scheduler;
 mod scope_arena;
+mod scope_memory;
+mod scope_slab;
+mod scope_tree;
 mod scopes;
+mod text_cache;
 mod virtual_dom;
 
 pub(crate) mod innerlude {
+    pub use crate::alloc_stats::*;
     pub use crate::arena::*;
+    pub use crate::diff_strategy::*;
     pub use crate::dirty_scope::*;
+    pub use crate::dom_stats::*;
     pub use crate::error_boundary::*;
     pub use crate::events::*;
     pub use crate::fragment::*;
@@ -30,7 +47,12 @@ pub(crate) mod innerlude {
     pub use crate::nodes::RenderReturn;
     pub use crate::nodes::*;
     pub use crate::properties::*;
+    pub use crate::render_info::*;
+    pub use crate::render_snapshot::*;
+    pub use crate::render_summary::*;
     pub use crate::scheduler::*;
+    pub use crate::scope_memory::*;
+    pub use crate::scope_tree::*;
     pub use crate::scopes::*;
     pub use crate::virtual_dom::*;
 
@@ -70,12 +92,17 @@ pub(crate) mod innerlude {
 }
 
 pub use crate::innerlude::{
-    fc_to_builder, Attribute, AttributeValue, CapturedError, Component, DynamicNode, Element,
-    ElementId, Event, Fragment, IntoDynNode, LazyNodes, Mutation, Mutations, Properties,
-    RenderReturn, Scope, ScopeId, ScopeState, Scoped, SuspenseContext, TaskId, Template,
-    TemplateAttribute, TemplateNode, VComponent, VNode, VText, VirtualDom,
+    fc_to_builder, AllocatorStats, Attribute, AttributeValue, CapturedError, Change, Component,
+    DiffStrategy, DomStats, DynamicNode, Element, ElementId, ErrorBoundary, Event, Fragment,
+    IntoDynNode, LazyNodes, Mutation, Mutations, Properties, RenderInfo, RenderPanic, RenderReturn,
+    RenderSnapshot, RenderSummary, Scope, ScopeId, ScopeMemory, ScopeSnapshot, ScopeState,
+    ScopeTooDeep, Scoped, SnapshotNode, SuspenseContext, SuspenseId, TaskId, Template,
+    TemplateAttribute, TemplateNode, VComponent, VNode, VText, VersionedScopeId, VirtualDom,
 };
 
+#[cfg(feature = "serialize")]
+pub use crate::persist::ImportStateError;
+
 /// The purpose of this module is to alleviate imports of many common types
 ///
 /// This includes types like [`Scope`], [`Element`], and [`Component`].
@@ -1,3 +1,4 @@
+use bumpalo::Bump;
 use rustc_hash::FxHashSet;
 
 use crate::{arena::ElementId, ScopeId, Template};
@@ -61,7 +62,7 @@ impl<'a> Mutations<'a> {
     derive(serde::Serialize, serde::Deserialize),
     serde(tag = "type")
 )]
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Mutation<'a> {
     /// Add these m children to the target element
     AppendChildren {
@@ -255,3 +256,61 @@ pub enum Mutation<'a> {
         id: ElementId,
     },
 }
+
+impl<'a> Mutation<'a> {
+    /// Copy this mutation, moving any text it references into the given arena
+    pub(crate) fn copy_into<'b>(&self, bump: &'b Bump) -> Mutation<'b> {
+        use Mutation::*;
+        let text = |s: &str| -> &'b str { bump.alloc_str(s) };
+
+        match *self {
+            AppendChildren { id, m } => AppendChildren { id, m },
+            AssignId { path, id } => AssignId { path, id },
+            CreatePlaceholder { id } => CreatePlaceholder { id },
+            CreateTextNode { value, id } => CreateTextNode {
+                value: text(value),
+                id,
+            },
+            HydrateText { path, value, id } => HydrateText {
+                path,
+                value: text(value),
+                id,
+            },
+            LoadTemplate { name, index, id } => LoadTemplate { name, index, id },
+            ReplaceWith { id, m } => ReplaceWith { id, m },
+            ReplacePlaceholder { path, m } => ReplacePlaceholder { path, m },
+            InsertAfter { id, m } => InsertAfter { id, m },
+            InsertBefore { id, m } => InsertBefore { id, m },
+            SetAttribute {
+                name,
+                value,
+                id,
+                ns,
+            } => SetAttribute {
+                name: text(name),
+                value: text(value),
+                id,
+                ns: ns.map(text),
+            },
+            SetBoolAttribute { name, value, id } => SetBoolAttribute {
+                name: text(name),
+                value,
+                id,
+            },
+            SetText { value, id } => SetText {
+                value: text(value),
+                id,
+            },
+            NewEventListener { name, id } => NewEventListener {
+                name: text(name),
+                id,
+            },
+            RemoveEventListener { name, id } => RemoveEventListener {
+                name: text(name),
+                id,
+            },
+            Remove { id } => Remove { id },
+            PushRoot { id } => PushRoot { id },
+        }
+    }
+}
@@ -6,7 +6,7 @@ use bumpalo::Bump;
 use std::{
     any::{Any, TypeId},
     cell::{Cell, RefCell, UnsafeCell},
-    fmt::Arguments,
+    fmt::{Arguments, Debug},
     future::Future,
 };
 
@@ -605,6 +605,30 @@ where
     }
 }
 
+#[doc(hidden)]
+pub struct AsyncResultMarker;
+impl<'a, F, E> ComponentReturn<'a, AsyncResultMarker> for F
+where
+    F: Future<Output = Result<Element<'a>, E>> + 'a,
+    E: Debug + 'static,
+{
+    fn into_return(self, cx: &'a ScopeState) -> RenderReturn<'a> {
+        // A future that fails hands its error to the nearest error boundary and renders like an aborted component
+        let flattened = async move {
+            match self.await {
+                Ok(element) => element,
+                Err(error) => {
+                    log::error!("Async component {} failed: {:?}", cx.name(), error);
+                    cx.throw(error);
+                    None
+                }
+            }
+        };
+
+        ComponentReturn::<'a, AsyncMarker>::into_return(flattened, cx)
+    }
+}
+
 impl<'a> RenderReturn<'a> {
     pub(crate) unsafe fn extend_lifetime_ref<'c>(&self) -> &'c RenderReturn<'c> {
         unsafe { std::mem::transmute(self) }
@@ -0,0 +1,73 @@
+use crate::scopes::ScopeState;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{any::Any, collections::BTreeMap, fmt};
+
+/// The version of the blob written by [`crate::VirtualDom::export_state`].
+///
+/// This is bumped whenever the layout of the blob changes. Blobs from older versions are still imported, but blobs
+/// from newer versions are rejected since we can't know what changed.
+pub(crate) const STATE_VERSION: u32 = 1;
+
+/// The hook state of every scope, keyed by the path of the scope and then the index of the hook
+#[derive(Serialize, Deserialize, Default)]
+pub(crate) struct StateBlob {
+    pub version: u32,
+    pub scopes: BTreeMap<String, BTreeMap<usize, Value>>,
+}
+
+/// Turn the value of a persistent hook back into JSON, without knowing its type
+pub(crate) type HookSerializer = fn(&dyn Any) -> Option<Value>;
+
+pub(crate) fn serialize_hook<State: Serialize + 'static>(hook: &dyn Any) -> Option<Value> {
+    serde_json::to_value(hook.downcast_ref::<State>()?).ok()
+}
+
+/// Build the path that identifies a scope across launches, like `app/TodoList/TodoItem#3`.
+///
+/// Scopes are identified by the names of their components, and the keys of the list items they were rendered in.
+pub(crate) fn state_path(parent: Option<*mut ScopeState>, name: &str, key: Option<&str>) -> String {
+    let mut segments = vec![segment(name, key)];
+
+    let mut cur = parent;
+    while let Some(scope) = cur {
+        let scope = unsafe { &*scope };
+        segments.push(segment(scope.name, scope.key.as_deref()));
+        cur = scope.parent;
+    }
+
+    segments.reverse();
+    segments.join("/")
+}
+
+fn segment(name: &str, key: Option<&str>) -> String {
+    match key {
+        Some(key) => format!("{}#{}", name, key),
+        None => name.to_string(),
+    }
+}
+
+/// Why a blob couldn't be imported with [`crate::VirtualDom::import_state`]
+#[derive(Debug)]
+pub enum ImportStateError {
+    /// The blob isn't one written by [`crate::VirtualDom::export_state`]
+    Malformed(serde_json::Error),
+
+    /// The blob was written by a newer version of dioxus
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for ImportStateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportStateError::Malformed(err) => write!(f, "malformed state blob: {}", err),
+            ImportStateError::UnsupportedVersion(version) => write!(
+                f,
+                "state blob version {} is newer than the supported version {}",
+                version, STATE_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ImportStateError {}
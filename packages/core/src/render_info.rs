@@ -0,0 +1,17 @@
+use crate::ScopeId;
+
+/// The scope that's about to render, handed to the observer set with [`crate::VirtualDom::with_render_observer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderInfo {
+    /// The scope being rendered
+    pub scope: ScopeId,
+
+    /// The name of the component
+    pub name: &'static str,
+
+    /// The type of the component's props, like `my_app::CardProps`
+    pub props_type: &'static str,
+
+    /// How many times the scope has rendered before this render
+    pub render_count: usize,
+}
@@ -0,0 +1,343 @@
+use crate::{
+    debug_render::debug_attr_value,
+    innerlude::{DynamicNode, TemplateAttribute, TemplateNode, VNode},
+    nodes::RenderReturn,
+    AttributeValue, ScopeId, VirtualDom,
+};
+
+/// An owned copy of what a scope rendered, from [`VirtualDom::capture_render`].
+///
+/// Snapshots don't borrow from the VirtualDom, so they can be kept across renders and compared with
+/// [`RenderSnapshot::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RenderSnapshot {
+    /// The root nodes of the render
+    pub roots: Vec<SnapshotNode>,
+}
+
+/// A node in a [`RenderSnapshot`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotNode {
+    /// An element along with its attributes, in the order they were declared
+    Element {
+        /// The tag of the element, like `div`
+        tag: String,
+        /// The name and value of each attribute. Listeners have the value `<listener>`.
+        attributes: Vec<(String, String)>,
+        /// The children of the element
+        children: Vec<SnapshotNode>,
+    },
+
+    /// A text node
+    Text(String),
+
+    /// A component along with what it rendered
+    Component {
+        /// The name of the component
+        name: String,
+        /// The root nodes the component rendered
+        children: Vec<SnapshotNode>,
+    },
+
+    /// An empty node, or a render that was aborted
+    Placeholder,
+
+    /// A component that's waiting on suspense
+    Suspended,
+}
+
+/// A difference between two [`RenderSnapshot`]s, from [`RenderSnapshot::diff`].
+///
+/// Nodes are located by their path from the roots of the snapshot: `[1, 0]` is the first child of the second root.
+/// The children of a component count as its children in the path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// A node only exists in the new snapshot
+    Added {
+        /// Where the node is in the new snapshot
+        path: Vec<usize>,
+        /// The node that was added
+        node: SnapshotNode,
+    },
+
+    /// A node only exists in the old snapshot
+    Removed {
+        /// Where the node was in the old snapshot
+        path: Vec<usize>,
+        /// The node that was removed
+        node: SnapshotNode,
+    },
+
+    /// The contents of a text node changed
+    TextChanged {
+        /// Where the text node is
+        path: Vec<usize>,
+        /// The old text
+        old: String,
+        /// The new text
+        new: String,
+    },
+
+    /// An attribute was added, removed, or changed its value
+    AttributeChanged {
+        /// Where the element is
+        path: Vec<usize>,
+        /// The name of the attribute
+        name: String,
+        /// The old value, or `None` if the attribute was added
+        old: Option<String>,
+        /// The new value, or `None` if the attribute was removed
+        new: Option<String>,
+    },
+}
+
+impl RenderSnapshot {
+    /// List the changes that turn this snapshot into `other`.
+    ///
+    /// Children are compared by position, so inserting a node at the start of a list shows up as every node after it
+    /// changing, plus one added at the end. A node that changed kind (or tag, or component) shows up as a removal
+    /// followed by an addition at the same path.
+    pub fn diff(&self, other: &RenderSnapshot) -> Vec<Change> {
+        let mut changes = Vec::new();
+        diff_children(&self.roots, &other.roots, &mut Vec::new(), &mut changes);
+        changes
+    }
+}
+
+fn diff_children(
+    old: &[SnapshotNode],
+    new: &[SnapshotNode],
+    path: &mut Vec<usize>,
+    changes: &mut Vec<Change>,
+) {
+    for idx in 0..old.len().max(new.len()) {
+        path.push(idx);
+
+        match (old.get(idx), new.get(idx)) {
+            (Some(old), Some(new)) => diff_node(old, new, path, changes),
+            (Some(old), None) => changes.push(Change::Removed {
+                path: path.clone(),
+                node: old.clone(),
+            }),
+            (None, Some(new)) => changes.push(Change::Added {
+                path: path.clone(),
+                node: new.clone(),
+            }),
+            (None, None) => unreachable!(),
+        }
+
+        path.pop();
+    }
+}
+
+fn diff_node(
+    old: &SnapshotNode,
+    new: &SnapshotNode,
+    path: &mut Vec<usize>,
+    changes: &mut Vec<Change>,
+) {
+    use SnapshotNode::*;
+
+    match (old, new) {
+        (
+            Element {
+                tag: old_tag,
+                attributes: old_attrs,
+                children: old_children,
+            },
+            Element {
+                tag: new_tag,
+                attributes: new_attrs,
+                children: new_children,
+            },
+        ) if old_tag == new_tag => {
+            diff_attributes(old_attrs, new_attrs, path, changes);
+            diff_children(old_children, new_children, path, changes);
+        }
+
+        (
+            Component {
+                name: old_name,
+                children: old_children,
+            },
+            Component {
+                name: new_name,
+                children: new_children,
+            },
+        ) if old_name == new_name => diff_children(old_children, new_children, path, changes),
+
+        (Text(old), Text(new)) => {
+            if old != new {
+                changes.push(Change::TextChanged {
+                    path: path.clone(),
+                    old: old.clone(),
+                    new: new.clone(),
+                });
+            }
+        }
+
+        (Placeholder, Placeholder) | (Suspended, Suspended) => {}
+
+        _ => {
+            changes.push(Change::Removed {
+                path: path.clone(),
+                node: old.clone(),
+            });
+            changes.push(Change::Added {
+                path: path.clone(),
+                node: new.clone(),
+            });
+        }
+    }
+}
+
+fn diff_attributes(
+    old: &[(String, String)],
+    new: &[(String, String)],
+    path: &[usize],
+    changes: &mut Vec<Change>,
+) {
+    let find = |attrs: &[(String, String)], name: &str| {
+        attrs
+            .iter()
+            .find(|(attr, _)| attr == name)
+            .map(|(_, value)| value.clone())
+    };
+
+    for (name, old_value) in old {
+        let new_value = find(new, name);
+        if new_value.as_ref() != Some(old_value) {
+            changes.push(Change::AttributeChanged {
+                path: path.to_vec(),
+                name: name.clone(),
+                old: Some(old_value.clone()),
+                new: new_value,
+            });
+        }
+    }
+
+    for (name, new_value) in new {
+        if find(old, name).is_none() {
+            changes.push(Change::AttributeChanged {
+                path: path.to_vec(),
+                name: name.clone(),
+                old: None,
+                new: Some(new_value.clone()),
+            });
+        }
+    }
+}
+
+impl VirtualDom {
+    /// Copy what a scope last rendered, including the output of every component under it, into an owned snapshot.
+    ///
+    /// This is meant for tests: capture a render, change some state, render again, and use [`RenderSnapshot::diff`]
+    /// to check exactly what changed. Returns an empty snapshot if the scope doesn't exist.
+    ///
+    /// ```rust, ignore
+    /// let before = dom.capture_render(ScopeId(0));
+    /// dom.mark_dirty(ScopeId(0));
+    /// let _ = dom.render_immediate();
+    ///
+    /// let changes = before.diff(&dom.capture_render(ScopeId(0)));
+    /// assert!(matches!(changes[..], [Change::TextChanged { .. }]));
+    /// ```
+    pub fn capture_render(&self, id: ScopeId) -> RenderSnapshot {
+        RenderSnapshot {
+            roots: self.capture_scope(id),
+        }
+    }
+
+    fn capture_scope(&self, id: ScopeId) -> Vec<SnapshotNode> {
+        let scope = match self.get_scope(id) {
+            Some(scope) => scope,
+            None => return Vec::new(),
+        };
+
+        match scope.try_root_node() {
+            Some(RenderReturn::Ready(node)) => self.capture_vnode(node),
+            Some(RenderReturn::Aborted(_)) => vec![SnapshotNode::Placeholder],
+            Some(RenderReturn::Pending(_)) | None => vec![SnapshotNode::Suspended],
+        }
+    }
+
+    fn capture_vnode(&self, vnode: &VNode) -> Vec<SnapshotNode> {
+        let mut nodes = Vec::new();
+        for root in vnode.template.get().roots {
+            self.capture_template_node(vnode, root, &mut nodes);
+        }
+        nodes
+    }
+
+    fn capture_template_node(
+        &self,
+        vnode: &VNode,
+        node: &TemplateNode,
+        out: &mut Vec<SnapshotNode>,
+    ) {
+        match node {
+            TemplateNode::Element {
+                tag,
+                attrs,
+                children,
+                ..
+            } => {
+                let attributes = attrs
+                    .iter()
+                    .map(|attr| match attr {
+                        TemplateAttribute::Static { name, value, .. } => {
+                            (name.to_string(), value.to_string())
+                        }
+                        TemplateAttribute::Dynamic { id } => {
+                            let attr = &vnode.dynamic_attrs[*id];
+                            (attr.name.to_string(), snapshot_attr_value(&attr.value))
+                        }
+                    })
+                    .collect();
+
+                let mut captured = Vec::new();
+                for child in children.iter() {
+                    self.capture_template_node(vnode, child, &mut captured);
+                }
+
+                out.push(SnapshotNode::Element {
+                    tag: tag.to_string(),
+                    attributes,
+                    children: captured,
+                });
+            }
+            TemplateNode::Text { text } => out.push(SnapshotNode::Text(text.to_string())),
+            TemplateNode::Dynamic { id } | TemplateNode::DynamicText { id } => {
+                self.capture_dynamic_node(&vnode.dynamic_nodes[*id], out)
+            }
+        }
+    }
+
+    fn capture_dynamic_node(&self, node: &DynamicNode, out: &mut Vec<SnapshotNode>) {
+        match node {
+            DynamicNode::Component(comp) => out.push(SnapshotNode::Component {
+                name: comp.name.to_string(),
+                children: comp
+                    .scope
+                    .get()
+                    .map(|scope| self.capture_scope(scope))
+                    .unwrap_or_default(),
+            }),
+            DynamicNode::Text(text) => out.push(SnapshotNode::Text(text.value.to_string())),
+            DynamicNode::Placeholder(_) => out.push(SnapshotNode::Placeholder),
+            DynamicNode::Fragment(nodes) => {
+                for node in nodes.iter() {
+                    out.extend(self.capture_vnode(node));
+                }
+            }
+        }
+    }
+}
+
+fn snapshot_attr_value(value: &AttributeValue) -> String {
+    match value {
+        // Text is kept as-is instead of quoted like in the debug render
+        AttributeValue::Text(text) => text.to_string(),
+        value => debug_attr_value(value),
+    }
+}
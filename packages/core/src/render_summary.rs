@@ -0,0 +1,17 @@
+use std::time::Duration;
+
+/// What happened during a call to [`crate::VirtualDom::render_dirty_to_completion`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderSummary {
+    /// The number of dirty scopes that were rendered.
+    ///
+    /// Children that re-render because their parent passed them new props are part of their parent's render and
+    /// aren't counted separately.
+    pub scopes_rendered: usize,
+
+    /// The number of async components that suspended during the pass
+    pub suspense_leaves: usize,
+
+    /// How long the pass took. This is always zero on `wasm32`, where there's no clock to measure it with.
+    pub elapsed: Duration,
+}
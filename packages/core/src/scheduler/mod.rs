@@ -3,11 +3,15 @@ use slab::Slab;
 
 mod suspense;
 mod task;
+#[cfg(not(target_arch = "wasm32"))]
+mod timer;
 mod wait;
 mod waker;
 
 pub use suspense::*;
 pub use task::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use timer::Timer;
 pub use waker::ArcWake;
 
 /// The type of message that can be sent to the scheduler.
@@ -25,7 +29,13 @@ pub(crate) enum SchedulerMsg {
     SuspenseNotified(SuspenseId),
 }
 
-use std::{cell::RefCell, rc::Rc, sync::Arc};
+use rustc_hash::FxHashMap;
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    rc::Rc,
+    sync::Arc,
+};
 
 pub(crate) struct Scheduler {
     pub sender: futures_channel::mpsc::UnboundedSender<SchedulerMsg>,
@@ -33,6 +43,10 @@ pub(crate) struct Scheduler {
     /// Tasks created with cx.spawn
     pub tasks: RefCell<Slab<Arc<LocalTask>>>,
 
+    /// The most tasks a scope can have at once, and the tasks of each scope from oldest to newest
+    pub task_limit: Cell<Option<usize>>,
+    pub scope_tasks: RefCell<FxHashMap<ScopeId, VecDeque<TaskId>>>,
+
     /// Async components
     pub leaves: RefCell<Slab<Arc<SuspenseLeaf>>>,
 }
@@ -42,6 +56,8 @@ impl Scheduler {
         Rc::new(Scheduler {
             sender,
             tasks: RefCell::new(Slab::new()),
+            task_limit: Cell::new(None),
+            scope_tasks: RefCell::new(FxHashMap::default()),
             leaves: RefCell::new(Slab::new()),
         })
     }
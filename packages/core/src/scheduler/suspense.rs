@@ -3,14 +3,15 @@ use crate::ElementId;
 use crate::{innerlude::Mutations, Element, ScopeId};
 use std::future::Future;
 use std::sync::Arc;
+use std::time::Instant;
 use std::{
     cell::{Cell, RefCell},
     collections::HashSet,
 };
 
 /// An ID representing an ongoing suspended component
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
-pub(crate) struct SuspenseId(pub usize);
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct SuspenseId(pub usize);
 
 /// A boundary in the VirtualDom that captures all suspended components below it
 pub struct SuspenseContext {
@@ -39,6 +40,8 @@ pub(crate) struct SuspenseLeaf {
     pub(crate) scope_id: ScopeId,
     pub(crate) tx: futures_channel::mpsc::UnboundedSender<SchedulerMsg>,
     pub(crate) notified: Cell<bool>,
+    // When the leaf gives up and renders a placeholder, if the VirtualDom has a suspense timeout
+    pub(crate) deadline: Option<Instant>,
     pub(crate) task: *mut dyn Future<Output = Element<'static>>,
 }
 
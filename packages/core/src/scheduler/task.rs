@@ -43,6 +43,12 @@ impl Scheduler {
             scope,
         }));
 
+        drop(tasks);
+
+        if let Some(limit) = self.task_limit.get() {
+            self.enforce_task_limit(scope, task_id, limit);
+        }
+
         self.sender
             .unbounded_send(SchedulerMsg::TaskNotified(task_id))
             .expect("Scheduler should exist");
@@ -50,12 +56,65 @@ impl Scheduler {
         task_id
     }
 
+    /// Drop the oldest tasks of a scope until it's back under the limit
+    fn enforce_task_limit(&self, scope: ScopeId, task_id: TaskId, limit: usize) {
+        let mut scope_tasks = self.scope_tasks.borrow_mut();
+        let queue = scope_tasks.entry(scope).or_default();
+
+        // Forget tasks that finished or were removed, since their ids may have been handed out again
+        {
+            let tasks = self.tasks.borrow();
+            queue.retain(|id| {
+                *id != task_id && tasks.get(id.0).map_or(false, |task| task.scope == scope)
+            });
+        }
+        queue.push_back(task_id);
+
+        while queue.len() > limit {
+            if let Some(oldest) = queue.pop_front() {
+                log::warn!(
+                    "{:?} has more than {} tasks running, dropping the oldest one",
+                    scope,
+                    limit
+                );
+                self.remove(oldest);
+            }
+        }
+    }
+
     /// Drop the future with the given TaskId
     ///
     /// This does nto abort the task, so you'll want to wrap it in an aborthandle if that's important to you
     pub fn remove(&self, id: TaskId) {
         self.tasks.borrow_mut().remove(id.0);
     }
+
+    /// Drop the futures of every task a scope spawned, because the scope is being unmounted
+    pub(crate) fn cancel_scope(&self, scope: ScopeId, tasks: impl IntoIterator<Item = TaskId>) {
+        let mut all = self.tasks.borrow_mut();
+        for id in tasks {
+            if let Some(task) = all.get(id.0) {
+                // A waker may still be holding onto the task, so drop the future itself instead of waiting for it
+                if task.scope == scope {
+                    let task = all.remove(id.0);
+                    if let Ok(mut future) = task.task.try_borrow_mut() {
+                        *future = Box::pin(async {});
+                    }
+                }
+            }
+        }
+        drop(all);
+
+        self.scope_tasks.borrow_mut().remove(&scope);
+    }
+
+    /// Drop every task's future right away, even if a waker is still holding onto the task itself
+    pub(crate) fn cancel_all(&self) {
+        for task in self.tasks.borrow_mut().drain() {
+            *task.task.borrow_mut() = Box::pin(async {});
+        }
+        self.scope_tasks.borrow_mut().clear();
+    }
 }
 
 impl ArcWake for LocalTask {
@@ -0,0 +1,98 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Condvar, Mutex},
+    task::{Context, Poll, Waker},
+    thread,
+    time::Instant,
+};
+
+/// A future that resolves once its deadline has passed
+///
+/// The core doesn't depend on an async runtime, so the timer sleeps on a background thread and then wakes whichever
+/// executor polled it last. Dropping the timer cancels the thread, so a timer that's replaced before its deadline
+/// doesn't leave a thread sleeping behind it. This means it isn't available on targets without threads, like
+/// `wasm32-unknown-unknown`.
+pub(crate) struct Timer {
+    deadline: Instant,
+    shared: Arc<(Mutex<TimerState>, Condvar)>,
+    started: bool,
+}
+
+#[derive(Default)]
+struct TimerState {
+    waker: Option<Waker>,
+    cancelled: bool,
+}
+
+impl Timer {
+    pub fn new(deadline: Instant) -> Self {
+        Self {
+            deadline,
+            shared: Default::default(),
+            started: false,
+        }
+    }
+
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+}
+
+impl Future for Timer {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+
+        // The timer can be kept across polls from different tasks, so always wake the latest one
+        {
+            let mut state = self.shared.0.lock().unwrap();
+            match &state.waker {
+                Some(waker) if waker.will_wake(cx.waker()) => {}
+                _ => state.waker = Some(cx.waker().clone()),
+            }
+        }
+
+        // Only start one thread per timer, even if it's polled again before the deadline
+        if !self.started {
+            self.started = true;
+
+            let deadline = self.deadline;
+            let shared = self.shared.clone();
+            thread::spawn(move || {
+                let (lock, condvar) = &*shared;
+                let mut state = lock.lock().unwrap();
+
+                loop {
+                    if state.cancelled {
+                        return;
+                    }
+
+                    let now = Instant::now();
+                    if now >= deadline {
+                        break;
+                    }
+
+                    state = condvar.wait_timeout(state, deadline - now).unwrap().0;
+                }
+
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            });
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        let (lock, condvar) = &*self.shared;
+        lock.lock().unwrap().cancelled = true;
+        condvar.notify_one();
+    }
+}
@@ -2,11 +2,12 @@ use futures_util::FutureExt;
 use std::{
     rc::Rc,
     task::{Context, Poll},
+    time::Instant,
 };
 
 use crate::{
     innerlude::{Mutation, Mutations, SuspenseContext},
-    nodes::RenderReturn,
+    nodes::{RenderReturn, VPlaceholder},
     ScopeId, TaskId, VNode, VirtualDom,
 };
 
@@ -31,7 +32,9 @@ impl VirtualDom {
         // If the task completes...
         if task.task.borrow_mut().as_mut().poll(&mut cx).is_ready() {
             // Remove it from the scope so we dont try to double drop it when the scope dropes
-            self.scopes[task.scope.0].spawned_tasks.remove(&id);
+            if let Some(scope) = self.scopes.get(task.scope.0) {
+                scope.spawned_tasks.borrow_mut().remove(&id);
+            }
 
             // Remove it from the scheduler
             tasks.remove(id.0);
@@ -44,14 +47,28 @@ impl VirtualDom {
             .unwrap()
     }
 
+    /// Poll every suspended component that woke up since the last batch
+    ///
+    /// Leaves are polled in the order they suspended rather than the order they woke up in, so boundaries that finish
+    /// in the same batch are always committed in the same order. A leaf that wakes up several times before the batch
+    /// is only polled once.
+    pub(crate) fn poll_notified_leaves(&mut self) {
+        for id in std::mem::take(&mut self.notified_leaves) {
+            self.handle_suspense_wakeup(id);
+        }
+    }
+
     pub(crate) fn handle_suspense_wakeup(&mut self, id: SuspenseId) {
-        let leaf = self
-            .scheduler
-            .leaves
-            .borrow_mut()
-            .get(id.0)
-            .unwrap()
-            .clone();
+        // The leaf may have already resolved or timed out
+        let leaf = match self.scheduler.leaves.borrow().get(id.0) {
+            Some(leaf) => leaf.clone(),
+            None => return,
+        };
+
+        // A wakeup that arrived while the leaf was rendering was already handled by polling it again right away
+        if !leaf.notified.replace(false) {
+            return;
+        }
 
         let scope_id = leaf.scope_id;
 
@@ -67,6 +84,8 @@ impl VirtualDom {
         // we should attach them to that component and then render its children
         // continue rendering the tree until we hit yet another suspended component
         if let Poll::Ready(new_nodes) = as_pinned_mut.poll_unpin(&mut cx) {
+            self.scheduler.leaves.borrow_mut().remove(id.0);
+
             let fiber = self.acquire_suspense_boundary(leaf.scope_id);
 
             let scope = &mut self.scopes[scope_id.0];
@@ -110,4 +129,129 @@ impl VirtualDom {
             }
         }
     }
+
+    /// Get the earliest time a suspended component will time out
+    pub(crate) fn next_suspense_deadline(&self) -> Option<Instant> {
+        self.scheduler
+            .leaves
+            .borrow()
+            .iter()
+            .filter_map(|(_, leaf)| leaf.deadline)
+            .min()
+    }
+
+    /// Replace every suspended component that has timed out with its placeholder
+    pub(crate) fn expire_suspense_leaves(&mut self) {
+        if self.suspense_timeout.is_none() {
+            return;
+        }
+
+        let now = Instant::now();
+        let expired: Vec<_> = self
+            .scheduler
+            .leaves
+            .borrow()
+            .iter()
+            .filter(|(_, leaf)| leaf.deadline.map_or(false, |deadline| deadline <= now))
+            .map(|(id, _)| SuspenseId(id))
+            .collect();
+
+        for id in expired {
+            self.handle_suspense_timeout(id);
+        }
+    }
+
+    fn handle_suspense_timeout(&mut self, id: SuspenseId) {
+        let scope_id = match self.scheduler.leaves.borrow().get(id.0) {
+            Some(leaf) => leaf.scope_id,
+            None => return,
+        };
+
+        if let Some(scope) = self.scopes.get(scope_id.0) {
+            log::warn!(
+                "Suspended component {} in {:?} timed out, rendering a placeholder instead",
+                scope.name,
+                scope_id
+            );
+        }
+
+        self.retire_suspense_leaf(id);
+    }
+
+    /// Give up on a suspended component and drop its future, leaving its placeholder in place of what it would have
+    /// rendered.
+    ///
+    /// Returns `false` if there's no such leaf, because it already resolved, timed out, or was aborted.
+    pub fn abort_suspense(&mut self, id: SuspenseId) -> bool {
+        self.retire_suspense_leaf(id)
+    }
+
+    /// Poll a suspended component one last time, rendering it if it's ready and aborting it if it isn't.
+    ///
+    /// Returns `true` if the component finished rendering. Returns `false` if it had to be aborted like with
+    /// [`VirtualDom::abort_suspense`], or if there's no such leaf.
+    pub fn resolve_suspense(&mut self, id: SuspenseId) -> bool {
+        let leaf = match self.scheduler.leaves.borrow().get(id.0) {
+            Some(leaf) => leaf.clone(),
+            None => return false,
+        };
+
+        self.collected_leaves.retain(|leaf| *leaf != id);
+        self.notified_leaves.remove(&id);
+
+        leaf.notified.set(true);
+        self.handle_suspense_wakeup(id);
+
+        if self.scheduler.leaves.borrow().contains(id.0) {
+            self.abort_suspense(id);
+            return false;
+        }
+
+        true
+    }
+
+    /// Remove a leaf that will never resolve, drop its future, and settle its boundary with the placeholder.
+    ///
+    /// Suspense ids are reused, so the leaf is also purged from the wakeup queues to keep a stale wakeup from
+    /// reaching whichever leaf gets the id next.
+    fn retire_suspense_leaf(&mut self, id: SuspenseId) -> bool {
+        let leaf = match self.scheduler.leaves.borrow_mut().try_remove(id.0) {
+            Some(leaf) => leaf,
+            None => return false,
+        };
+
+        self.collected_leaves.retain(|leaf| *leaf != id);
+        self.notified_leaves.remove(&id);
+
+        // The component may have been unmounted while it was suspended, taking its future with it
+        if self.scopes.get(leaf.scope_id.0).is_none() {
+            return true;
+        }
+
+        // Safety: the leaf was the only thing left that could poll the future, and it's no longer reachable from the
+        // scope's frame once the placeholder takes its place
+        unsafe { std::ptr::drop_in_place(leaf.task) };
+
+        self.settle_with_placeholder(id, leaf.scope_id);
+        true
+    }
+
+    /// Leave the placeholder of a leaf that's been removed in the DOM, and finish its boundary if it was the last leaf
+    fn settle_with_placeholder(&mut self, id: SuspenseId, scope_id: ScopeId) {
+        let fiber = self.acquire_suspense_boundary(scope_id);
+
+        // Leave the placeholder that's already in the DOM, and make sure the next render replaces it
+        let scope = &self.scopes[scope_id.0];
+        let placeholder = VPlaceholder::default();
+        placeholder.id.set(scope.placeholder.get());
+
+        let arena = scope.current_frame();
+        let ret = arena.bump.alloc(RenderReturn::Aborted(placeholder));
+        arena.node.set(ret);
+
+        fiber.waiting_on.borrow_mut().remove(&id);
+        if fiber.waiting_on.borrow().is_empty() {
+            self.finished_fibers.push(fiber.id);
+        }
+    }
 }
@@ -1,20 +1,28 @@
 use crate::{
     any_props::AnyProps,
-    bump_frame::BumpFrame,
     innerlude::DirtyScope,
-    innerlude::{SuspenseId, SuspenseLeaf},
+    innerlude::{ScopeTooDeep, SuspenseId, SuspenseLeaf},
     nodes::RenderReturn,
     scheduler::ArcWake,
     scopes::{ScopeId, ScopeState},
+    text_cache::TextCache,
     virtual_dom::VirtualDom,
 };
 use bumpalo::Bump;
 use futures_util::FutureExt;
+
+#[cfg(feature = "serialize")]
+use crate::persist::state_path;
+#[cfg(feature = "render-observer")]
+use crate::render_info::RenderInfo;
+#[cfg(feature = "serialize")]
+use std::cell::RefCell;
 use std::{
     mem,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::Instant,
 };
 
 impl VirtualDom {
@@ -22,30 +30,62 @@ impl VirtualDom {
         &mut self,
         props: Box<dyn AnyProps<'static>>,
         name: &'static str,
+        key: Option<&str>,
     ) -> &ScopeState {
         let parent = self.acquire_current_scope_raw();
-        let entry = self.scopes.vacant_entry();
         let height = unsafe { parent.map(|f| (*f).height + 1).unwrap_or(0) };
-        let id = ScopeId(entry.key());
+        let id = ScopeId(self.scopes.next_key());
+
+        // Every scope gets a new version so IDs held from a previous occupant of this slot can be told apart
+        let version = self.next_scope_version;
+        self.next_scope_version += 1;
+
+        // Hand over any imported state that belongs to this scope
+        #[cfg(feature = "serialize")]
+        let restored_hooks = if self.restored_state.is_empty() {
+            Default::default()
+        } else {
+            self.restored_state
+                .remove(&state_path(parent, name, key))
+                .unwrap_or_default()
+        };
 
-        entry.insert(Box::new(ScopeState {
+        self.scopes.insert(Box::new(ScopeState {
             parent,
             id,
+            version,
             height,
             name,
+            debug_name: Default::default(),
+            key: key.map(ToOwned::to_owned),
             props: Some(props),
             tasks: self.scheduler.clone(),
             placeholder: Default::default(),
-            node_arena_1: BumpFrame::new(0),
-            node_arena_2: BumpFrame::new(0),
+            diff_strategy: Default::default(),
+            node_arena_1: self.frame_pool.take(self.scope_arena_capacity),
+            node_arena_2: self.frame_pool.take(self.scope_arena_capacity),
             spawned_tasks: Default::default(),
             render_cnt: Default::default(),
+            arena_bytes: Default::default(),
             hook_arena: Default::default(),
             hook_list: Default::default(),
             hook_idx: Default::default(),
+            retired_hooks: None,
+            last_hook_count: Default::default(),
+            mount_callbacks: Default::default(),
+            unmount_callbacks: Default::default(),
+            text_cache: self.text_interning.map(TextCache::new),
+            strict_hooks: self.strict_hooks,
+            hook_types: Default::default(),
             shared_contexts: Default::default(),
             borrowed_props: Default::default(),
             listeners: Default::default(),
+            #[cfg(feature = "profile")]
+            unchanged_renders: Default::default(),
+            #[cfg(feature = "serialize")]
+            persistent_hooks: Default::default(),
+            #[cfg(feature = "serialize")]
+            restored_hooks: RefCell::new(restored_hooks),
         }))
     }
 
@@ -53,7 +93,7 @@ impl VirtualDom {
         self.scope_stack
             .last()
             .copied()
-            .and_then(|id| self.scopes.get_mut(id.0).map(|f| f.as_mut() as *mut _))
+            .and_then(|id| self.scopes.get_mut(id.0).map(|f| f as *mut _))
     }
 
     pub(crate) fn run_scope(&mut self, scope_id: ScopeId) -> &RenderReturn {
@@ -63,24 +103,116 @@ impl VirtualDom {
         self.ensure_drop_safety(scope_id);
 
         let mut new_nodes = unsafe {
-            let scope = self.scopes[scope_id.0].as_mut();
+            let scope = &mut self.scopes[scope_id.0];
 
+            // In low-memory mode, swap in a fresh small frame so whatever the last render grew it to is freed
+            if let Some(capacity) = self.fixed_frame_capacity {
+                scope.previous_frame_mut().bump = Bump::with_capacity(capacity);
+
+                #[cfg(feature = "profile")]
+                {
+                    self.allocator_stats.bump_allocations += 1;
+                }
+            }
             // if this frame hasn't been intialized yet, we can guess the size of the next frame to be more efficient
-            if scope.previous_frame().bump.allocated_bytes() == 0 {
+            else if scope.previous_frame().bump.allocated_bytes() == 0 {
                 scope.previous_frame_mut().bump =
                     Bump::with_capacity(scope.current_frame().bump.allocated_bytes());
+
+                #[cfg(feature = "profile")]
+                {
+                    self.allocator_stats.bump_allocations += 1;
+                }
+            }
+            // If the last few renders only used a sliver of this frame, give back the memory of its largest render
+            else if let Some(capacity) = self
+                .frame_shrinking
+                .and_then(|policy| policy.shrunk_capacity(scope.previous_frame()))
+            {
+                let frame = scope.previous_frame_mut();
+                frame.bump = Bump::with_capacity(capacity);
+                frame.small_renders.set(0);
+
+                #[cfg(feature = "profile")]
+                {
+                    self.allocator_stats.bump_allocations += 1;
+                }
             } else {
                 scope.previous_frame_mut().bump.reset();
+
+                #[cfg(feature = "profile")]
+                {
+                    self.allocator_stats.bump_resets += 1;
+                }
             }
 
             // Make sure to reset the hook counter so we give out hooks in the right order
             scope.hook_idx.set(0);
 
-            // safety: due to how we traverse the tree, we know that the scope is not currently aliased
-            let props: &dyn AnyProps = scope.props.as_ref().unwrap().as_ref();
-            let props: &dyn AnyProps = mem::transmute(props);
+            // Runaway recursion would overflow the stack long before it ran out of memory, so stop it early
+            if scope.height > self.max_scope_height {
+                let error = ScopeTooDeep {
+                    name: scope.name,
+                    height: scope.height,
+                };
+                log::error!("{}", error);
+                scope.throw(error);
+                RenderReturn::default()
+            } else {
+                // safety: due to how we traverse the tree, we know that the scope is not currently aliased
+                let props: &dyn AnyProps = scope.props.as_ref().unwrap().as_ref();
+                let props: &dyn AnyProps = mem::transmute(props);
+
+                #[cfg(feature = "render-observer")]
+                if let Some(observer) = self.render_observer.as_mut() {
+                    observer(&RenderInfo {
+                        scope: scope_id,
+                        name: scope.name,
+                        props_type: props.props_type_name(),
+                        render_count: scope.render_cnt.get(),
+                    });
+                }
+
+                // Running out of room panics inside the allocator, which is caught like any other render panic
+                if let Some(limit) = self.max_frame_bytes {
+                    scope
+                        .previous_frame()
+                        .bump
+                        .set_allocation_limit(Some(limit));
+                }
+
+                let hook_bytes = self
+                    .hook_growth_warning
+                    .map(|_| scope.hook_arena.allocated_bytes());
 
-            props.render(scope).extend_lifetime()
+                let started = self.render_timer.as_ref().map(|_| Instant::now());
+                let nodes = props.render(scope).extend_lifetime();
+
+                if let (Some(timer), Some(started)) = (self.render_timer.as_mut(), started) {
+                    timer(scope_id, started.elapsed());
+                }
+
+                // Hooks are only created on the first render, so growing after that is a sign of a leak
+                if let (Some(threshold), Some(before)) = (self.hook_growth_warning, hook_bytes) {
+                    let grown = scope.hook_arena.allocated_bytes() - before;
+                    if scope.render_cnt.get() > 0 && grown > threshold {
+                        log::warn!(
+                            "The hooks of component `{}` ({:?}) grew by {} bytes in one render. A hook is likely \
+                            creating new state every render instead of reusing its slot.",
+                            scope.name,
+                            scope_id,
+                            grown
+                        );
+                    }
+                }
+
+                // Lift the limit so there's always room to store what the render returned
+                if self.max_frame_bytes.is_some() {
+                    scope.previous_frame().bump.set_allocation_limit(None);
+                }
+
+                nodes
+            }
         };
 
         // immediately resolve futures that can be resolved
@@ -96,6 +228,9 @@ impl VirtualDom {
                 id: suspense_id,
                 tx: self.scheduler.sender.clone(),
                 notified: Default::default(),
+                deadline: self
+                    .suspense_timeout
+                    .map(|timeout| Instant::now() + timeout),
             });
 
             let waker = leaf.waker();
@@ -104,6 +239,8 @@ impl VirtualDom {
             // safety: the task is already pinned in the bump arena
             let mut pinned = unsafe { Pin::new_unchecked(task.as_mut()) };
 
+            let mut repolls = 0;
+
             // Keep polling until either we get a value or the future is not ready
             loop {
                 match pinned.poll_unpin(&mut cx) {
@@ -120,14 +257,26 @@ impl VirtualDom {
                     // If no nodes are produced but the future woke up immediately, then try polling it again
                     // This circumvents things like yield_now, but is important is important when rendering
                     // components that are just a stream of immediately ready futures
-                    _ if leaf.notified.get() => {
+                    _ if leaf.notified.get() && repolls < self.suspense_repoll_limit => {
                         leaf.notified.set(false);
+                        repolls += 1;
+                        self.suspense_repolls += 1;
                         continue;
                     }
 
                     // If no nodes are produced, then we need to wait for the future to be woken up
                     // Insert the future into fiber leaves and break
+                    // The wakeups we already repolled for are skipped when they come through the queue, so only a
+                    // leaf that's still notified gets polled again right away
                     _ => {
+                        if leaf.notified.get() {
+                            log::warn!(
+                                "Suspended component in {:?} woke itself up {} times in a row without finishing, parking it until the next wakeup",
+                                scope_id,
+                                repolls
+                            );
+                        }
+
                         entry.insert(leaf);
                         self.collected_leaves.push(suspense_id);
                         break;
@@ -138,6 +287,11 @@ impl VirtualDom {
 
         let scope = &self.scopes[scope_id.0];
 
+        // Renders that were aborted or suspended stop partway through, so they can't be compared
+        if let RenderReturn::Ready(_) = new_nodes {
+            scope.validate_hook_count();
+        }
+
         // We write on top of the previous frame and then make it the current by pushing the generation forward
         let frame = scope.previous_frame();
 
@@ -145,15 +299,38 @@ impl VirtualDom {
         let allocated = &*frame.bump.alloc(new_nodes);
         frame.node.set(allocated);
 
+        if let Some(policy) = self.frame_shrinking {
+            policy.record(frame);
+        }
+
         // And move the render generation forward by one
         scope.render_cnt.set(scope.render_cnt.get() + 1);
 
+        // Swap this scope's last count for its new size in the running total
+        let arena_bytes =
+            scope.node_arena_1.bump.allocated_bytes() + scope.node_arena_2.bump.allocated_bytes();
+        self.arena_bytes = self.arena_bytes + arena_bytes - scope.arena_bytes.replace(arena_bytes);
+        self.peak_arena_bytes = self.peak_arena_bytes.max(self.arena_bytes);
+
+        // Only the first render can register mount callbacks
+        scope.run_mount_callbacks();
+
+        #[cfg(feature = "profile")]
+        {
+            self.allocator_stats.renders += 1;
+            self.allocator_stats.bytes_allocated += frame.bump.allocated_bytes() as u64;
+        }
+
         // remove this scope from dirty scopes
         self.dirty_scopes.remove(&DirtyScope {
             height: scope.height,
             id: scope.id,
         });
 
+        for listener in self.render_listeners.iter_mut() {
+            listener(scope_id);
+        }
+
         // rebind the lifetime now that its stored internally
         unsafe { allocated.extend_lifetime_ref() }
     }
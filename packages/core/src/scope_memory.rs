@@ -0,0 +1,20 @@
+/// The memory held by a single scope, from [`crate::VirtualDom::scope_memory`]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScopeMemory {
+    /// The bytes held by the frame with the scope's latest render
+    pub current_frame: usize,
+
+    /// The bytes held by the frame with the render before that, which is reused for the next render
+    pub previous_frame: usize,
+
+    /// The bytes held by the scope's hooks
+    pub hooks: usize,
+}
+
+impl ScopeMemory {
+    /// The bytes held by the frames and hooks together
+    pub fn total(&self) -> usize {
+        self.current_frame + self.previous_frame + self.hooks
+    }
+}
@@ -0,0 +1,109 @@
+use crate::scopes::ScopeState;
+use std::ops::{Index, IndexMut};
+
+/// The storage for every scope in a [`crate::VirtualDom`], indexed by the number inside its [`crate::ScopeId`]
+///
+/// This works like a [`slab::Slab`], handing out the slot of the most recently removed scope first. In stable mode,
+/// slots are never reused so scopes get IDs in the order they're created.
+#[derive(Default)]
+pub(crate) struct ScopeSlab {
+    entries: Vec<Option<Box<ScopeState>>>,
+    free: Vec<usize>,
+    len: usize,
+    stable: bool,
+}
+
+impl ScopeSlab {
+    /// Stop reusing the slots of removed scopes
+    pub fn set_stable(&mut self, stable: bool) {
+        self.stable = stable;
+    }
+
+    /// Get the key the next inserted scope will have
+    pub fn next_key(&self) -> usize {
+        match self.free.last() {
+            Some(key) if !self.stable => *key,
+            _ => self.entries.len(),
+        }
+    }
+
+    pub fn insert(&mut self, scope: Box<ScopeState>) -> &mut ScopeState {
+        let key = self.next_key();
+        if key == self.entries.len() {
+            self.entries.push(None);
+        } else {
+            self.free.pop();
+        }
+
+        self.len += 1;
+        self.entries[key].insert(scope)
+    }
+
+    pub fn remove(&mut self, key: usize) -> Box<ScopeState> {
+        let scope = self.entries[key].take().expect("invalid scope key");
+        self.len -= 1;
+
+        if !self.stable {
+            self.free.push(key);
+        }
+
+        scope
+    }
+
+    pub fn get(&self, key: usize) -> Option<&ScopeState> {
+        self.entries.get(key)?.as_deref()
+    }
+
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut ScopeState> {
+        self.entries.get_mut(key)?.as_deref_mut()
+    }
+
+    pub fn contains(&self, key: usize) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Get how many scopes can be stored before the storage has to grow
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity() - self.entries.len() + self.reusable()
+    }
+
+    /// Make room for `additional` more scopes, counting the free slots that will be reused first
+    pub fn reserve(&mut self, additional: usize) {
+        self.entries
+            .reserve(additional.saturating_sub(self.reusable()));
+    }
+
+    /// Get how many slots of removed scopes will be handed out again before new ones are added
+    fn reusable(&self) -> usize {
+        if self.stable {
+            0
+        } else {
+            self.free.len()
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &ScopeState)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(key, scope)| Some((key, scope.as_deref()?)))
+    }
+}
+
+impl Index<usize> for ScopeSlab {
+    type Output = ScopeState;
+
+    fn index(&self, key: usize) -> &Self::Output {
+        self.get(key).expect("invalid scope key")
+    }
+}
+
+impl IndexMut<usize> for ScopeSlab {
+    fn index_mut(&mut self, key: usize) -> &mut Self::Output {
+        self.get_mut(key).expect("invalid scope key")
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{innerlude::ScopeMemory, ScopeId, VirtualDom};
+
+/// A single scope in the flat list from [`VirtualDom::scope_tree_snapshot`]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeSnapshot {
+    /// The id of the scope
+    pub id: ScopeId,
+
+    /// The name of the component, or the name set with [`crate::ScopeState::set_debug_name`]
+    pub name: String,
+
+    /// The scope that rendered this one, or `None` for the root
+    pub parent: Option<ScopeId>,
+
+    /// How deep the scope is in the tree, starting at 0 for the root
+    pub height: u32,
+
+    /// How many times the scope has rendered
+    pub render_count: usize,
+
+    /// The bytes held by the scope's render frames and hooks
+    pub memory: ScopeMemory,
+}
+
+impl VirtualDom {
+    /// Copy the id, name, parent, height, render count, and memory of every mounted scope into a flat list, sorted by
+    /// id.
+    ///
+    /// This is meant for devtools: the list is owned and can be serialized with the `serialize` feature, and the tree
+    /// can be put back together by following each scope's `parent`. It only reads the scopes, so it's safe to call
+    /// between renders.
+    pub fn scope_tree_snapshot(&self) -> Vec<ScopeSnapshot> {
+        self.scopes
+            .iter()
+            .map(|(_, scope)| ScopeSnapshot {
+                id: scope.scope_id(),
+                name: scope.name().into_owned(),
+                parent: scope.parent(),
+                height: scope.height(),
+                render_count: scope.generation(),
+                memory: self.scope_memory(scope.scope_id()).unwrap_or_default(),
+            })
+            .collect()
+    }
+}
@@ -3,16 +3,18 @@ use crate::{
     any_props::VProps,
     arena::ElementId,
     bump_frame::BumpFrame,
+    innerlude::{DiffStrategy, ErrorBoundary, Scheduler, SchedulerMsg},
     innerlude::{DynamicNode, EventHandler, VComponent, VText},
-    innerlude::{ErrorBoundary, Scheduler, SchedulerMsg},
     lazynodes::LazyNodes,
     nodes::{ComponentReturn, IntoAttributeValue, IntoDynNode, RenderReturn},
+    text_cache::TextCache,
     Attribute, AttributeValue, Element, Event, Properties, TaskId,
 };
 use bumpalo::{boxed::Box as BumpBox, Bump};
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::{
-    any::{Any, TypeId},
+    any::{type_name, Any, TypeId},
+    borrow::Cow,
     cell::{Cell, RefCell},
     fmt::{Arguments, Debug},
     future::Future,
@@ -20,6 +22,11 @@ use std::{
     sync::Arc,
 };
 
+#[cfg(feature = "serialize")]
+use crate::persist::{serialize_hook, HookSerializer};
+#[cfg(feature = "serialize")]
+use std::collections::BTreeMap;
+
 /// A wrapper around the [`Scoped`] object that contains a reference to the [`ScopeState`] and properties for a given
 /// component.
 ///
@@ -59,22 +66,42 @@ impl<'a, T> std::ops::Deref for Scoped<'a, T> {
 /// `ScopeId` is a `usize` that acts a key for the internal slab of Scopes. This means that the key is not unqiue across
 /// time. We do try and guarantee that between calls to `wait_for_work`, no ScopeIds will be recycled in order to give
 /// time for any logic that relies on these IDs to properly update.
+///
+/// If you need to hold onto an ID for longer than that, use a [`VersionedScopeId`] instead.
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
 pub struct ScopeId(pub usize);
 
+/// A [`ScopeId`] that remembers which component it was created for.
+///
+/// Once the component is dropped, its slot is recycled for new components. A plain [`ScopeId`] would silently point
+/// to the new component, but a `VersionedScopeId` is rejected by [`VirtualDom::get_scope_versioned`] instead.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct VersionedScopeId {
+    /// The slot of the scope
+    pub id: ScopeId,
+
+    /// Which occupant of the slot this ID belongs to
+    pub version: usize,
+}
+
 /// A component's state separate from its props.
 ///
 /// This struct exists to provide a common interface for all scopes without relying on generics.
 pub struct ScopeState {
     pub(crate) render_cnt: Cell<usize>,
+    // The bytes of this scope's frames counted toward the VirtualDom's total
+    pub(crate) arena_bytes: Cell<usize>,
     pub(crate) name: &'static str,
+    pub(crate) debug_name: RefCell<Option<String>>,
+    pub(crate) key: Option<String>,
 
     pub(crate) node_arena_1: BumpFrame,
     pub(crate) node_arena_2: BumpFrame,
 
     pub(crate) parent: Option<*mut ScopeState>,
     pub(crate) id: ScopeId,
+    pub(crate) version: usize,
 
     pub(crate) height: u32,
 
@@ -82,16 +109,44 @@ pub struct ScopeState {
     pub(crate) hook_list: RefCell<Vec<*mut dyn Any>>,
     pub(crate) hook_idx: Cell<usize>,
 
-    pub(crate) shared_contexts: RefCell<FxHashMap<TypeId, Box<dyn Any>>>,
+    // Hooks from before `reset_scope_hooks`, kept alive until the render that borrowed from them has been diffed
+    pub(crate) retired_hooks: Option<(Bump, Vec<*mut dyn Any>)>,
+
+    // How many hooks the last render that finished used, to catch hooks that are called conditionally
+    pub(crate) last_hook_count: Cell<Option<usize>>,
+
+    // Callbacks registered with `on_mount` and `on_unmount` that haven't run yet
+    pub(crate) mount_callbacks: RefCell<Vec<Box<dyn FnOnce()>>>,
+    pub(crate) unmount_callbacks: RefCell<Vec<Box<dyn FnOnce()>>>,
+
+    // Formatted text that's kept across renders when text interning is on
+    pub(crate) text_cache: Option<TextCache>,
+
+    // Only filled out in strict mode so we can name the types of mismatched hooks
+    pub(crate) strict_hooks: bool,
+    pub(crate) hook_types: RefCell<Vec<(TypeId, &'static str)>>,
+
+    // Contexts are stored with their type name so they can be listed for debugging
+    pub(crate) shared_contexts: RefCell<FxHashMap<TypeId, (&'static str, Box<dyn Any>)>>,
 
     pub(crate) tasks: Rc<Scheduler>,
-    pub(crate) spawned_tasks: FxHashSet<TaskId>,
+    pub(crate) spawned_tasks: RefCell<FxHashSet<TaskId>>,
 
     pub(crate) borrowed_props: RefCell<Vec<*const VComponent<'static>>>,
     pub(crate) listeners: RefCell<Vec<*const Attribute<'static>>>,
 
     pub(crate) props: Option<Box<dyn AnyProps<'static>>>,
     pub(crate) placeholder: Cell<Option<ElementId>>,
+    pub(crate) diff_strategy: Cell<DiffStrategy>,
+
+    #[cfg(feature = "profile")]
+    pub(crate) unchanged_renders: Cell<usize>,
+
+    // The hooks created with `use_persistent_hook`, and the state imported for them that hasn't been claimed yet
+    #[cfg(feature = "serialize")]
+    pub(crate) persistent_hooks: RefCell<Vec<(usize, HookSerializer)>>,
+    #[cfg(feature = "serialize")]
+    pub(crate) restored_hooks: RefCell<BTreeMap<usize, serde_json::Value>>,
 }
 
 impl<'src> ScopeState {
@@ -119,18 +174,80 @@ impl<'src> ScopeState {
         }
     }
 
-    /// Get the name of this component
-    pub fn name(&self) -> &str {
-        self.name
+    /// Get the name of this component, or the name it was given with [`ScopeState::set_debug_name`]
+    pub fn name(&self) -> Cow<'static, str> {
+        match &*self.debug_name.borrow() {
+            Some(debug_name) => Cow::Owned(debug_name.clone()),
+            None => Cow::Borrowed(self.name),
+        }
+    }
+
+    /// Give this scope a name for debugging tools to show instead of the name of its component.
+    ///
+    /// This tells apart the scopes of generic or recursive components, which all share the same component name:
+    ///
+    /// ```rust, ignore
+    /// cx.set_debug_name(format!("TreeNode[path={}]", cx.props.path));
+    /// ```
+    pub fn set_debug_name(&self, name: impl Into<String>) {
+        *self.debug_name.borrow_mut() = Some(name.into());
+    }
+
+    /// Get the key of the list item this component was rendered in, if it has one.
+    ///
+    /// Keyed siblings are matched by key when a list is diffed, so a component keeps its scope (and its state) when
+    /// its list item is reordered. Keys must be unique among siblings - duplicate keys panic in debug builds. If the
+    /// list isn't keyed, this is `None` and siblings are matched by position instead.
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
     }
 
     /// Get the current render since the inception of this component
     ///
-    /// This can be used as a helpful diagnostic when debugging hooks/renders, etc
+    /// This is the number of times the component has rendered, so it can be used to check that a subtree re-rendered
+    /// exactly as many times as expected after a state change. It can also be a helpful diagnostic when debugging
+    /// hooks/renders, etc
+    ///
+    /// ```rust, ignore
+    /// let mut dom = VirtualDom::new(app);
+    /// let _ = dom.rebuild();
+    /// assert_eq!(dom.get_scope(ScopeId(1)).unwrap().generation(), 1);
+    /// ```
     pub fn generation(&self) -> usize {
         self.render_cnt.get()
     }
 
+    /// Get the number of hooks this scope has created.
+    ///
+    /// Hooks are only created the first time they're called, so a component that calls its hooks in the same order
+    /// every render keeps the same count after its first render. A count that grows on a later render usually means a
+    /// hook is being called conditionally.
+    pub fn hook_count(&self) -> usize {
+        self.hook_list.borrow().len()
+    }
+
+    /// Get the number of bytes the arena backing this scope's hooks has allocated, including any space it hasn't used
+    /// yet.
+    pub fn hook_capacity(&self) -> usize {
+        self.hook_arena.allocated_bytes()
+    }
+
+    /// Get the number of bytes of text this scope has interned with [`crate::VirtualDom::with_text_interning`], or 0
+    /// if interning is off.
+    pub fn interned_text_bytes(&self) -> usize {
+        self.text_cache.as_ref().map_or(0, TextCache::bytes)
+    }
+
+    /// Get the number of times this scope re-rendered without producing any mutations in its subtree.
+    ///
+    /// These renders are wasted work - a high count usually means the component should be memoized. Renders that
+    /// only changed a child component aren't counted, since the child needed the new props. This is only available
+    /// with the `profile` feature enabled.
+    #[cfg(feature = "profile")]
+    pub fn unchanged_renders(&self) -> usize {
+        self.unchanged_renders.get()
+    }
+
     /// Get a handle to the currently active bump arena for this Scope
     ///
     /// This is a bump memory allocator. Be careful using this directly since the contents will be wiped on the next render.
@@ -250,7 +367,11 @@ impl<'src> ScopeState {
         self.needs_update_any(self.scope_id());
     }
 
-    /// Get the [`ScopeId`] of a mounted component.
+    /// Mark any scope as dirty, like a sibling or parent that subscribes to some shared state.
+    ///
+    /// The update is sent through the scheduler, so it never interrupts the current render: the target is marked dirty
+    /// the next time the VirtualDom processes its messages, and renders in height order with any other dirty scopes.
+    /// If the target has been unmounted by then, the update is ignored.
     ///
     /// `ScopeId` is not unique for the lifetime of the [`crate::VirtualDom`] - a [`ScopeId`] will be reused if a component is unmounted.
     pub fn needs_update_any(&self, id: ScopeId) {
@@ -260,11 +381,20 @@ impl<'src> ScopeState {
             .expect("Scheduler to exist if scope exists");
     }
 
+    /// Choose how this scope's renders are compared against each other. See [`DiffStrategy`] for the options.
+    ///
+    /// The strategy applies from the next time the scope is diffed, which includes the render that's in progress if
+    /// this is called while rendering.
+    pub fn set_diff_strategy(&self, strategy: DiffStrategy) {
+        self.diff_strategy.set(strategy);
+    }
+
     /// Return any context of type T if it exists on this scope
     pub fn has_context<T: 'static + Clone>(&self) -> Option<T> {
         self.shared_contexts
             .borrow()
             .get(&TypeId::of::<T>())?
+            .1
             .downcast_ref::<T>()
             .cloned()
     }
@@ -281,7 +411,7 @@ impl<'src> ScopeState {
         while let Some(parent_ptr) = search_parent {
             // safety: all parent pointers are valid thanks to the bump arena
             let parent = unsafe { &*parent_ptr };
-            if let Some(shared) = parent.shared_contexts.borrow().get(&TypeId::of::<T>()) {
+            if let Some((_, shared)) = parent.shared_contexts.borrow().get(&TypeId::of::<T>()) {
                 return shared.downcast_ref::<T>().cloned();
             }
             search_parent = parent.parent;
@@ -320,14 +450,38 @@ impl<'src> ScopeState {
 
         self.shared_contexts
             .borrow_mut()
-            .insert(TypeId::of::<T>(), Box::new(value));
+            .insert(TypeId::of::<T>(), (type_name::<T>(), Box::new(value)));
 
         value2
     }
 
+    /// Stop providing the context of type `T` from this scope, returning it if it was provided.
+    ///
+    /// Descendants that consume `T` after this find it further up the tree instead. Ones that already consumed it keep
+    /// their copy until they consume it again, so mark them dirty if they need to see the change right away.
+    pub fn remove_context<T: 'static + Clone>(&self) -> Option<T> {
+        let (_, value) = self
+            .shared_contexts
+            .borrow_mut()
+            .remove(&TypeId::of::<T>())?;
+
+        value.downcast::<T>().ok().map(|value| *value)
+    }
+
     /// Pushes the future onto the poll queue to be polled after the component renders.
     pub fn push_future(&self, fut: impl Future<Output = ()> + 'static) -> TaskId {
-        self.tasks.spawn(self.id, fut)
+        let id = self.tasks.spawn(self.id, fut);
+        self.spawned_tasks.borrow_mut().insert(id);
+        id
+    }
+
+    /// Get the ids of the tasks this scope spawned that are still running, oldest first.
+    ///
+    /// Pass them to [`crate::VirtualDom::wait_for_task`] to wait for an effect to finish before checking its result.
+    pub fn spawned_task_ids(&self) -> Vec<TaskId> {
+        let mut ids: Vec<_> = self.spawned_tasks.borrow().iter().copied().collect();
+        ids.sort_unstable_by_key(|id| id.0);
+        ids
     }
 
     /// Spawns the future but does not return the [`TaskId`]
@@ -355,6 +509,7 @@ impl<'src> ScopeState {
     ///
     /// This drops the task immediately.
     pub fn remove_future(&self, id: TaskId) {
+        self.spawned_tasks.borrow_mut().remove(&id);
         self.tasks.remove(id);
     }
 
@@ -406,6 +561,10 @@ impl<'src> ScopeState {
     /// Uses the currently active [`Bump`] allocator
     pub fn raw_text(&'src self, args: Arguments) -> &'src str {
         args.as_str().unwrap_or_else(|| {
+            if let Some(cache) = &self.text_cache {
+                return cache.intern(args, self.bump());
+            }
+
             use bumpalo::core_alloc::fmt::Write;
             let mut str_buf = bumpalo::collections::String::new_in(self.bump());
             str_buf.write_fmt(args).unwrap();
@@ -509,6 +668,47 @@ impl<'src> ScopeState {
         AttributeValue::Listener(RefCell::new(Some(boxed)))
     }
 
+    /// Run `callback` once this scope's first render finishes.
+    ///
+    /// Like hooks, only the callbacks registered during the first render are kept, so it's fine to call this on every
+    /// render. Use [`ScopeState::on_unmount`] to clean up whatever the callback sets up.
+    ///
+    /// ```rust, ignore
+    /// fn Feed(cx: Scope) -> Element {
+    ///     cx.on_mount(|| println!("subscribing"));
+    ///     cx.on_unmount(|| println!("unsubscribing"));
+    ///     render!(div {})
+    /// }
+    /// ```
+    pub fn on_mount(&self, callback: impl FnOnce() + 'static) {
+        if self.generation() == 0 {
+            self.mount_callbacks.borrow_mut().push(Box::new(callback));
+        }
+    }
+
+    /// Run `callback` once when this scope is torn down, either because its component was removed from the tree or
+    /// because the [`crate::VirtualDom`] was dropped.
+    ///
+    /// Callbacks run before the scope's hooks are dropped, and a scope's children are torn down before it. Like hooks,
+    /// only the callbacks registered during the first render are kept, so it's fine to call this on every render.
+    pub fn on_unmount(&self, callback: impl FnOnce() + 'static) {
+        if self.generation() == 0 {
+            self.unmount_callbacks.borrow_mut().push(Box::new(callback));
+        }
+    }
+
+    pub(crate) fn run_mount_callbacks(&self) {
+        for callback in self.mount_callbacks.take() {
+            callback();
+        }
+    }
+
+    pub(crate) fn run_unmount_callbacks(&self) {
+        for callback in self.unmount_callbacks.take() {
+            callback();
+        }
+    }
+
     /// Inject an error into the nearest error boundary and quit rendering
     ///
     /// The error doesn't need to implement Error or any specific traits since the boundary
@@ -545,6 +745,14 @@ impl<'src> ScopeState {
 
         if cur_hook >= hook_list.len() {
             hook_list.push(self.hook_arena.alloc(initializer()));
+
+            if self.strict_hooks {
+                self.hook_types
+                    .borrow_mut()
+                    .push((TypeId::of::<State>(), type_name::<State>()));
+            }
+        } else if self.strict_hooks {
+            self.validate_hook_type::<State>(cur_hook);
         }
 
         hook_list
@@ -564,4 +772,95 @@ impl<'src> ScopeState {
                 "###,
             )
     }
+
+    /// Store a value between renders like [`ScopeState::use_hook`], and include it in [`crate::VirtualDom::export_state`].
+    ///
+    /// If state was imported with [`crate::VirtualDom::import_state`] for this hook, it's used instead of running the
+    /// initializer. Imported state that no longer deserializes into `State` (because the type changed between
+    /// versions of the app, for example) is ignored and the initializer runs as usual.
+    ///
+    /// This is only available with the `serialize` feature enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust, ignore
+    /// fn Editor(cx: Scope) -> Element {
+    ///     let draft = cx.use_persistent_hook(String::new);
+    ///     // ...
+    /// }
+    /// ```
+    #[cfg(feature = "serialize")]
+    #[allow(clippy::mut_from_ref)]
+    pub fn use_persistent_hook<State>(&self, initializer: impl FnOnce() -> State) -> &mut State
+    where
+        State: serde::Serialize + serde::de::DeserializeOwned + 'static,
+    {
+        let idx = self.hook_idx.get();
+
+        self.use_hook(|| {
+            self.persistent_hooks
+                .borrow_mut()
+                .push((idx, serialize_hook::<State>));
+
+            self.restored_hooks
+                .borrow_mut()
+                .remove(&idx)
+                .and_then(|value| serde_json::from_value(value).ok())
+                .unwrap_or_else(initializer)
+        })
+    }
+
+    /// Serialize every hook created with [`ScopeState::use_persistent_hook`], keyed by the index of the hook
+    #[cfg(feature = "serialize")]
+    pub(crate) fn export_hooks(&self) -> BTreeMap<usize, serde_json::Value> {
+        let hook_list = self.hook_list.borrow();
+
+        self.persistent_hooks
+            .borrow()
+            .iter()
+            .filter_map(|(idx, serialize)| {
+                let hook = unsafe { &**hook_list.get(*idx)? };
+                Some((*idx, serialize(hook)?))
+            })
+            .collect()
+    }
+
+    /// Make sure the hook at this index has the same type it had when it was first initialized
+    /// Check that a render that just finished used as many hooks as the last one
+    ///
+    /// In strict mode this panics, otherwise the mismatch is logged since the hooks that were skipped are still valid.
+    pub(crate) fn validate_hook_count(&self) {
+        let count = self.hook_idx.get();
+        let expected = match self.last_hook_count.replace(Some(count)) {
+            Some(expected) if expected != count => expected,
+            _ => return,
+        };
+
+        let message = format!(
+            "Component `{}` ({:?}) used {} hooks in this render but {} in the last render.\n\
+            Hooks must be called in the same order on every render - you likely called a hook conditionally.",
+            self.name, self.id, count, expected
+        );
+
+        if self.strict_hooks {
+            panic!("{}", message);
+        }
+
+        log::error!("{}", message);
+    }
+
+    fn validate_hook_type<State: 'static>(&self, idx: usize) {
+        if let Some((id, name)) = self.hook_types.borrow().get(idx) {
+            if *id != TypeId::of::<State>() {
+                panic!(
+                    "Hook {} in component `{}` changed type between renders: it was `{}` but is now `{}`.\n\
+                    Hooks must be called in the same order on every render - you likely called a hook conditionally.",
+                    idx,
+                    self.name,
+                    name,
+                    type_name::<State>()
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,68 @@
+use bumpalo::Bump;
+use rustc_hash::FxHashSet;
+use std::{
+    cell::{Cell, RefCell},
+    fmt::{Arguments, Write},
+};
+
+/// Formatted text a scope has rendered before, stored once in an arena that lives as long as the scope.
+///
+/// Render frames are reset every other render, so text that comes out the same every time is normally copied into the
+/// frame again on every render. With [`crate::VirtualDom::with_text_interning`], text is formatted into a scratch
+/// buffer first and only copied the first time it's seen. Nothing is ever removed from the cache, so once it holds
+/// `limit` bytes, new text goes into the frame like it would without a cache.
+pub(crate) struct TextCache {
+    // Every string in the set points into `arena`, which is declared after it so it's dropped after it too
+    interned: RefCell<FxHashSet<&'static str>>,
+    arena: Bump,
+    scratch: RefCell<String>,
+    bytes: Cell<usize>,
+    limit: usize,
+}
+
+impl TextCache {
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            interned: Default::default(),
+            arena: Bump::new(),
+            scratch: Default::default(),
+            bytes: Cell::new(0),
+            limit,
+        }
+    }
+
+    /// Format `args` and find the same text in the cache, adding it if there's room.
+    ///
+    /// Returns the formatted text in `frame` if the cache is full, or if formatting the text rendered more text in the
+    /// same scope.
+    pub(crate) fn intern<'a>(&'a self, args: Arguments, frame: &'a Bump) -> &'a str {
+        let mut scratch = match self.scratch.try_borrow_mut() {
+            Ok(scratch) => scratch,
+            Err(_) => return bumpalo::format!(in frame, "{}", args).into_bump_str(),
+        };
+        scratch.clear();
+        scratch.write_fmt(args).unwrap();
+
+        let mut interned = self.interned.borrow_mut();
+        if let Some(text) = interned.get(scratch.as_str()) {
+            return *text;
+        }
+
+        if self.bytes.get() + scratch.len() > self.limit {
+            return frame.alloc_str(&scratch);
+        }
+
+        let text = self.arena.alloc_str(&scratch);
+        self.bytes.set(self.bytes.get() + text.len());
+
+        // The arena lives as long as the cache, so the text outlives anything that borrowed it from the cache
+        let text: &'static str = unsafe { std::mem::transmute::<&str, &'static str>(text) };
+        interned.insert(text);
+        text
+    }
+
+    /// The number of bytes of text in the cache
+    pub(crate) fn bytes(&self) -> usize {
+        self.bytes.get()
+    }
+}
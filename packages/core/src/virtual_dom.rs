@@ -5,18 +5,50 @@
 use crate::{
     any_props::VProps,
     arena::{ElementId, ElementRef},
-    innerlude::{DirtyScope, ErrorBoundary, Mutations, Scheduler, SchedulerMsg},
+    bump_frame::{BumpFrame, FramePool, FrameShrinking},
+    history::MutationHistory,
+    innerlude::{
+        DirtyScope, DomStats, ErrorBoundary, Mutations, RenderSummary, Scheduler, SchedulerMsg,
+        ScopeMemory,
+    },
     mutations::Mutation,
     nodes::RenderReturn,
     nodes::{Template, TemplateId},
     scheduler::SuspenseId,
-    scopes::{ScopeId, ScopeState},
-    AttributeValue, Element, Event, Scope, SuspenseContext,
+    scope_slab::ScopeSlab,
+    scopes::{ScopeId, ScopeState, VersionedScopeId},
+    text_cache::TextCache,
+    AttributeValue, Element, Event, Scope, SuspenseContext, TaskId,
 };
 use futures_util::{pin_mut, StreamExt};
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use slab::Slab;
-use std::{any::Any, borrow::BorrowMut, cell::Cell, collections::BTreeSet, future::Future, rc::Rc};
+use std::{
+    any::{Any, TypeId},
+    borrow::BorrowMut,
+    cell::Cell,
+    collections::BTreeSet,
+    future::Future,
+    rc::Rc,
+    time::Duration,
+};
+
+#[cfg(feature = "profile")]
+use crate::alloc_stats::AllocatorStats;
+#[cfg(feature = "serialize")]
+use crate::persist::{state_path, ImportStateError, StateBlob, STATE_VERSION};
+#[cfg(feature = "render-observer")]
+use crate::render_info::RenderInfo;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::scheduler::Timer;
+#[cfg(feature = "serialize")]
+use std::collections::BTreeMap;
+
+/// How many times a suspended component can wake itself up during a render before it's parked
+const DEFAULT_SUSPENSE_REPOLL_LIMIT: usize = 256;
+
+/// How deep a scope can be before it's treated as runaway recursion
+const DEFAULT_MAX_SCOPE_HEIGHT: u32 = 10_000;
 
 /// A virtual node system that progresses user events and diffs UI trees.
 ///
@@ -144,7 +176,7 @@ use std::{any::Any, borrow::BorrowMut, cell::Cell, collections::BTreeSet, future
 pub struct VirtualDom {
     // Maps a template path to a map of byteindexes to templates
     pub(crate) templates: FxHashMap<TemplateId, FxHashMap<usize, Template<'static>>>,
-    pub(crate) scopes: Slab<Box<ScopeState>>,
+    pub(crate) scopes: ScopeSlab,
     pub(crate) dirty_scopes: BTreeSet<DirtyScope>,
     pub(crate) scheduler: Rc<Scheduler>,
 
@@ -155,6 +187,9 @@ pub struct VirtualDom {
     pub(crate) scope_stack: Vec<ScopeId>,
     pub(crate) collected_leaves: Vec<SuspenseId>,
 
+    // Suspended components that woke up and are waiting to be polled in the next batch
+    pub(crate) notified_leaves: BTreeSet<SuspenseId>,
+
     // Whenever a suspense tree is finished, we push its boundary onto this stack.
     // When "render_with_deadline" is called, we pop the stack and return the mutations
     pub(crate) finished_fibers: Vec<ScopeId>,
@@ -162,6 +197,81 @@ pub struct VirtualDom {
     pub(crate) rx: futures_channel::mpsc::UnboundedReceiver<SchedulerMsg>,
 
     pub(crate) mutations: Mutations<'static>,
+
+    // Bumped every time a set of mutations is handed out to the renderer
+    pub(crate) mutation_generation: usize,
+    pub(crate) mutation_history: MutationHistory,
+
+    pub(crate) strict_hooks: bool,
+
+    pub(crate) next_scope_version: usize,
+
+    // How long a suspended component can stay pending before it's replaced with a placeholder
+    pub(crate) suspense_timeout: Option<Duration>,
+
+    // Wakes `wait_for_work` at the next suspense deadline. It's kept across calls so every message that arrives
+    // before the deadline doesn't start another timer thread.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) suspense_timer: Option<Timer>,
+
+    // How many times a suspended component can wake itself up during a render before it's parked
+    pub(crate) suspense_repoll_limit: usize,
+
+    // How many times suspended components were polled again right away, for diagnostics
+    pub(crate) suspense_repolls: usize,
+
+    // Scopes deeper than this aren't rendered, to catch runaway recursion
+    pub(crate) max_scope_height: u32,
+
+    // The capacity the frames of new scopes start with
+    pub(crate) scope_arena_capacity: usize,
+
+    // Frames from unmounted scopes that new scopes reuse before allocating their own
+    pub(crate) frame_pool: FramePool,
+
+    // When frames that stay mostly empty are replaced with smaller ones
+    pub(crate) frame_shrinking: Option<FrameShrinking>,
+
+    // The capacity every frame is reset to in low-memory mode
+    pub(crate) fixed_frame_capacity: Option<usize>,
+    pub(crate) max_frame_bytes: Option<usize>,
+    pub(crate) hook_growth_warning: Option<usize>,
+
+    // How many bytes of formatted text each new scope can keep across renders
+    pub(crate) text_interning: Option<usize>,
+
+    // How many times a scope can render in one pass before it's reported, whether to panic instead of logging, and
+    // how many times each scope has rendered in the current pass
+    pub(crate) render_loop_limit: Option<(usize, bool)>,
+    pub(crate) pass_renders: FxHashMap<ScopeId, usize>,
+
+    // Set once `shutdown` has torn down the tree so it isn't torn down again on drop
+    pub(crate) shut_down: bool,
+
+    // Roots of subtrees that shouldn't re-render, and the scopes under them that were dirtied in the meantime
+    pub(crate) frozen_scopes: FxHashSet<ScopeId>,
+    pub(crate) deferred_scopes: BTreeSet<DirtyScope>,
+
+    // The bytes held by the render frames of every scope as of its last render, and the most that's ever been
+    pub(crate) arena_bytes: usize,
+    pub(crate) peak_arena_bytes: usize,
+
+    #[cfg(feature = "profile")]
+    pub(crate) allocator_stats: AllocatorStats,
+
+    // State imported with `import_state`, waiting for its scope to be created
+    #[cfg(feature = "serialize")]
+    pub(crate) restored_state: FxHashMap<String, BTreeMap<usize, serde_json::Value>>,
+
+    #[cfg(feature = "render-observer")]
+    pub(crate) render_observer: Option<Box<dyn FnMut(&RenderInfo)>>,
+
+    pub(crate) render_timer: Option<Box<dyn FnMut(ScopeId, Duration)>>,
+
+    pub(crate) render_listeners: Vec<Box<dyn FnMut(ScopeId)>>,
+
+    // The scope created by `render_scope_once`, which isn't part of the tree so it has to be dropped by hand
+    pub(crate) detached_scope: Option<ScopeId>,
 }
 
 impl VirtualDom {
@@ -225,18 +335,53 @@ impl VirtualDom {
             rx,
             scheduler: Scheduler::new(tx),
             templates: Default::default(),
-            scopes: Slab::default(),
+            scopes: ScopeSlab::default(),
             elements: Default::default(),
             scope_stack: Vec::new(),
             dirty_scopes: BTreeSet::new(),
             collected_leaves: Vec::new(),
+            notified_leaves: BTreeSet::new(),
             finished_fibers: Vec::new(),
             mutations: Mutations::default(),
+            mutation_generation: 0,
+            mutation_history: MutationHistory::default(),
+            strict_hooks: false,
+            next_scope_version: 0,
+            suspense_timeout: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            suspense_timer: None,
+            suspense_repoll_limit: DEFAULT_SUSPENSE_REPOLL_LIMIT,
+            suspense_repolls: 0,
+            max_scope_height: DEFAULT_MAX_SCOPE_HEIGHT,
+            scope_arena_capacity: 0,
+            frame_pool: FramePool::default(),
+            frame_shrinking: None,
+            fixed_frame_capacity: None,
+            max_frame_bytes: None,
+            hook_growth_warning: None,
+            text_interning: None,
+            render_loop_limit: None,
+            pass_renders: FxHashMap::default(),
+            shut_down: false,
+            frozen_scopes: FxHashSet::default(),
+            deferred_scopes: BTreeSet::new(),
+            arena_bytes: 0,
+            peak_arena_bytes: 0,
+            #[cfg(feature = "profile")]
+            allocator_stats: AllocatorStats::default(),
+            #[cfg(feature = "serialize")]
+            restored_state: FxHashMap::default(),
+            #[cfg(feature = "render-observer")]
+            render_observer: None,
+            render_timer: None,
+            render_listeners: Vec::new(),
+            detached_scope: None,
         };
 
         let root = dom.new_scope(
             Box::new(VProps::new(root, |_, _| unreachable!(), root_props)),
             "app",
+            None,
         );
 
         // The root component is always a suspense boundary for any async children
@@ -258,7 +403,58 @@ impl VirtualDom {
     ///
     /// This is useful for inserting or removing contexts from a scope, or rendering out its root node
     pub fn get_scope(&self, id: ScopeId) -> Option<&ScopeState> {
-        self.scopes.get(id.0).map(|f| f.as_ref())
+        self.scopes.get(id.0)
+    }
+
+    /// Get an ID for a live scope that won't alias whatever component reuses its slot after it's dropped
+    pub fn versioned_scope_id(&self, id: ScopeId) -> Option<VersionedScopeId> {
+        let scope = self.scopes.get(id.0)?;
+        Some(VersionedScopeId {
+            id,
+            version: scope.version,
+        })
+    }
+
+    /// Get the state for a scope, as long as it's still the same scope the ID was created for.
+    ///
+    /// Returns `None` if the scope was dropped, even if a new scope has taken over its [`ScopeId`].
+    pub fn get_scope_versioned(&self, id: VersionedScopeId) -> Option<&ScopeState> {
+        self.get_scope(id.id)
+            .filter(|scope| scope.version == id.version)
+    }
+
+    /// Get every scope at a specific depth of the tree, where the root scope has a height of `0`.
+    ///
+    /// This scans every scope, so it's O(n) in the number of scopes. The scopes are returned in the order of their IDs.
+    pub fn scopes_at_height(&self, height: u32) -> Vec<ScopeId> {
+        self.scopes
+            .iter()
+            .filter(|(_, scope)| scope.height == height)
+            .map(|(_, scope)| scope.id)
+            .collect()
+    }
+
+    /// Walk up the tree from a scope, yielding its parent, then its grandparent, and so on up to the root scope.
+    ///
+    /// The scope itself isn't included. The iterator is empty for the root scope, or a scope that doesn't exist.
+    pub fn ancestors(&self, id: ScopeId) -> impl Iterator<Item = ScopeId> + '_ {
+        let parent = self.get_scope(id).and_then(|scope| scope.parent());
+        std::iter::successors(parent, move |id| {
+            self.get_scope(*id).and_then(|scope| scope.parent())
+        })
+    }
+
+    /// Make room for at least `additional` more scopes, so mounting a large list doesn't have to grow the scope storage
+    /// several times over in the middle of a render.
+    ///
+    /// This pairs with [`VirtualDom::with_scope_arena_capacity`], which does the same for the frames of each scope.
+    pub fn reserve_scopes(&mut self, additional: usize) {
+        self.scopes.reserve(additional);
+    }
+
+    /// Get how many more scopes can be mounted before the scope storage has to grow
+    pub fn scope_capacity(&self) -> usize {
+        self.scopes.capacity()
     }
 
     /// Get the single scope at the top of the VirtualDom tree that will always be around
@@ -272,20 +468,351 @@ impl VirtualDom {
     ///
     /// This is useful for what is essentially dependency injection when building the app
     pub fn with_root_context<T: Clone + 'static>(self, context: T) -> Self {
+        self.provide_root_context(context);
+        self
+    }
+
+    /// Provide a context from the root scope, so every scope in the tree can consume it.
+    ///
+    /// This replaces any context of the same type the root already provides. Scopes that already consumed the old
+    /// value keep it until they consume the context again.
+    pub fn provide_root_context<T: Clone + 'static>(&self, context: T) {
         self.base_scope().provide_context(context);
+    }
+
+    /// Keep the mutations from the last `capacity` renders around so renderers can catch up with
+    /// [`VirtualDom::take_mutations_since`] instead of rebuilding from scratch.
+    ///
+    /// Every edit's text is copied into the history, so this costs some memory per render. By default, no history is
+    /// kept.
+    pub fn with_mutation_history(mut self, capacity: usize) -> Self {
+        self.mutation_history = MutationHistory::new(capacity);
+        self
+    }
+
+    /// Give up on any suspended component that's still pending `timeout` after it rendered, and leave its placeholder
+    /// in place instead.
+    ///
+    /// Once a component times out, its future is never polled again. The suspense boundary above it is finished as
+    /// soon as every other component it's waiting on resolves or times out too. Re-rendering the component starts it
+    /// over with a new timeout.
+    ///
+    /// The timeout is tracked on a background thread while [`VirtualDom::wait_for_work`] is waiting, so this isn't
+    /// supported on targets without threads like `wasm32-unknown-unknown`, where it's ignored. By default, components
+    /// can stay suspended forever.
+    pub fn with_suspense_timeout(mut self, timeout: Duration) -> Self {
+        // Without a timeout, no deadlines are ever set, so nothing reaches for the clock or the timer thread
+        if cfg!(not(target_arch = "wasm32")) {
+            self.suspense_timeout = Some(timeout);
+        }
+        self
+    }
+
+    /// Set how many times in a row a suspended component can be polled again during a render because it woke itself up.
+    ///
+    /// Components that are just a stream of immediately ready futures are polled until they finish, so they don't have
+    /// to wait for the scheduler. A future that wakes itself every time it's polled but never finishes would spin
+    /// forever, so once it's been polled `limit` extra times it's parked like any other pending component and a
+    /// warning is logged with its scope. Defaults to 256.
+    ///
+    /// A lower limit keeps the event loop responsive at the cost of resolving those components later. See
+    /// [`VirtualDom::suspense_repolls`] for how often the extra polls happen.
+    pub fn with_suspense_repoll_limit(mut self, limit: usize) -> Self {
+        self.suspense_repoll_limit = limit;
+        self
+    }
+
+    /// Start the render frames of every new scope with `capacity` bytes instead of empty.
+    ///
+    /// Frames grow as a scope renders into them, so the first render of a large component reallocates its frame
+    /// several times over. If you know roughly how much a typical component renders on first paint, setting this
+    /// avoids those reallocations at the cost of holding `capacity` bytes per frame in every scope, even small ones.
+    ///
+    /// Defaults to 0.
+    pub fn with_scope_arena_capacity(mut self, capacity: usize) -> Self {
+        self.scope_arena_capacity = capacity;
+
+        // The root scope is created before any builder methods run
+        let root = &mut self.scopes[0];
+        root.node_arena_1 = BumpFrame::new(capacity);
+        root.node_arena_2 = BumpFrame::new(capacity);
+
+        self
+    }
+
+    /// Reset every scope's render frame to `capacity` bytes before it renders, instead of reusing the memory it grew
+    /// to on earlier renders.
+    ///
+    /// By default, a frame keeps its memory between renders so a component that renders the same amount of nodes
+    /// every time never has to go back to the allocator. That means every scope holds onto the memory of its largest
+    /// render for as long as it's mounted. In low-memory mode, frames that outgrow `capacity` are allocated again on
+    /// every render, which trades some render time for a much lower memory ceiling.
+    ///
+    /// The `frame_capacity` benchmark in the `dioxus` crate compares the two.
+    pub fn with_fixed_frame_capacity(mut self, capacity: usize) -> Self {
+        self.fixed_frame_capacity = Some(capacity);
+        self
+    }
+
+    /// Stop any single render from growing its scope's render frame past `bytes`.
+    ///
+    /// A runaway render (like a list that was supposed to be paginated) would otherwise keep asking for memory until
+    /// the process is killed. With a limit, the render is stopped as soon as the frame would have to grow past it:
+    /// the component renders a placeholder and the nearest error boundary receives a [`crate::RenderPanic`], just
+    /// like a component that panicked.
+    ///
+    /// The limit only applies while the component function runs, and a frame that's already larger from an earlier
+    /// render can still fill the memory it has. By default, frames can grow as large as they need to.
+    pub fn with_max_frame_bytes(mut self, bytes: usize) -> Self {
+        self.max_frame_bytes = Some(bytes);
+        self
+    }
+
+    /// Log a warning whenever a scope's hooks grow by more than `bytes` in a single render after its first.
+    ///
+    /// Unlike render frames, the arena behind a scope's hooks is never reset, so a hook that creates new state every
+    /// render instead of reusing its slot keeps growing it for as long as the scope is mounted. Use
+    /// [`ScopeState::hook_capacity`] to check the size of a scope's hook arena directly. Off by default.
+    pub fn with_hook_growth_warning(mut self, bytes: usize) -> Self {
+        self.hook_growth_warning = Some(bytes);
+        self
+    }
+
+    /// Report any scope that renders more than `renders` times in a single pass of [`VirtualDom::render_immediate`],
+    /// [`VirtualDom::render_with_deadline`], or [`VirtualDom::render_dirty_to_completion`].
+    ///
+    /// A component that marks itself dirty while it renders, like one that sets state unconditionally, keeps the pass
+    /// from ever settling and hangs the app without saying why. With a limit, the first render past it logs a warning
+    /// naming the component, or panics if `panic` is true. Only renders of dirty scopes count, not children that
+    /// re-render because their parent gave them new props. Off by default.
+    pub fn with_render_loop_limit(mut self, renders: usize, panic: bool) -> Self {
+        self.render_loop_limit = Some((renders, panic));
+        self
+    }
+
+    /// Keep up to `bytes` of each scope's formatted text across renders instead of copying it into the render frame
+    /// every time.
+    ///
+    /// Text with arguments, like `"{count} items"`, is formatted into the scope's frame on every render, even when it
+    /// comes out the same. With interning on, it's formatted into a reusable buffer and only copied the first time it's
+    /// seen, which takes pressure off the allocator for components that re-render a lot of unchanging text. Text without
+    /// arguments is never copied in the first place.
+    ///
+    /// The cache of each scope only grows until the scope is dropped, so text that changes every render fills it up
+    /// and then goes back to the frame. Formatting into the buffer and looking the text up costs more than copying short
+    /// strings, so measure with the `text_interning` benchmark in the `dioxus` crate before turning this on. Use
+    /// [`ScopeState::interned_text_bytes`] to see how full a scope's cache is. Off by default.
+    pub fn with_text_interning(mut self, bytes: usize) -> Self {
+        self.text_interning = Some(bytes);
+
+        // The root scope is created before any builder methods run
+        self.scopes[0].text_cache = Some(TextCache::new(bytes));
+
+        self
+    }
+
+    /// Replace a scope's render frame with a smaller one once `renders` renders in a row have used less than
+    /// `1 / ratio` of it.
+    ///
+    /// Frames keep the memory of their largest render, so a component that renders a huge tree once and then
+    /// collapses to a few nodes holds onto that memory for as long as it's mounted. With shrinking on, the frame is
+    /// reallocated at the size of its last render instead. Unlike [`VirtualDom::with_fixed_frame_capacity`], frames
+    /// that are used consistently are left alone.
+    ///
+    /// By default, frames never shrink.
+    pub fn with_frame_shrinking(mut self, ratio: usize, renders: usize) -> Self {
+        self.frame_shrinking = Some(FrameShrinking { ratio, renders });
+        self
+    }
+
+    /// Limit how many tasks each scope can have running at once.
+    ///
+    /// When a scope spawns a task while it's at the limit, its oldest task is dropped to make room and a warning is
+    /// logged. This keeps a component that spawns tasks in a loop from growing the task queue forever. Tasks spawned
+    /// with [`ScopeState::spawn_forever`] count toward the root scope.
+    ///
+    /// By default, scopes can have any number of tasks.
+    pub fn with_task_limit(self, limit: usize) -> Self {
+        self.scheduler.task_limit.set(Some(limit));
+        self
+    }
+
+    /// Never reuse the [`ScopeId`] of a scope that was removed, so every scope gets the next ID in the order scopes are
+    /// created.
+    ///
+    /// By default, new scopes take over the IDs of removed ones, so the ID a component ends up with depends on what was
+    /// mounted and unmounted before it. This makes IDs reproducible for snapshot tests, at the cost of never freeing
+    /// the slot each removed scope leaves behind. It's meant for tests only.
+    pub fn with_stable_scope_ids(mut self, stable: bool) -> Self {
+        self.scopes.set_stable(stable);
+        self
+    }
+
+    /// Stop rendering components nested more than `height` scopes below the root.
+    ///
+    /// A component that renders itself without a base case would otherwise build a deeper and deeper tree until it
+    /// overflows the stack, crashing the app without saying which component was at fault. Instead, the first scope past
+    /// the limit renders a placeholder and throws a [`crate::ScopeTooDeep`] error naming its component to the nearest
+    /// error boundary.
+    ///
+    /// Defaults to 10,000, which is far deeper than real apps nest.
+    pub fn with_max_scope_height(mut self, height: u32) -> Self {
+        self.max_scope_height = height;
+        self
+    }
+
+    /// Validate that every hook keeps the same type between renders.
+    ///
+    /// Calling hooks conditionally usually changes the number of hooks a component uses, but it's possible to swap one
+    /// hook for another and keep the count stable. In strict mode, every scope records the type of each of its hooks
+    /// and panics with the component name and hook index if a hook changes type on a later render.
+    pub fn with_strict_hooks(mut self, strict: bool) -> Self {
+        self.strict_hooks = strict;
+
+        // The root scope is created before any builder methods run
+        self.scopes[0].strict_hooks = strict;
+
+        self
+    }
+
+    /// Call `observer` just before any component renders, with the scope, its component, and the type of its props.
+    ///
+    /// This is meant for debugging and instrumentation, like logging which components re-render and why. The observer
+    /// can't see or change the props themselves since they're type-erased by the time they reach the VirtualDom.
+    ///
+    /// This is only available with the `render-observer` feature enabled, so release builds don't pay for the check.
+    #[cfg(feature = "render-observer")]
+    pub fn with_render_observer(mut self, observer: impl FnMut(&RenderInfo) + 'static) -> Self {
+        self.render_observer = Some(Box::new(observer));
         self
     }
 
+    /// Call `timer` after every component renders, with the scope and how long its component function took.
+    ///
+    /// Only the component function itself is timed, not diffing its output or rendering its children, so the times of
+    /// every scope add up to the time spent in user code. Without a timer, renders aren't timed at all.
+    ///
+    /// ```rust, ignore
+    /// let dom = VirtualDom::new(app).with_render_timer(|scope, took| println!("{:?} took {:?}", scope, took));
+    /// ```
+    pub fn with_render_timer(mut self, timer: impl FnMut(ScopeId, Duration) + 'static) -> Self {
+        self.render_timer = Some(Box::new(timer));
+        self
+    }
+
+    /// Call `listener` every time a scope finishes rendering, with the id of the scope that rendered.
+    ///
+    /// Listeners are called in the order they were added, after the scope is marked clean. This is handy for checking
+    /// that a change to some state re-renders exactly the scopes that depend on it.
+    ///
+    /// ```rust, ignore
+    /// dom.on_render(|scope| println!("{:?} rendered", scope));
+    /// ```
+    pub fn on_render(&mut self, listener: impl FnMut(ScopeId) + 'static) {
+        self.render_listeners.push(Box::new(listener));
+    }
+
+    /// Get the counters for every render and bump frame allocation since the VirtualDom was created.
+    ///
+    /// This is only available with the `profile` feature enabled, so release builds don't pay for the bookkeeping.
+    #[cfg(feature = "profile")]
+    pub fn allocator_lifetime_stats(&self) -> AllocatorStats {
+        self.allocator_stats
+    }
+
+    /// Get the generation of the most recent set of mutations handed out by the VirtualDom.
+    ///
+    /// This starts at `0` and is incremented every time [`VirtualDom::rebuild`], [`VirtualDom::render_immediate`], or
+    /// [`VirtualDom::render_with_deadline`] returns.
+    pub fn mutation_generation(&self) -> usize {
+        self.mutation_generation
+    }
+
+    /// Get every mutation produced since the given [`VirtualDom::mutation_generation`].
+    ///
+    /// This is useful for syncing a remote renderer that reconnects after missing a few renders. The history is left
+    /// untouched, so multiple renderers can catch up from different generations.
+    ///
+    /// Only the number of generations passed to [`VirtualDom::with_mutation_history`] are kept. If the requested
+    /// generation is older than that (or newer than the current generation), this returns `None` and the renderer
+    /// needs to be rebuilt from scratch with [`VirtualDom::rebuild`].
+    pub fn take_mutations_since(&self, generation: usize) -> Option<Mutations> {
+        self.mutation_history
+            .since(generation, self.mutation_generation)
+    }
+
+    /// Tear down the whole tree right away: cancel every task, drop every suspended future, and drop the hooks of
+    /// every scope from the bottom up.
+    ///
+    /// Dropping the VirtualDom does this too, but tasks can outlive it if a waker is still holding onto them. Call this
+    /// when the app exits to make sure everything that holds a resource (like a file or a socket) is dropped before
+    /// the process ends.
+    ///
+    /// Calling this more than once does nothing. The VirtualDom can't be used afterwards - rendering or handling events
+    /// after a shutdown will panic.
+    pub fn shutdown(&mut self) {
+        if self.shut_down {
+            return;
+        }
+        self.shut_down = true;
+
+        self.scheduler.cancel_all();
+        self.scheduler.leaves.borrow_mut().clear();
+        self.collected_leaves.clear();
+        self.notified_leaves.clear();
+        self.finished_fibers.clear();
+
+        self.drop_detached_scope();
+        self.drop_scope(ScopeId(0));
+
+        self.dirty_scopes.clear();
+        self.deferred_scopes.clear();
+    }
+
     /// Manually mark a scope as requiring a re-render
     ///
     /// Whenever the VirtualDom "works", it will re-render this scope
     pub fn mark_dirty(&mut self, id: ScopeId) {
+        // The scope from `render_scope_once` has nothing mounted to re-render
+        if self.detached_scope == Some(id) {
+            return;
+        }
+
         if let Some(scope) = self.scopes.get(id.0) {
             let height = scope.height;
             self.dirty_scopes.insert(DirtyScope { height, id });
         }
     }
 
+    /// Mark a scope and every scope under it as requiring a re-render
+    ///
+    /// Unlike [`VirtualDom::mark_dirty`], this re-renders children even if their props haven't changed. Scopes are
+    /// found by walking up from every scope in the VirtualDom, so this is O(n) in the number of scopes.
+    pub fn mark_subtree_dirty(&mut self, id: ScopeId) {
+        if !self.scopes.contains(id.0) {
+            return;
+        }
+
+        let subtree: Vec<ScopeId> = self
+            .scopes
+            .iter()
+            .map(|(idx, _)| ScopeId(idx))
+            .filter(|&scope| scope == id || self.ancestors(scope).any(|ancestor| ancestor == id))
+            .collect();
+
+        for scope in subtree {
+            self.mark_dirty(scope);
+        }
+    }
+
+    /// List the scopes that will re-render on the next render, from the top of the tree down.
+    ///
+    /// Scopes are ordered by height and then by ID, which is the order they'll be rendered in. Scopes marked dirty
+    /// inside a frozen or suspended subtree are listed too, but they're skipped when the render reaches them.
+    pub fn dirty_scopes(&self) -> impl Iterator<Item = ScopeId> + '_ {
+        self.dirty_scopes.iter().map(|dirty| dirty.id)
+    }
+
     /// Determine whether or not a scope is currently in a suspended state
     ///
     /// This does not mean the scope is waiting on its own futures, just that the tree that the scope exists in is
@@ -299,6 +826,265 @@ impl VirtualDom {
             .is_empty()
     }
 
+    /// Stop a scope and every scope under it from re-rendering until [`VirtualDom::unfreeze_subtree`] is called.
+    ///
+    /// Scopes in a frozen subtree that are marked dirty, or that get new props from a parent outside the subtree, are
+    /// deferred instead of rendered. Their last render stays on screen.
+    ///
+    /// Freezing only applies to re-renders. Async components under a frozen subtree that haven't rendered yet still
+    /// resolve as usual, since there is no previous render to keep on screen.
+    pub fn freeze_subtree(&mut self, id: ScopeId) {
+        if self.scopes.contains(id.0) {
+            self.frozen_scopes.insert(id);
+        }
+    }
+
+    /// Let a subtree frozen with [`VirtualDom::freeze_subtree`] render again.
+    ///
+    /// Any scopes that were deferred while the subtree was frozen are marked dirty and rendered with the next call to
+    /// [`VirtualDom::render_immediate`] or [`VirtualDom::render_with_deadline`], unless they're still under another
+    /// frozen scope.
+    pub fn unfreeze_subtree(&mut self, id: ScopeId) {
+        if !self.frozen_scopes.remove(&id) {
+            return;
+        }
+
+        let deferred = std::mem::take(&mut self.deferred_scopes);
+        for dirty in deferred {
+            if !self.scopes.contains(dirty.id.0) {
+                continue;
+            }
+
+            if self.is_frozen(dirty.id) {
+                self.deferred_scopes.insert(dirty);
+            } else {
+                self.dirty_scopes.insert(dirty);
+            }
+        }
+    }
+
+    /// Check if a scope or any of its ancestors are frozen
+    pub(crate) fn is_frozen(&self, id: ScopeId) -> bool {
+        if self.frozen_scopes.is_empty() {
+            return false;
+        }
+
+        let mut current = Some(id);
+        while let Some(id) = current {
+            if self.frozen_scopes.contains(&id) {
+                return true;
+            }
+            current = self.scopes.get(id.0).and_then(|scope| scope.parent());
+        }
+
+        false
+    }
+
+    /// Determine whether a specific scope is waiting on its own async render to resolve.
+    ///
+    /// Unlike [`VirtualDom::is_scope_suspended`], this only returns true if the scope itself is a suspended leaf, not
+    /// if some other scope under the same boundary is suspended.
+    ///
+    /// This scans every outstanding suspense leaf, so it is O(n) in the number of suspended scopes.
+    pub fn is_suspended(&self, id: ScopeId) -> bool {
+        self.scheduler
+            .leaves
+            .borrow()
+            .iter()
+            .any(|(_, leaf)| leaf.scope_id == id)
+    }
+
+    /// List the contexts provided directly by a scope, as their [`TypeId`] and type name, sorted by name.
+    ///
+    /// This only includes contexts the scope provided itself. Walk up through [`ScopeState::parent`] to see every
+    /// context a scope can consume. Returns `None` if the scope doesn't exist.
+    pub fn scope_shared_context_keys(&self, id: ScopeId) -> Option<Vec<(TypeId, &'static str)>> {
+        let mut keys: Vec<_> = self
+            .scopes
+            .get(id.0)?
+            .shared_contexts
+            .borrow()
+            .iter()
+            .map(|(id, (name, _))| (*id, *name))
+            .collect();
+
+        keys.sort_by_key(|(_, name)| *name);
+
+        Some(keys)
+    }
+
+    /// Look up a context as if it were consumed from `start`, checking the scope itself and then each of its parents.
+    ///
+    /// This is useful for content that's rendered in one place but logically belongs to another, like a portal.
+    /// Returns `None` if the scope doesn't exist or nothing above it provides a `T`.
+    pub fn consume_context_from<T: 'static + Clone>(&self, start: ScopeId) -> Option<T> {
+        self.get_scope(start)?.consume_context()
+    }
+
+    /// Get the bytes held by a scope's render frames and hooks.
+    ///
+    /// This only reads the size of the scope's arenas, so it's cheap enough to poll regularly. Returns `None` if the
+    /// scope doesn't exist (or has been unmounted).
+    pub fn scope_memory(&self, id: ScopeId) -> Option<ScopeMemory> {
+        let scope = self.scopes.get(id.0)?;
+
+        Some(ScopeMemory {
+            current_frame: scope.current_frame().bump.allocated_bytes(),
+            previous_frame: scope.previous_frame().bump.allocated_bytes(),
+            hooks: scope.hook_arena.allocated_bytes(),
+        })
+    }
+
+    /// Get the most bytes the render frames of every scope have held at once, from when the VirtualDom was created.
+    ///
+    /// The total is kept up to date as each scope renders and unmounts, so this is free to call. It catches spikes
+    /// that [`VirtualDom::scope_memory`] would miss once the frames shrink back down, like a large list that was only
+    /// mounted for a moment. Hooks aren't counted.
+    pub fn peak_arena_bytes(&self) -> usize {
+        self.peak_arena_bytes
+    }
+
+    /// Get how many times suspended components have been polled again right away because they woke themselves up
+    /// during a render, over the lifetime of this VirtualDom.
+    ///
+    /// A count that keeps climbing means some component is spending its time spinning instead of waiting, which is
+    /// worth tuning with [`VirtualDom::with_suspense_repoll_limit`].
+    pub fn suspense_repolls(&self) -> usize {
+        self.suspense_repolls
+    }
+
+    /// Get the total bytes held by the render frames and hooks of a scope and every scope under it.
+    ///
+    /// Scopes don't keep track of their children, so this checks the ancestors of every mounted scope and is
+    /// O(n * height). Returns 0 if the scope doesn't exist (or has been unmounted).
+    pub fn subtree_memory(&self, id: ScopeId) -> usize {
+        if !self.scopes.contains(id.0) {
+            return 0;
+        }
+
+        self.scopes
+            .iter()
+            .map(|(idx, _)| ScopeId(idx))
+            .filter(|&scope| scope == id || self.ancestors(scope).any(|ancestor| ancestor == id))
+            .filter_map(|scope| self.scope_memory(scope))
+            .map(|memory| memory.total())
+            .sum()
+    }
+
+    /// Take a snapshot of how many scopes, tasks, and elements the VirtualDom is holding onto.
+    ///
+    /// This walks every scope to total up their memory, so it's O(n) in the number of scopes.
+    pub fn stats(&self) -> DomStats {
+        DomStats {
+            scopes: self.scopes.len(),
+            dirty_scopes: self.dirty_scopes.len(),
+            suspended_scopes: self.scheduler.leaves.borrow().len(),
+            tasks: self.scheduler.tasks.borrow().len(),
+            elements: self.elements.len(),
+            pooled_frames: self.frame_pool.len(),
+            allocated_bytes: self
+                .scopes
+                .iter()
+                .map(|(_, scope)| {
+                    scope.node_arena_1.bump.allocated_bytes()
+                        + scope.node_arena_2.bump.allocated_bytes()
+                        + scope.hook_arena.allocated_bytes()
+                })
+                .sum(),
+        }
+    }
+
+    /// Save the state of every hook created with [`ScopeState::use_persistent_hook`] into a blob that can be restored
+    /// with [`VirtualDom::import_state`], on this VirtualDom or one created on the next launch of the app.
+    ///
+    /// Scopes are matched by their path through the tree: the names of their component and its ancestors, along with
+    /// the keys of any list items they were rendered in. Only persistent hooks are saved - state kept in plain hooks,
+    /// contexts, or tasks is skipped, as are hooks that fail to serialize. Sibling components with the same name and no
+    /// key can't be told apart, so their state is skipped too.
+    ///
+    /// The blob is JSON tagged with a format version. Newer versions of dioxus can import blobs written by older ones,
+    /// but not the other way around.
+    ///
+    /// This is only available with the `serialize` feature enabled.
+    #[cfg(feature = "serialize")]
+    pub fn export_state(&self) -> Vec<u8> {
+        let mut blob = StateBlob {
+            version: STATE_VERSION,
+            scopes: BTreeMap::new(),
+        };
+        let mut ambiguous = FxHashSet::default();
+
+        for (_, scope) in self.scopes.iter() {
+            let hooks = scope.export_hooks();
+            if hooks.is_empty() {
+                continue;
+            }
+
+            let path = state_path(scope.parent, scope.name, scope.key.as_deref());
+            if blob.scopes.insert(path.clone(), hooks).is_some() {
+                ambiguous.insert(path);
+            }
+        }
+
+        for path in ambiguous {
+            blob.scopes.remove(&path);
+        }
+
+        serde_json::to_vec(&blob).expect("hook state to always serialize")
+    }
+
+    /// Restore the state saved with [`VirtualDom::export_state`].
+    ///
+    /// The state is handed to each persistent hook the first time it runs, so this should be called before
+    /// [`VirtualDom::rebuild`]. Hooks that have already been created keep their current state. Replaces any state
+    /// imported earlier that hasn't been claimed yet.
+    ///
+    /// This is only available with the `serialize` feature enabled.
+    #[cfg(feature = "serialize")]
+    pub fn import_state(&mut self, blob: &[u8]) -> Result<(), ImportStateError> {
+        let blob: StateBlob = serde_json::from_slice(blob).map_err(ImportStateError::Malformed)?;
+
+        if blob.version > STATE_VERSION {
+            return Err(ImportStateError::UnsupportedVersion(blob.version));
+        }
+
+        self.restored_state = blob.scopes.into_iter().collect();
+
+        // The root scope (and any others) already exist, so they won't pick up their state when they're created
+        for (_, scope) in self.scopes.iter() {
+            let path = state_path(scope.parent, scope.name, scope.key.as_deref());
+            if let Some(hooks) = self.restored_state.remove(&path) {
+                *scope.restored_hooks.borrow_mut() = hooks;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// List every outstanding suspended leaf along with the scope it belongs to and the boundary it's holding up.
+    ///
+    /// Each entry is `(leaf, leaf scope, boundary scope)`, where the boundary is the nearest ancestor that provides a
+    /// [`SuspenseContext`]. Every leaf has to resolve before its boundary can show its subtree, so this is useful for
+    /// figuring out why a region of the tree stays suspended.
+    ///
+    /// Leaves are parked as soon as their component renders, so this includes leaves from a render that's still
+    /// waiting to be attached to its boundary. Leaves drop out of the list once they resolve or time out. Use
+    /// [`VirtualDom::ancestors`] on the leaf scope to show where it sits in the tree.
+    pub fn suspense_dependencies(&self) -> Vec<(SuspenseId, ScopeId, ScopeId)> {
+        self.scheduler
+            .leaves
+            .borrow()
+            .iter()
+            .filter_map(|(_, leaf)| {
+                let boundary = self
+                    .scopes
+                    .get(leaf.scope_id.0)?
+                    .consume_context::<Rc<SuspenseContext>>()?;
+                Some((leaf.id, leaf.scope_id, boundary.id))
+            })
+            .collect()
+    }
+
     /// Determine if the tree is at all suspended. Used by SSR and other outside mechanisms to determine if the tree is
     /// ready to be rendered.
     pub fn has_suspended_work(&self) -> bool {
@@ -404,15 +1190,26 @@ impl VirtualDom {
     /// any work is ready. If multiple scopes are marked dirty from a task or a suspense tree is finished, this method
     /// will exit.
     ///
-    /// This method is cancel-safe, so you're fine to discard the future in a select block.
+    /// This method is cancel-safe, so you're fine to discard the future in a select block. Messages are only pulled
+    /// off the scheduler's channel between await points and are applied to the VirtualDom immediately, so dropping the
+    /// future never loses a message - any work it already picked up is left in the dirty scopes for the next render.
     ///
     /// This lets us poll async tasks and suspended trees during idle periods without blocking the main thread.
     ///
     /// # Example
     ///
     /// ```rust, ignore
-    /// let dom = VirtualDom::new(App);
-    /// let sender = dom.get_scheduler_channel();
+    /// let mut dom = VirtualDom::new(App);
+    /// let edits = dom.rebuild();
+    ///
+    /// loop {
+    ///     tokio::select! {
+    ///         _ = dom.wait_for_work() => {}
+    ///         evt = native_events.recv() => dom.handle_event(evt.name, evt.data, evt.element, evt.bubbles),
+    ///     }
+    ///
+    ///     let edits = dom.render_immediate();
+    /// }
     /// ```
     pub async fn wait_for_work(&mut self) {
         let mut some_msg = None;
@@ -423,21 +1220,64 @@ impl VirtualDom {
                 Some(msg) => match msg {
                     SchedulerMsg::Immediate(id) => self.mark_dirty(id),
                     SchedulerMsg::TaskNotified(task) => self.handle_task_wakeup(task),
-                    SchedulerMsg::SuspenseNotified(id) => self.handle_suspense_wakeup(id),
+                    SchedulerMsg::SuspenseNotified(id) => {
+                        self.notified_leaves.insert(id);
+                    }
                 },
 
                 // If they're not ready, then we should wait for them to be ready
                 None => {
                     match self.rx.try_next() {
                         Ok(Some(val)) => some_msg = Some(val),
-                        Ok(None) => return,
+                        Ok(None) => {
+                            self.poll_notified_leaves();
+                            return;
+                        }
                         Err(_) => {
+                            // Every leaf that woke up since the last batch is polled together once the queue is empty
+                            if !self.notified_leaves.is_empty() {
+                                self.poll_notified_leaves();
+                                continue;
+                            }
+
                             // If we have any dirty scopes, or finished fiber trees then we should exit
                             if !self.dirty_scopes.is_empty() || !self.finished_fibers.is_empty() {
                                 return;
                             }
 
-                            some_msg = self.rx.next().await
+                            // Wake up for the next suspense timeout, even if nothing else happens first
+                            #[cfg(not(target_arch = "wasm32"))]
+                            if let Some(deadline) = self.next_suspense_deadline() {
+                                // Only start a new timer when the deadline moves
+                                if self.suspense_timer.as_ref().map(Timer::deadline)
+                                    != Some(deadline)
+                                {
+                                    self.suspense_timer = Some(Timer::new(deadline));
+                                }
+                                let timer = self.suspense_timer.as_mut().unwrap();
+
+                                use futures_util::future::{select, Either};
+                                let timed_out = match select(self.rx.next(), timer).await {
+                                    Either::Left((msg, _)) => {
+                                        some_msg = msg;
+                                        false
+                                    }
+                                    Either::Right(_) => true,
+                                };
+
+                                if timed_out {
+                                    self.suspense_timer = None;
+                                    self.expire_suspense_leaves();
+                                }
+                                continue;
+                            }
+
+                            #[cfg(not(target_arch = "wasm32"))]
+                            {
+                                self.suspense_timer = None;
+                            }
+
+                            some_msg = self.rx.next().await;
                         }
                     }
                 }
@@ -451,7 +1291,36 @@ impl VirtualDom {
             match msg {
                 SchedulerMsg::Immediate(id) => self.mark_dirty(id),
                 SchedulerMsg::TaskNotified(task) => self.handle_task_wakeup(task),
-                SchedulerMsg::SuspenseNotified(id) => self.handle_suspense_wakeup(id),
+                SchedulerMsg::SuspenseNotified(id) => {
+                    self.notified_leaves.insert(id);
+                }
+            }
+        }
+
+        self.poll_notified_leaves();
+        self.expire_suspense_leaves();
+    }
+
+    /// Wait for a task to finish, polling it and every other task through the scheduler as they wake up.
+    ///
+    /// Scopes that the tasks mark dirty are left for the next render, like with [`VirtualDom::wait_for_work`]. Returns
+    /// right away if the task already finished or was dropped along with its scope.
+    ///
+    /// ```rust, ignore
+    /// let _ = dom.rebuild();
+    /// for id in dom.base_scope().spawned_task_ids() {
+    ///     dom.wait_for_task(id).await;
+    /// }
+    /// ```
+    pub async fn wait_for_task(&mut self, id: TaskId) {
+        while self.scheduler.tasks.borrow().contains(id.0) {
+            match self.rx.next().await {
+                Some(SchedulerMsg::Immediate(id)) => self.mark_dirty(id),
+                Some(SchedulerMsg::TaskNotified(task)) => self.handle_task_wakeup(task),
+                Some(SchedulerMsg::SuspenseNotified(id)) => {
+                    self.notified_leaves.insert(id);
+                }
+                None => return,
             }
         }
     }
@@ -465,7 +1334,7 @@ impl VirtualDom {
     pub fn replace_template(&mut self, template: Template<'static>) {
         self.register_template_first_byte_index(template);
         // iterating a slab is very inefficient, but this is a rare operation that will only happen during development so it's fine
-        for (_, scope) in &self.scopes {
+        for (_, scope) in self.scopes.iter() {
             if let Some(RenderReturn::Ready(sync)) = scope.try_root_node() {
                 if sync.template.get().name.rsplit_once(':').unwrap().0
                     == template.name.rsplit_once(':').unwrap().0
@@ -518,6 +1387,85 @@ impl VirtualDom {
         self.finalize()
     }
 
+    /// Run a component once with `props` and get back what it rendered, without mounting or diffing it.
+    ///
+    /// This is meant for unit testing a component's output in isolation. The component gets a new scope under the root
+    /// so it can read the root's contexts, but the scope isn't part of the tree: its output never produces mutations,
+    /// the child components it renders aren't created, and it never re-renders. Updates it schedules are ignored, and
+    /// any tasks it spawns or futures it suspends on are dropped right after it renders. The scope from the previous
+    /// call is dropped when this is called again, and otherwise when the VirtualDom is dropped.
+    ///
+    /// ```rust, ignore
+    /// let mut dom = VirtualDom::new(app);
+    /// match dom.render_scope_once(Greeting, GreetingProps { name: "jane" }) {
+    ///     RenderReturn::Ready(node) => assert_eq!(node.dynamic_nodes.len(), 1),
+    ///     _ => panic!("expected Greeting to render"),
+    /// }
+    /// ```
+    pub fn render_scope_once<P: 'static>(
+        &mut self,
+        component: fn(Scope<P>) -> Element,
+        props: P,
+    ) -> &RenderReturn {
+        self.drop_detached_scope();
+
+        self.scope_stack.push(ScopeId(0));
+        let id = self
+            .new_scope(
+                Box::new(VProps::new(component, |_, _| unreachable!(), props)),
+                "render_scope_once",
+                None,
+            )
+            .id;
+        self.scope_stack.pop();
+
+        self.detached_scope = Some(id);
+        self.run_scope(id);
+        self.forget_detached_work(id);
+
+        self.scopes[id.0].root_node()
+    }
+
+    /// Drop the scope created by [`VirtualDom::render_scope_once`], if there is one
+    fn drop_detached_scope(&mut self) {
+        if let Some(id) = self.detached_scope.take() {
+            self.forget_detached_work(id);
+            self.drop_scope(id);
+            self.arena_bytes -= self.scopes[id.0].arena_bytes.get();
+            self.scopes.remove(id.0);
+        }
+    }
+
+    /// Clear out everything the scheduler knows about a scope from [`VirtualDom::render_scope_once`], since its nodes
+    /// were never created and can't be diffed or attached to a suspense boundary
+    fn forget_detached_work(&mut self, id: ScopeId) {
+        let tasks = self.scopes[id.0].spawned_tasks.take();
+        self.scheduler.cancel_scope(id, tasks);
+
+        let dirty = DirtyScope {
+            height: self.scopes[id.0].height,
+            id,
+        };
+        self.dirty_scopes.remove(&dirty);
+        self.deferred_scopes.remove(&dirty);
+
+        let mut leaves = self.scheduler.leaves.borrow_mut();
+        let suspended: Vec<SuspenseId> = leaves
+            .iter()
+            .filter(|(_, leaf)| leaf.scope_id == id)
+            .map(|(key, _)| SuspenseId(key))
+            .collect();
+        for leaf in &suspended {
+            leaves.remove(leaf.0);
+        }
+        drop(leaves);
+
+        self.collected_leaves
+            .retain(|leaf| !suspended.contains(leaf));
+        self.notified_leaves
+            .retain(|leaf| !suspended.contains(leaf));
+    }
+
     /// Render whatever the VirtualDom has ready as fast as possible without requiring an executor to progress
     /// suspended subtrees.
     pub fn render_immediate(&mut self) -> Mutations {
@@ -548,69 +1496,12 @@ impl VirtualDom {
 
         loop {
             // first, unload any complete suspense trees
-            for finished_fiber in self.finished_fibers.drain(..) {
-                let scope = &mut self.scopes[finished_fiber.0];
-                let context = scope.has_context::<Rc<SuspenseContext>>().unwrap();
-
-                self.mutations
-                    .templates
-                    .append(&mut context.mutations.borrow_mut().templates);
-
-                self.mutations
-                    .edits
-                    .append(&mut context.mutations.borrow_mut().edits);
-
-                // TODO: count how many nodes are on the stack?
-                self.mutations.push(Mutation::ReplaceWith {
-                    id: context.placeholder.get().unwrap(),
-                    m: 1,
-                })
-            }
+            self.unload_finished_fibers();
 
             // Next, diff any dirty scopes
             // We choose not to poll the deadline since we complete pretty quickly anyways
-            if let Some(dirty) = self.dirty_scopes.iter().next().cloned() {
-                self.dirty_scopes.remove(&dirty);
-
-                // If the scope doesn't exist for whatever reason, then we should skip it
-                if !self.scopes.contains(dirty.id.0) {
-                    continue;
-                }
-
-                // if the scope is currently suspended, then we should skip it, ignoring any tasks calling for an update
-                if self.is_scope_suspended(dirty.id) {
-                    continue;
-                }
-
-                // Save the current mutations length so we can split them into boundary
-                let mutations_to_this_point = self.mutations.edits.len();
-
-                // Run the scope and get the mutations
-                self.run_scope(dirty.id);
-                self.diff_scope(dirty.id);
-
-                // If suspended leaves are present, then we should find the boundary for this scope and attach things
-                // No placeholder necessary since this is a diff
-                if !self.collected_leaves.is_empty() {
-                    let mut boundary = self.scopes[dirty.id.0]
-                        .consume_context::<Rc<SuspenseContext>>()
-                        .unwrap();
-
-                    let boundary_mut = boundary.borrow_mut();
-
-                    // Attach mutations
-                    boundary_mut
-                        .mutations
-                        .borrow_mut()
-                        .edits
-                        .extend(self.mutations.edits.split_off(mutations_to_this_point));
-
-                    // Attach suspended leaves
-                    boundary
-                        .waiting_on
-                        .borrow_mut()
-                        .extend(self.collected_leaves.drain(..));
-                }
+            if let Some(dirty) = self.next_dirty_scope() {
+                self.render_dirty_scope(dirty);
             }
 
             // If there's more work, then just continue, plenty of work to do
@@ -639,11 +1530,210 @@ impl VirtualDom {
         }
     }
 
+    /// Drive the VirtualDom until the shutdown future resolves, handing every batch of mutations to `apply`.
+    ///
+    /// This is a reference event loop for hosts that don't need to interleave their own events with the VirtualDom.
+    /// Call [`VirtualDom::rebuild`] and apply its mutations before running the loop.
+    ///
+    /// Each iteration waits for work with [`VirtualDom::wait_for_work`], then renders everything that's ready. Async
+    /// components are never awaited inline: when a suspense leaf resolves, its boundary's mutations are handed to
+    /// `apply` in the iteration after its future wakes up, so a slow future never holds back updates to the rest of
+    /// the tree. Iterations that don't produce any mutations don't call `apply`.
+    ///
+    /// # Example
+    ///
+    /// ```rust, ignore
+    /// let mut dom = VirtualDom::new(app);
+    /// renderer.apply(dom.rebuild());
+    ///
+    /// dom.run(|mutations| renderer.apply(mutations), shutdown_signal).await;
+    /// ```
+    pub async fn run(
+        &mut self,
+        mut apply: impl FnMut(Mutations),
+        shutdown: impl Future<Output = ()>,
+    ) {
+        pin_mut!(shutdown);
+
+        loop {
+            {
+                let work = self.wait_for_work();
+                pin_mut!(work);
+
+                use futures_util::future::{select, Either};
+                if let Either::Left(_) = select(&mut shutdown, work).await {
+                    return;
+                }
+            }
+
+            let mutations = self.render_immediate();
+            if !mutations.edits.is_empty() || !mutations.templates.is_empty() {
+                apply(mutations);
+            }
+        }
+    }
+
+    /// Render every dirty scope, including scopes that are marked dirty while rendering, until there are none left.
+    ///
+    /// This is a convenient way to flush all pending work for hosts that don't need a deadline. Scopes are rendered in
+    /// height order, and updates scheduled by a render are picked up in the same pass. Suspended leaves are collected
+    /// but not awaited - use [`VirtualDom::wait_for_work`] to make progress on them.
+    ///
+    /// A component that marks itself dirty on every render will keep this from returning.
+    pub fn render_dirty_to_completion(&mut self) -> (Mutations, RenderSummary) {
+        #[cfg(not(target_arch = "wasm32"))]
+        let start = std::time::Instant::now();
+
+        let mut summary = RenderSummary::default();
+
+        loop {
+            // Pick up anything scheduled by the last render
+            self.process_events();
+            self.unload_finished_fibers();
+
+            let dirty = match self.next_dirty_scope() {
+                Some(dirty) => dirty,
+                None => break,
+            };
+
+            if let Some(leaves) = self.render_dirty_scope(dirty) {
+                summary.scopes_rendered += 1;
+                summary.suspense_leaves += leaves;
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            summary.elapsed = start.elapsed();
+        }
+
+        (self.finalize(), summary)
+    }
+
+    /// Get the dirty scope closest to the root, which is the next one to render.
+    ///
+    /// Parents always render before their children so a child never renders against props its parent is about to
+    /// replace. Rendering a parent can dirty or clean its children, so this is checked again after every render
+    /// instead of draining the set up front.
+    fn next_dirty_scope(&self) -> Option<DirtyScope> {
+        self.dirty_scopes.iter().next().cloned()
+    }
+
+    /// Move the mutations of every suspense tree that finished loading into the main list of mutations
+    fn unload_finished_fibers(&mut self) {
+        for finished_fiber in self.finished_fibers.drain(..) {
+            let scope = &mut self.scopes[finished_fiber.0];
+            let context = scope.has_context::<Rc<SuspenseContext>>().unwrap();
+
+            self.mutations
+                .templates
+                .append(&mut context.mutations.borrow_mut().templates);
+
+            self.mutations
+                .edits
+                .append(&mut context.mutations.borrow_mut().edits);
+
+            // TODO: count how many nodes are on the stack?
+            self.mutations.push(Mutation::ReplaceWith {
+                id: context.placeholder.get().unwrap(),
+                m: 1,
+            })
+        }
+    }
+
+    /// Render a scope that was popped off the dirty list, attaching any suspended leaves to its boundary.
+    ///
+    /// Returns the number of suspended leaves the render produced, or `None` if the scope was skipped.
+    fn render_dirty_scope(&mut self, dirty: DirtyScope) -> Option<usize> {
+        self.dirty_scopes.remove(&dirty);
+
+        // If the scope doesn't exist for whatever reason, or was never mounted, then we should skip it
+        if !self.scopes.contains(dirty.id.0) || self.detached_scope == Some(dirty.id) {
+            return None;
+        }
+
+        // if the scope is currently suspended, then we should skip it, ignoring any tasks calling for an update
+        if self.is_scope_suspended(dirty.id) {
+            return None;
+        }
+
+        // Frozen scopes hold onto their update until they're unfrozen
+        if self.is_frozen(dirty.id) {
+            self.deferred_scopes.insert(dirty);
+            return None;
+        }
+
+        if let Some((limit, panic)) = self.render_loop_limit {
+            self.count_pass_render(dirty.id, limit, panic);
+        }
+
+        // Save the current mutations length so we can split them into boundary
+        let mutations_to_this_point = self.mutations.edits.len();
+
+        // Run the scope and get the mutations
+        self.run_scope(dirty.id);
+        self.diff_scope(dirty.id);
+
+        let leaves = self.collected_leaves.len();
+
+        // If suspended leaves are present, then we should find the boundary for this scope and attach things
+        // No placeholder necessary since this is a diff
+        if !self.collected_leaves.is_empty() {
+            let mut boundary = self.scopes[dirty.id.0]
+                .consume_context::<Rc<SuspenseContext>>()
+                .unwrap();
+
+            let boundary_mut = boundary.borrow_mut();
+
+            // Attach mutations
+            boundary_mut
+                .mutations
+                .borrow_mut()
+                .edits
+                .extend(self.mutations.edits.split_off(mutations_to_this_point));
+
+            // Attach suspended leaves
+            boundary
+                .waiting_on
+                .borrow_mut()
+                .extend(self.collected_leaves.drain(..));
+        }
+
+        Some(leaves)
+    }
+
+    /// Count a render of a dirty scope toward the current pass, and report the scope the first time it goes past the
+    /// render loop limit
+    fn count_pass_render(&mut self, id: ScopeId, limit: usize, panic: bool) {
+        let renders = self.pass_renders.entry(id).or_default();
+        *renders += 1;
+
+        if *renders == limit + 1 {
+            let message = format!(
+                "Component `{}` ({:?}) rendered more than {} times in one pass. It's likely marking itself dirty every \
+                time it renders, like by setting state unconditionally.",
+                self.scopes[id.0].name(),
+                id,
+                limit
+            );
+
+            if panic {
+                panic!("{}", message);
+            }
+            log::warn!("{}", message);
+        }
+    }
+
     /// Swap the current mutations with a new
     fn finalize(&mut self) -> Mutations {
+        // Every render pass ends here
+        self.pass_renders.clear();
+
         // todo: make this a routine
         let mut out = Mutations::default();
         std::mem::swap(&mut self.mutations, &mut out);
+        self.mutation_generation += 1;
+        self.mutation_history.record(self.mutation_generation, &out);
         out
     }
 }
@@ -651,6 +1741,9 @@ impl VirtualDom {
 impl Drop for VirtualDom {
     fn drop(&mut self) {
         // Simply drop this scope which drops all of its children
-        self.drop_scope(ScopeId(0));
+        if !self.shut_down {
+            self.drop_detached_scope();
+            self.drop_scope(ScopeId(0));
+        }
     }
 }
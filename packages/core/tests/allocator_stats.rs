@@ -0,0 +1,31 @@
+//! Verify the lifetime allocator counters
+#![cfg(feature = "profile")]
+
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!( div { "hello" } ))
+}
+
+#[test]
+fn counts_renders_and_frame_reuse() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    let stats = dom.allocator_lifetime_stats();
+    assert_eq!(stats.renders, 1);
+    assert_eq!(stats.bump_allocations, 1);
+    assert_eq!(stats.bump_resets, 0);
+
+    // The second render fills the other fresh frame, after that the frames are reset and reused
+    for _ in 0..3 {
+        dom.mark_dirty(ScopeId(0));
+        let _ = dom.render_immediate();
+    }
+
+    let stats = dom.allocator_lifetime_stats();
+    assert_eq!(stats.renders, 4);
+    assert_eq!(stats.bump_allocations, 2);
+    assert_eq!(stats.bump_resets, 2);
+    assert!(stats.bytes_allocated > 0);
+}
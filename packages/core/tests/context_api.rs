@@ -49,3 +49,118 @@ fn state_shares() {
         [SetText { value: "Value is 3", id: ElementId(1,) },]
     );
 }
+
+#[test]
+fn provided_contexts_are_listed() {
+    fn app(cx: Scope) -> Element {
+        cx.provide_context(1_u8);
+        cx.provide_context(String::from("shared"));
+
+        cx.render(rsx!(child {}))
+    }
+
+    fn child(cx: Scope) -> Element {
+        cx.render(rsx!("child"))
+    }
+
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+
+    // The root also provides the default suspense and error boundaries
+    let keys = dom.scope_shared_context_keys(ScopeId(0)).unwrap();
+    assert_eq!(keys.len(), 4);
+    assert!(keys.contains(&(std::any::TypeId::of::<u8>(), "u8")));
+    assert!(keys.contains(&(std::any::TypeId::of::<String>(), "alloc::string::String")));
+    assert!(keys.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+
+    // Contexts provided by parents aren't listed on their children
+    assert_eq!(dom.scope_shared_context_keys(ScopeId(1)), Some(vec![]));
+    assert_eq!(dom.scope_shared_context_keys(ScopeId(100)), None);
+}
+
+#[test]
+fn contexts_resolve_from_any_scope() {
+    fn app(cx: Scope) -> Element {
+        cx.provide_context(1_u8);
+
+        cx.render(rsx!(child {}))
+    }
+
+    fn child(cx: Scope) -> Element {
+        cx.provide_context(String::from("child"));
+
+        cx.render(rsx!("child"))
+    }
+
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+
+    assert_eq!(dom.consume_context_from::<u8>(ScopeId(1)), Some(1));
+    assert_eq!(
+        dom.consume_context_from::<String>(ScopeId(1)),
+        Some("child".to_string())
+    );
+
+    // Contexts provided below the starting scope aren't visible
+    assert_eq!(dom.consume_context_from::<String>(ScopeId(0)), None);
+    assert_eq!(dom.consume_context_from::<u8>(ScopeId(100)), None);
+}
+
+#[test]
+fn root_contexts_reach_every_scope() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx!(child {}))
+    }
+
+    fn child(cx: Scope) -> Element {
+        let value = cx.consume_context::<i32>().unwrap_or_default();
+        cx.render(rsx!("Value is {value}"))
+    }
+
+    let mut dom = VirtualDom::new(app);
+    dom.provide_root_context(1);
+    _ = dom.rebuild();
+    assert_eq!(dom.consume_context_from::<i32>(ScopeId(1)), Some(1));
+
+    // Providing the same type again replaces the old value
+    dom.provide_root_context(2);
+    dom.mark_dirty(ScopeId(1));
+    assert_eq!(
+        dom.render_immediate().santize().edits,
+        [SetText { value: "Value is 2", id: ElementId(1,) },]
+    );
+}
+
+#[test]
+fn removed_contexts_fall_back_to_ancestors() {
+    fn app(cx: Scope) -> Element {
+        cx.provide_context(1_u8);
+
+        cx.render(rsx!(middle {}))
+    }
+
+    fn middle(cx: Scope) -> Element {
+        cx.use_hook(|| cx.provide_context(2_u8));
+
+        cx.render(rsx!(leaf {}))
+    }
+
+    fn leaf(cx: Scope) -> Element {
+        let value = cx.consume_context::<u8>().unwrap();
+        cx.render(rsx!("Value is {value}"))
+    }
+
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+    assert_eq!(dom.consume_context_from::<u8>(ScopeId(2)), Some(2));
+
+    let middle = dom.get_scope(ScopeId(1)).unwrap();
+    assert_eq!(middle.remove_context::<u8>(), Some(2));
+    assert_eq!(middle.remove_context::<u8>(), None);
+
+    dom.mark_dirty(ScopeId(2));
+    assert_eq!(
+        dom.render_immediate().santize().edits,
+        [SetText { value: "Value is 1", id: ElementId(1,) },]
+    );
+}
@@ -0,0 +1,40 @@
+#![allow(non_snake_case)]
+
+//! The debug render should produce a readable dump of the tree
+
+use dioxus::prelude::*;
+
+#[test]
+fn renders_nested_components() {
+    fn app(cx: Scope) -> Element {
+        let name = "world";
+
+        cx.render(rsx! {
+            div { class: "container",
+                "hello"
+                Child {}
+                "{name}"
+            }
+        })
+    }
+
+    fn Child(cx: Scope) -> Element {
+        cx.render(rsx! { span { "child" } })
+    }
+
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    assert_eq!(
+        dom.debug_render_tree(ScopeId(0)),
+        r#"<div class="container">
+  "hello"
+  component Child
+    <span>
+      "child"
+  "world"
+"#
+    );
+
+    assert_eq!(dom.debug_render_tree(ScopeId(1)), "<span>\n  \"child\"\n");
+}
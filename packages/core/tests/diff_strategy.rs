@@ -0,0 +1,41 @@
+//! Scopes that replace their renders wholesale should skip diffing
+
+use dioxus::core::{DiffStrategy, Mutation};
+use dioxus::prelude::*;
+
+fn app(cx: Scope<DiffStrategy>) -> Element {
+    cx.set_diff_strategy(*cx.props);
+
+    let count = cx.use_hook(|| 0);
+    *count += 1;
+
+    cx.render(rsx!( div { "{count}" } ))
+}
+
+/// Render the app a second time and check the edits it produced
+fn rerender(strategy: DiffStrategy, check: impl FnOnce(&[Mutation])) {
+    let mut dom = VirtualDom::new_with_props(app, strategy);
+    _ = dom.rebuild();
+
+    dom.mark_dirty(ScopeId(0));
+    check(&dom.render_immediate().edits);
+}
+
+#[test]
+fn default_strategy_diffs() {
+    rerender(DiffStrategy::default(), |edits| {
+        assert!(matches!(edits, [Mutation::SetText { value: "2", .. }]));
+    });
+}
+
+#[test]
+fn replace_always_recreates_the_render() {
+    rerender(DiffStrategy::ReplaceAlways, |edits| {
+        assert!(edits
+            .iter()
+            .any(|edit| matches!(edit, Mutation::ReplaceWith { m: 1, .. })));
+        assert!(!edits
+            .iter()
+            .any(|edit| matches!(edit, Mutation::SetText { .. })));
+    });
+}
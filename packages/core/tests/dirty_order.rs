@@ -0,0 +1,35 @@
+#![allow(non_snake_case)]
+
+//! Dirty scopes should render from the root down, no matter what order they were marked dirty in
+
+use dioxus::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+fn app(cx: Scope) -> Element {
+    let generation = cx.generation();
+    cx.render(rsx!( div { Child { generation: generation } } ))
+}
+
+#[inline_props]
+fn Child(cx: Scope, generation: usize) -> Element {
+    cx.render(rsx!("generation {generation}"))
+}
+
+#[test]
+fn parents_render_before_children() {
+    let rendered = Rc::new(RefCell::new(Vec::new()));
+
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    dom.on_render({
+        let rendered = rendered.clone();
+        move |scope| rendered.borrow_mut().push(scope)
+    });
+
+    // The child is marked dirty first, but its parent still renders first and re-renders it with the new props
+    dom.mark_dirty(ScopeId(1));
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+
+    assert_eq!(*rendered.borrow(), [ScopeId(0), ScopeId(1)]);
+}
@@ -0,0 +1,48 @@
+#![allow(non_snake_case)]
+
+//! Verify the dom stats snapshot
+
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!(
+        div { Child {} Child {} }
+    ))
+}
+
+fn Child(cx: Scope) -> Element {
+    cx.render(rsx!("child"))
+}
+
+#[test]
+fn counts_scopes_and_dirty_work() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    let stats = dom.stats();
+    assert_eq!(stats.scopes, 3);
+    assert_eq!(stats.dirty_scopes, 0);
+    assert_eq!(stats.suspended_scopes, 0);
+    assert!(stats.allocated_bytes > 0);
+
+    dom.mark_dirty(ScopeId(1));
+    assert_eq!(dom.stats().dirty_scopes, 1);
+}
+
+#[test]
+fn lists_dirty_scopes_top_down() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    assert_eq!(dom.dirty_scopes().count(), 0);
+
+    dom.mark_dirty(ScopeId(2));
+    dom.mark_dirty(ScopeId(0));
+    dom.mark_dirty(ScopeId(1));
+    assert_eq!(
+        dom.dirty_scopes().collect::<Vec<_>>(),
+        [ScopeId(0), ScopeId(1), ScopeId(2)]
+    );
+
+    let _ = dom.render_immediate();
+    assert_eq!(dom.dirty_scopes().count(), 0);
+}
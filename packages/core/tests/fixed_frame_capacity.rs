@@ -0,0 +1,36 @@
+//! In low-memory mode, frames shouldn't hold onto the memory of their largest render
+
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    let renders = cx.use_hook(|| 0);
+    *renders += 1;
+
+    // The first two renders fill both frames, and every render after is tiny
+    let rows = if *renders <= 2 { 1000 } else { 1 };
+
+    cx.render(rsx! {
+        (0..rows).map(|i| rsx!( div { "row {i}" } ))
+    })
+}
+
+#[test]
+fn fixed_frames_release_memory() {
+    let render = |dom: &mut VirtualDom| {
+        let _ = dom.rebuild();
+
+        // One more large render to fill the second frame, then a small render into each frame
+        for _ in 0..3 {
+            dom.mark_dirty(ScopeId(0));
+            let _ = dom.render_immediate();
+        }
+    };
+
+    let mut default = VirtualDom::new(app);
+    render(&mut default);
+
+    let mut fixed = VirtualDom::new(app).with_fixed_frame_capacity(256);
+    render(&mut fixed);
+
+    assert!(fixed.stats().allocated_bytes < default.stats().allocated_bytes);
+}
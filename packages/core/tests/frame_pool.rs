@@ -0,0 +1,42 @@
+#![allow(non_snake_case)]
+
+//! Frames from unmounted scopes should be reused by the next scopes that mount
+
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    let renders = cx.use_hook(|| 0);
+    *renders += 1;
+
+    // Mount the rows on odd renders and unmount them on even ones
+    let rows = if *renders % 2 == 1 { 3 } else { 0 };
+
+    cx.render(rsx! {
+        div {
+            (0..rows).map(|i| rsx!( div { key: "{i}", Row {} } ))
+        }
+    })
+}
+
+fn Row(cx: Scope) -> Element {
+    cx.render(rsx!( div { "row" } ))
+}
+
+#[test]
+fn unmounted_frames_are_reused() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    assert_eq!(dom.stats().pooled_frames, 0);
+
+    // Every row hands back both of its frames
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+    assert_eq!(dom.stats().scopes, 1);
+    assert_eq!(dom.stats().pooled_frames, 6);
+
+    // And the new rows take them again
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+    assert_eq!(dom.stats().scopes, 4);
+    assert_eq!(dom.stats().pooled_frames, 0);
+}
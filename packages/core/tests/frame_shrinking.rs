@@ -0,0 +1,54 @@
+//! Frames that stay mostly empty after a large render should shrink when shrinking is on
+
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    let renders = cx.use_hook(|| 0);
+    *renders += 1;
+
+    // The first two renders fill both frames, and every render after is tiny
+    let rows = if *renders <= 2 { 1000 } else { 1 };
+
+    cx.render(rsx! {
+        (0..rows).map(|i| rsx!( div { "row {i}" } ))
+    })
+}
+
+fn render(dom: &mut VirtualDom) {
+    let _ = dom.rebuild();
+    for _ in 0..8 {
+        dom.mark_dirty(ScopeId(0));
+        let _ = dom.render_immediate();
+    }
+}
+
+#[test]
+fn mostly_empty_frames_shrink() {
+    let mut default = VirtualDom::new(app);
+    render(&mut default);
+
+    let mut shrinking = VirtualDom::new(app).with_frame_shrinking(4, 2);
+    render(&mut shrinking);
+
+    assert!(shrinking.stats().allocated_bytes < default.stats().allocated_bytes);
+}
+
+#[test]
+fn frames_that_stay_full_keep_their_memory() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            (0..1000).map(|i| rsx!( div { "row {i}" } ))
+        })
+    }
+
+    let mut default = VirtualDom::new(app);
+    render(&mut default);
+
+    let mut shrinking = VirtualDom::new(app).with_frame_shrinking(4, 2);
+    render(&mut shrinking);
+
+    assert_eq!(
+        shrinking.stats().allocated_bytes,
+        default.stats().allocated_bytes
+    );
+}
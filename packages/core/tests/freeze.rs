@@ -0,0 +1,36 @@
+#![allow(non_snake_case)]
+
+//! Verify that frozen subtrees skip re-rendering until they're unfrozen
+
+use dioxus::core::Mutation::SetText;
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!(Counter {}))
+}
+
+fn Counter(cx: Scope) -> Element {
+    let count = cx.use_hook(|| 0);
+    *count += 1;
+    cx.render(rsx!( div { "{count}" } ))
+}
+
+#[test]
+fn frozen_scopes_defer_updates() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    dom.freeze_subtree(ScopeId(0));
+
+    // The counter is under the frozen root, so nothing renders
+    dom.mark_dirty(ScopeId(1));
+    assert!(dom.render_immediate().edits.is_empty());
+    assert_eq!(dom.get_scope(ScopeId(1)).unwrap().generation(), 1);
+
+    // Unfreezing renders the update that was held back
+    dom.unfreeze_subtree(ScopeId(0));
+    let edits = dom.render_immediate().edits;
+    assert!(edits
+        .iter()
+        .any(|edit| matches!(edit, SetText { value: "2", .. })));
+}
@@ -0,0 +1,43 @@
+//! Verify that scopes report the hooks they've created
+
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    let renders = cx.use_hook(|| 0);
+    *renders += 1;
+    cx.use_hook(|| "stable");
+
+    // Called conditionally, so it's only created on the second render
+    if *renders > 1 {
+        cx.use_hook(|| [0u8; 64]);
+    }
+
+    cx.render(rsx!("{renders}"))
+}
+
+#[test]
+fn counts_hooks_created_by_scope() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    let scope = dom.base_scope();
+    assert_eq!(scope.hook_count(), 2);
+    assert!(scope.hook_capacity() > 0);
+
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+    assert_eq!(dom.base_scope().hook_count(), 3);
+}
+
+#[test]
+fn hook_growth_is_measured_between_renders() {
+    let mut dom = VirtualDom::new(app).with_hook_growth_warning(16);
+    let _ = dom.rebuild();
+    let before = dom.base_scope().hook_capacity();
+
+    // The conditional hook is created on the second render, which is past the threshold and only logs a warning
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+    assert!(dom.base_scope().hook_capacity() >= before);
+    assert_eq!(dom.base_scope().hook_count(), 3);
+}
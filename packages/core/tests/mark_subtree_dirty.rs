@@ -0,0 +1,52 @@
+#![allow(non_snake_case)]
+
+//! Marking a subtree dirty should re-render every scope under it, even ones with memoized props
+
+use dioxus::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!( div { Child {} } Sibling {} ))
+}
+
+fn Child(cx: Scope) -> Element {
+    cx.render(rsx!(GrandChild {}))
+}
+
+fn GrandChild(cx: Scope) -> Element {
+    cx.render(rsx!("grandchild"))
+}
+
+fn Sibling(cx: Scope) -> Element {
+    cx.render(rsx!("sibling"))
+}
+
+#[test]
+fn every_scope_in_the_subtree_renders() {
+    let rendered = Rc::new(RefCell::new(Vec::new()));
+
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    dom.on_render({
+        let rendered = rendered.clone();
+        move |scope| rendered.borrow_mut().push(scope)
+    });
+
+    // The child has no props, so marking just the root dirty leaves it memoized
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+    assert_eq!(*rendered.borrow(), [ScopeId(0)]);
+
+    rendered.borrow_mut().clear();
+    dom.mark_subtree_dirty(ScopeId(1));
+    let _ = dom.render_immediate();
+    assert_eq!(*rendered.borrow(), [ScopeId(1), ScopeId(2)]);
+
+    rendered.borrow_mut().clear();
+    dom.mark_subtree_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+    assert_eq!(
+        *rendered.borrow(),
+        [ScopeId(0), ScopeId(1), ScopeId(3), ScopeId(2)]
+    );
+}
@@ -0,0 +1,42 @@
+#![allow(non_snake_case)]
+
+//! A render that grows its frame past the limit should be stopped instead of taking all the memory it wants
+
+use dioxus::core::ErrorBoundary;
+use dioxus::prelude::*;
+use std::rc::Rc;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx! {
+        div { HungryChild {} }
+    })
+}
+
+fn HungryChild(cx: Scope) -> Element {
+    let bytes = cx.bump().alloc_slice_fill_copy(1 << 20, 0_u8);
+    let len = bytes.len();
+    cx.render(rsx!("allocated {len} bytes"))
+}
+
+#[test]
+fn large_renders_reach_the_error_boundary() {
+    let mut dom = VirtualDom::new(app).with_max_frame_bytes(64 * 1024);
+    let _ = dom.rebuild();
+
+    let boundary = dom.base_scope().has_context::<Rc<ErrorBoundary>>().unwrap();
+    let captured = boundary.take_error().unwrap();
+    assert_eq!(captured.scope, ScopeId(1));
+
+    // The frame never grew to fit the render
+    let memory = dom.scope_memory(ScopeId(1)).unwrap();
+    assert!(memory.current_frame < 1 << 20);
+}
+
+#[test]
+fn frames_are_unlimited_by_default() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    let boundary = dom.base_scope().has_context::<Rc<ErrorBoundary>>().unwrap();
+    assert!(boundary.take_error().is_none());
+}
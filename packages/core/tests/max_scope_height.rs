@@ -0,0 +1,29 @@
+#![allow(non_snake_case)]
+
+//! Components nested past the maximum height shouldn't render
+
+use dioxus::core::{ErrorBoundary, ScopeTooDeep};
+use dioxus::prelude::*;
+use std::rc::Rc;
+
+// Renders itself forever
+fn Recursive(cx: Scope) -> Element {
+    cx.render(rsx!( div { Recursive {} } ))
+}
+
+#[test]
+fn runaway_recursion_is_stopped() {
+    let mut dom = VirtualDom::new(Recursive).with_max_scope_height(10);
+    let _ = dom.rebuild();
+
+    // The scope past the limit exists but never rendered its child
+    assert_eq!(dom.scopes_at_height(11).len(), 1);
+    assert!(dom.scopes_at_height(12).is_empty());
+
+    let boundary = dom.base_scope().has_context::<Rc<ErrorBoundary>>().unwrap();
+    let captured = boundary.take_error().unwrap();
+    assert_eq!(
+        format!("{:?}", captured.error),
+        format!("{:?}", ScopeTooDeep { name: "Recursive", height: 11 })
+    );
+}
@@ -0,0 +1,74 @@
+#![allow(non_snake_case)]
+
+//! Mount and unmount callbacks should run exactly once for each scope
+
+use dioxus::prelude::*;
+use std::cell::RefCell;
+
+thread_local! {
+    static EVENTS: RefCell<Vec<String>> = RefCell::new(Vec::new());
+}
+
+fn log(event: String) {
+    EVENTS.with(|events| events.borrow_mut().push(event));
+}
+
+fn take_events() -> Vec<String> {
+    EVENTS.with(|events| events.borrow_mut().drain(..).collect())
+}
+
+fn app(cx: Scope) -> Element {
+    let renders = cx.use_hook(|| 0);
+    *renders += 1;
+
+    cx.on_mount(|| log("mount app".to_string()));
+    cx.on_unmount(|| log("unmount app".to_string()));
+
+    // The child is only mounted on the first two renders
+    let show_child = *renders <= 2;
+
+    cx.render(rsx!(
+        div {
+            show_child.then(|| rsx!( Child {} ))
+        }
+    ))
+}
+
+fn Child(cx: Scope) -> Element {
+    cx.on_mount(|| log("mount child".to_string()));
+    cx.on_unmount(|| log("unmount child".to_string()));
+
+    cx.render(rsx!("child"))
+}
+
+#[test]
+fn callbacks_run_once_per_scope() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    assert_eq!(take_events(), ["mount app", "mount child"]);
+
+    // Re-rendering doesn't run anything again
+    dom.mark_dirty(ScopeId(0));
+    dom.mark_dirty(ScopeId(1));
+    let _ = dom.render_immediate();
+    assert!(take_events().is_empty());
+
+    // Removing the child unmounts it
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+    assert_eq!(take_events(), ["unmount child"]);
+
+    // And dropping the VirtualDom unmounts the rest
+    drop(dom);
+    assert_eq!(take_events(), ["unmount app"]);
+}
+
+#[test]
+fn children_unmount_before_parents() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    take_events();
+
+    drop(dom);
+    assert_eq!(take_events(), ["unmount child", "unmount app"]);
+}
@@ -0,0 +1,54 @@
+//! Verify that renderers can catch up from the mutation history
+
+use dioxus::core::Mutation;
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    let count = cx.use_hook(|| 0);
+    *count += 1;
+    cx.render(rsx!( div { "{count}" } ))
+}
+
+fn text_values<'a>(edits: &'a [Mutation<'a>]) -> Vec<&'a str> {
+    edits
+        .iter()
+        .filter_map(|edit| match edit {
+            Mutation::SetText { value, .. } => Some(*value),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn catches_up_from_history() {
+    let mut dom = VirtualDom::new(app).with_mutation_history(2);
+    let _ = dom.rebuild();
+    let first = dom.mutation_generation();
+
+    for _ in 0..2 {
+        dom.mark_dirty(ScopeId(0));
+        let _ = dom.render_immediate();
+    }
+
+    let caught_up = dom.take_mutations_since(first).unwrap();
+    assert_eq!(text_values(&caught_up.edits), ["2", "3"]);
+
+    // Nothing has happened since the latest generation
+    let latest = dom.take_mutations_since(dom.mutation_generation()).unwrap();
+    assert!(latest.edits.is_empty());
+}
+
+#[test]
+fn too_old_requires_rebuild() {
+    let mut dom = VirtualDom::new(app).with_mutation_history(1);
+    let _ = dom.rebuild();
+
+    for _ in 0..2 {
+        dom.mark_dirty(ScopeId(0));
+        let _ = dom.render_immediate();
+    }
+
+    assert!(dom.take_mutations_since(0).is_none());
+    assert!(dom.take_mutations_since(1).is_none());
+    assert!(dom.take_mutations_since(2).is_some());
+}
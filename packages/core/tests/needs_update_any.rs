@@ -0,0 +1,44 @@
+#![allow(non_snake_case)]
+
+//! Verify that components can mark other scopes as dirty
+
+use dioxus::core::Mutation::SetText;
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!(
+        Notifier {}
+        Subscriber {}
+    ))
+}
+
+fn Notifier(cx: Scope) -> Element {
+    if cx.generation() == 1 {
+        // Notify the subscriber next to us, and a scope that doesn't exist
+        cx.needs_update_any(ScopeId(2));
+        cx.needs_update_any(ScopeId(100));
+    }
+
+    cx.render(rsx!("notifier"))
+}
+
+fn Subscriber(cx: Scope) -> Element {
+    let count = cx.use_hook(|| 0);
+    *count += 1;
+    cx.render(rsx!( div { "{count}" } ))
+}
+
+#[test]
+fn sibling_is_rendered_after_current_render() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    dom.mark_dirty(ScopeId(1));
+    let (mutations, _) = dom.render_dirty_to_completion();
+
+    assert!(mutations
+        .edits
+        .iter()
+        .any(|edit| matches!(edit, SetText { value: "2", .. })));
+    assert_eq!(dom.get_scope(ScopeId(2)).unwrap().generation(), 2);
+}
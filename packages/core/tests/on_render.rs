@@ -0,0 +1,34 @@
+#![allow(non_snake_case)]
+
+//! Render listeners should hear about every scope that runs, and only those
+
+use dioxus::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!( div { Child {} Child {} } ))
+}
+
+fn Child(cx: Scope) -> Element {
+    cx.render(rsx!("child"))
+}
+
+#[test]
+fn listeners_hear_every_render() {
+    let rendered = Rc::new(RefCell::new(Vec::new()));
+
+    let mut dom = VirtualDom::new(app);
+    dom.on_render({
+        let rendered = rendered.clone();
+        move |scope| rendered.borrow_mut().push(scope)
+    });
+
+    let _ = dom.rebuild();
+    assert_eq!(*rendered.borrow(), [ScopeId(0), ScopeId(1), ScopeId(2)]);
+
+    // Only the scope that was marked dirty runs again
+    rendered.borrow_mut().clear();
+    dom.mark_dirty(ScopeId(2));
+    let _ = dom.render_immediate();
+    assert_eq!(*rendered.borrow(), [ScopeId(2)]);
+}
@@ -0,0 +1,49 @@
+#![allow(non_snake_case)]
+
+//! The peak arena size should remember spikes after the frames shrink back down
+
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    // Only the first render mounts the big list
+    let show = cx.generation() == 0;
+
+    cx.render(rsx! {
+        show.then(|| rsx!(List {}))
+    })
+}
+
+fn List(cx: Scope) -> Element {
+    cx.render(rsx! {
+        (0..100).map(|i| rsx!(Row { key: "{i}", index: i }))
+    })
+}
+
+#[inline_props]
+fn Row(cx: Scope, index: usize) -> Element {
+    cx.render(rsx!(div { "row {index}" }))
+}
+
+fn current_arena_bytes(dom: &VirtualDom) -> usize {
+    (0..=101)
+        .filter_map(|id| dom.scope_memory(ScopeId(id)))
+        .map(|memory| memory.current_frame + memory.previous_frame)
+        .sum()
+}
+
+#[test]
+fn peaks_outlive_the_spike() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    let peak = dom.peak_arena_bytes();
+    assert!(peak > 0);
+
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+
+    // The list is gone, but the peak still counts it
+    assert!(dom.get_scope(ScopeId(1)).is_none());
+    assert_eq!(dom.peak_arena_bytes(), peak);
+    assert!(current_arena_bytes(&dom) < peak);
+}
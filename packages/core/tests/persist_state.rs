@@ -0,0 +1,86 @@
+//! Persistent hook state should survive a round trip through `export_state` and `import_state`
+#![cfg(feature = "serialize")]
+#![allow(non_snake_case)]
+
+use dioxus::core::ImportStateError;
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    let renders = cx.use_persistent_hook(|| 0);
+    *renders += 1;
+
+    cx.render(rsx! {
+        ["a", "b"].iter().map(|k| rsx! {
+            div { key: "{k}", Item { name: k.to_string() } }
+        })
+    })
+}
+
+#[inline_props]
+fn Item(cx: Scope, name: String) -> Element {
+    let label = cx.use_persistent_hook(|| format!("label {}", name));
+
+    // Plain hooks aren't persisted
+    cx.use_hook(|| 0);
+
+    cx.render(rsx!("{label}"))
+}
+
+#[test]
+fn state_round_trips_through_blob() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+
+    let blob = dom.export_state();
+    assert!(String::from_utf8_lossy(&blob).contains(r#""app":{"0":2}"#));
+
+    // The restored root picks up where the last one left off
+    let mut restored = VirtualDom::new(app);
+    restored.import_state(&blob).unwrap();
+    let _ = restored.rebuild();
+
+    let blob = restored.export_state();
+    assert!(String::from_utf8_lossy(&blob).contains(r#""app":{"0":3}"#));
+}
+
+#[test]
+fn keyed_scopes_get_their_own_state() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    let blob = String::from_utf8(dom.export_state()).unwrap();
+
+    assert!(blob.contains(r#""app/Item#a":{"0":"label a"}"#));
+    assert!(blob.contains(r#""app/Item#b":{"0":"label b"}"#));
+}
+
+#[test]
+fn unkeyed_siblings_are_skipped() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            Item { name: "a".to_string() }
+            Item { name: "b".to_string() }
+        })
+    }
+
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    let blob = String::from_utf8(dom.export_state()).unwrap();
+
+    assert!(!blob.contains("app/Item"));
+}
+
+#[test]
+fn newer_or_malformed_blobs_are_rejected() {
+    let mut dom = VirtualDom::new(app);
+
+    assert!(matches!(
+        dom.import_state(br#"{"version":999,"scopes":{}}"#),
+        Err(ImportStateError::UnsupportedVersion(999))
+    ));
+    assert!(matches!(
+        dom.import_state(b"not a blob"),
+        Err(ImportStateError::Malformed(_))
+    ));
+}
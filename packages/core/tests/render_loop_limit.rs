@@ -0,0 +1,37 @@
+#![allow(non_snake_case)]
+
+//! A scope that keeps marking itself dirty should be reported once it renders too many times in one pass
+
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!(Looping {}))
+}
+
+fn Looping(cx: Scope) -> Element {
+    // Setting state during render re-dirties the scope every time it renders
+    cx.needs_update();
+    cx.render(rsx!("looping"))
+}
+
+#[test]
+#[should_panic(
+    expected = "Component `Looping` (ScopeId(1)) rendered more than 3 times in one pass"
+)]
+fn render_loops_are_caught() {
+    let mut dom = VirtualDom::new(app).with_render_loop_limit(3, true);
+    let _ = dom.rebuild();
+
+    let _ = dom.render_dirty_to_completion();
+}
+
+#[test]
+fn renders_are_counted_per_pass() {
+    let mut dom = VirtualDom::new(app).with_render_loop_limit(1, true);
+    let _ = dom.rebuild();
+
+    // Each pass only renders the scope once, since the update it schedules is picked up by the next pass
+    for _ in 0..3 {
+        let _ = dom.render_immediate();
+    }
+}
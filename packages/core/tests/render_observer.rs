@@ -0,0 +1,47 @@
+//! The render observer should see every component just before it renders
+#![cfg(feature = "render-observer")]
+#![allow(non_snake_case)]
+
+use dioxus::core::RenderInfo;
+use dioxus::prelude::*;
+use std::{cell::RefCell, rc::Rc};
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx! { Child { value: 1 } })
+}
+
+#[inline_props]
+fn Child(cx: Scope, value: i32) -> Element {
+    cx.render(rsx!("{value}"))
+}
+
+#[test]
+fn observer_sees_each_render() {
+    let seen = Rc::new(RefCell::new(Vec::<RenderInfo>::new()));
+
+    let observed = seen.clone();
+    let mut dom =
+        VirtualDom::new(app).with_render_observer(move |info| observed.borrow_mut().push(*info));
+    let _ = dom.rebuild();
+
+    {
+        let seen = seen.borrow();
+        assert_eq!(seen.len(), 2);
+
+        assert_eq!(seen[0].scope, ScopeId(0));
+        assert_eq!(seen[0].props_type, "()");
+
+        assert_eq!(seen[1].scope, ScopeId(1));
+        assert_eq!(seen[1].name, "Child");
+        assert!(seen[1].props_type.ends_with("ChildProps"));
+        assert_eq!(seen[1].render_count, 0);
+    }
+
+    dom.mark_dirty(ScopeId(1));
+    let _ = dom.render_immediate();
+
+    let seen = seen.borrow();
+    assert_eq!(seen.len(), 3);
+    assert_eq!(seen[2].scope, ScopeId(1));
+    assert_eq!(seen[2].render_count, 1);
+}
@@ -0,0 +1,41 @@
+#![allow(non_snake_case)]
+
+//! A component that panics while rendering should hand the panic to the nearest error boundary
+
+use dioxus::core::{ErrorBoundary, RenderPanic};
+use dioxus::prelude::*;
+use std::rc::Rc;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx! {
+        div {
+            h1 { "Title" }
+            PanicChild {}
+        }
+    })
+}
+
+fn PanicChild(_cx: Scope) -> Element {
+    panic!("Rendering panicked for whatever reason");
+}
+
+#[test]
+fn panics_reach_the_error_boundary() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    let boundary = dom.base_scope().has_context::<Rc<ErrorBoundary>>().unwrap();
+    let captured = boundary.take_error().unwrap();
+
+    assert_eq!(captured.scope, ScopeId(1));
+    assert_eq!(
+        format!("{:?}", captured.error),
+        format!(
+            "{:?}",
+            RenderPanic { message: "Rendering panicked for whatever reason".to_string() }
+        )
+    );
+
+    // The error is only handed out once
+    assert!(boundary.take_error().is_none());
+}
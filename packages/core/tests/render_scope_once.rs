@@ -0,0 +1,81 @@
+#![allow(non_snake_case)]
+
+//! A component should be renderable on its own, without mounting it into the tree
+
+use dioxus::core::{DynamicNode, RenderReturn};
+use dioxus::prelude::*;
+use std::{cell::Cell, rc::Rc};
+
+fn app(cx: Scope) -> Element {
+    cx.provide_context(String::from("from the root"));
+    cx.render(rsx!(div {}))
+}
+
+struct GreetingProps {
+    name: &'static str,
+    unmounted: Rc<Cell<bool>>,
+}
+
+fn Greeting(cx: Scope<GreetingProps>) -> Element {
+    let unmounted = cx.props.unmounted.clone();
+    cx.on_unmount(move || unmounted.set(true));
+
+    let context = cx.consume_context::<String>().unwrap();
+    let name = cx.props.name;
+    cx.render(rsx!( h1 { "hello {name}, {context}" } Child {} ))
+}
+
+fn Child(cx: Scope) -> Element {
+    cx.render(rsx!("child"))
+}
+
+#[test]
+fn renders_without_mounting() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    let unmounted = Rc::new(Cell::new(false));
+    let props = GreetingProps { name: "jane", unmounted: unmounted.clone() };
+    match dom.render_scope_once(Greeting, props) {
+        RenderReturn::Ready(node) => {
+            assert!(matches!(
+                &node.dynamic_nodes[0],
+                DynamicNode::Text(text) if text.value == "hello jane, from the root"
+            ));
+
+            // Children are only described, not created
+            assert!(matches!(
+                &node.dynamic_nodes[1],
+                DynamicNode::Component(child) if child.scope.get().is_none()
+            ));
+        }
+        _ => panic!("expected Greeting to render"),
+    }
+
+    // Rendering again drops the last scope, and nothing was ever mounted
+    let props = GreetingProps { name: "john", unmounted: Rc::new(Cell::new(false)) };
+    let _ = dom.render_scope_once(Greeting, props);
+    assert!(unmounted.get());
+    assert!(dom.render_immediate().edits.is_empty());
+}
+
+fn Restless(cx: Scope) -> Element {
+    cx.needs_update();
+    cx.spawn(async move {
+        std::future::pending::<()>().await;
+    });
+    cx.render(rsx!( div { "restless" } ))
+}
+
+#[test]
+fn updates_from_the_detached_scope_are_ignored() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    let _ = dom.render_scope_once(Restless, ());
+
+    // Its nodes were never created, so rendering it again would have nothing to diff against
+    assert!(dom.render_immediate().edits.is_empty());
+    assert_eq!(dom.dirty_scopes().count(), 0);
+    assert_eq!(dom.stats().tasks, 0);
+}
@@ -0,0 +1,93 @@
+#![allow(non_snake_case)]
+
+//! Snapshots of two renders should diff into the changes between them
+
+use dioxus::core::{Change, SnapshotNode};
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    let count = cx.use_hook(|| 0);
+    *count += 1;
+
+    let class = if *count > 1 { "active" } else { "idle" };
+
+    cx.render(rsx! {
+        div { class: "{class}",
+            "count: {count}"
+            Child {}
+            (0..*count).map(|i| rsx!( span { key: "{i}", "{i}" } ))
+        }
+    })
+}
+
+fn Child(cx: Scope) -> Element {
+    cx.render(rsx!("child"))
+}
+
+#[test]
+fn snapshots_capture_the_tree() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    let snapshot = dom.capture_render(ScopeId(0));
+    assert_eq!(
+        snapshot.roots,
+        [SnapshotNode::Element {
+            tag: "div".to_string(),
+            attributes: vec![("class".to_string(), "idle".to_string())],
+            children: vec![
+                SnapshotNode::Text("count: 1".to_string()),
+                SnapshotNode::Component {
+                    name: "Child".to_string(),
+                    children: vec![SnapshotNode::Text("child".to_string())],
+                },
+                SnapshotNode::Element {
+                    tag: "span".to_string(),
+                    attributes: vec![],
+                    children: vec![SnapshotNode::Text("0".to_string())],
+                },
+            ],
+        }]
+    );
+
+    assert!(dom.capture_render(ScopeId(100)).roots.is_empty());
+}
+
+#[test]
+fn diff_reports_node_level_changes() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    let before = dom.capture_render(ScopeId(0));
+
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+    let after = dom.capture_render(ScopeId(0));
+
+    assert_eq!(
+        before.diff(&after),
+        [
+            Change::AttributeChanged {
+                path: vec![0],
+                name: "class".to_string(),
+                old: Some("idle".to_string()),
+                new: Some("active".to_string()),
+            },
+            Change::TextChanged {
+                path: vec![0, 0],
+                old: "count: 1".to_string(),
+                new: "count: 2".to_string(),
+            },
+            Change::Added {
+                path: vec![0, 3],
+                node: SnapshotNode::Element {
+                    tag: "span".to_string(),
+                    attributes: vec![],
+                    children: vec![SnapshotNode::Text("1".to_string())],
+                },
+            },
+        ]
+    );
+
+    // Nothing changes between identical snapshots
+    assert!(after.diff(&after).is_empty());
+}
@@ -0,0 +1,35 @@
+#![allow(non_snake_case)]
+
+//! The render timer should be called once for every component render
+
+use dioxus::prelude::*;
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!( div { Child {} } ))
+}
+
+fn Child(cx: Scope) -> Element {
+    std::thread::sleep(Duration::from_millis(5));
+    cx.render(rsx!("child"))
+}
+
+#[test]
+fn every_render_is_timed() {
+    let timings = Rc::new(RefCell::new(Vec::new()));
+
+    let mut dom = VirtualDom::new(app).with_render_timer({
+        let timings = timings.clone();
+        move |scope, took| timings.borrow_mut().push((scope, took))
+    });
+    let _ = dom.rebuild();
+
+    let scopes: Vec<_> = timings.borrow().iter().map(|(scope, _)| *scope).collect();
+    assert_eq!(scopes, [ScopeId(0), ScopeId(1)]);
+    assert!(timings.borrow()[1].1 >= Duration::from_millis(5));
+
+    dom.mark_dirty(ScopeId(1));
+    let _ = dom.render_immediate();
+    assert_eq!(timings.borrow().len(), 3);
+    assert_eq!(timings.borrow()[2].0, ScopeId(1));
+}
@@ -0,0 +1,49 @@
+#![allow(non_snake_case)]
+
+//! Verify that render_dirty_to_completion drains cascading updates in one pass
+
+use dioxus::core::Mutation::SetText;
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!(
+        Trigger {}
+        Counter {}
+    ))
+}
+
+fn Trigger(cx: Scope) -> Element {
+    // On its first re-render, mark the counter next to it as dirty
+    if cx.generation() == 1 {
+        cx.schedule_update_any()(ScopeId(2));
+    }
+
+    cx.render(rsx!("trigger"))
+}
+
+fn Counter(cx: Scope) -> Element {
+    let count = cx.use_hook(|| 0);
+    *count += 1;
+    cx.render(rsx!( div { "{count}" } ))
+}
+
+#[test]
+fn cascading_updates_render_in_one_pass() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    dom.mark_dirty(ScopeId(1));
+    let (mutations, summary) = dom.render_dirty_to_completion();
+
+    assert_eq!(summary.scopes_rendered, 2);
+    assert_eq!(summary.suspense_leaves, 0);
+    assert!(mutations
+        .edits
+        .iter()
+        .any(|edit| matches!(edit, SetText { value: "2", .. })));
+
+    // Nothing is left over for the next pass
+    let (mutations, summary) = dom.render_dirty_to_completion();
+    assert_eq!(summary.scopes_rendered, 0);
+    assert!(mutations.edits.is_empty());
+}
@@ -0,0 +1,74 @@
+//! Resetting a scope's hooks should drop them and run every initializer again on the next render
+
+use dioxus::core::{ElementId, Mutation::*};
+use dioxus::prelude::*;
+use std::{cell::Cell, rc::Rc};
+
+struct DropCounter(Rc<Cell<usize>>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+struct AppProps {
+    drops: Rc<Cell<usize>>,
+}
+
+fn app(cx: Scope<AppProps>) -> Element {
+    let renders = cx.use_hook(|| 0);
+    *renders += 1;
+    cx.use_hook(|| DropCounter(cx.props.drops.clone()));
+
+    cx.render(rsx!("{renders}"))
+}
+
+#[test]
+fn hooks_are_dropped_and_recreated() {
+    let drops = Rc::new(Cell::new(0));
+    let mut dom = VirtualDom::new_with_props(app, AppProps { drops: drops.clone() });
+    let _ = dom.rebuild();
+
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+    assert_eq!(dom.base_scope().generation(), 2);
+
+    // The old hooks are kept until the last render they might be borrowed by has been diffed
+    dom.reset_scope_hooks(ScopeId(0));
+    assert_eq!(drops.get(), 0);
+    assert_eq!(dom.base_scope().hook_count(), 0);
+
+    // The scope was marked dirty, and the counter starts over from its initializer
+    let edits = dom.render_immediate().santize();
+    assert_eq!(dom.base_scope().hook_count(), 2);
+    assert_eq!(edits.edits, [SetText { value: "1", id: ElementId(1) }]);
+    assert_eq!(drops.get(), 1);
+
+    // Hooks that were never set aside are dropped along with the scope
+    drop(dom);
+    assert_eq!(drops.get(), 2);
+}
+
+fn borrowing_app(cx: Scope) -> Element {
+    let renders = cx.use_hook(|| 0);
+    *renders += 1;
+    let class: &String = cx.use_hook(|| format!("render-{}", renders));
+
+    // The attribute borrows straight from the hook
+    cx.render(rsx!(div { class: class.as_str() }))
+}
+
+#[test]
+fn the_last_render_can_borrow_from_reset_hooks() {
+    let mut dom = VirtualDom::new(borrowing_app);
+    let _ = dom.rebuild();
+
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+
+    // Diffing against the old render reads the class out of the old hook
+    dom.reset_scope_hooks(ScopeId(0));
+    let edits = dom.render_immediate().santize();
+    assert!(edits.edits.is_empty());
+}
@@ -0,0 +1,78 @@
+#![allow(non_snake_case)]
+
+//! New scopes should start with frames of the configured capacity
+
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!(Child {}))
+}
+
+fn Child(cx: Scope) -> Element {
+    cx.render(rsx!( div { "child" } ))
+}
+
+#[test]
+fn frames_start_with_capacity() {
+    let mut dom = VirtualDom::new(app).with_scope_arena_capacity(64 * 1024);
+    let _ = dom.rebuild();
+
+    for id in [ScopeId(0), ScopeId(1)] {
+        let memory = dom.scope_memory(id).unwrap();
+        assert!(memory.current_frame >= 64 * 1024);
+        assert!(memory.previous_frame >= 64 * 1024);
+    }
+}
+
+#[test]
+fn frames_start_empty_by_default() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    // The frame that hasn't been rendered into yet was never allocated
+    let memory = dom.scope_memory(ScopeId(1)).unwrap();
+    assert!(memory.current_frame < 64 * 1024);
+    assert!(memory.previous_frame < 64 * 1024);
+}
+
+#[test]
+fn scope_storage_can_be_reserved() {
+    fn list(cx: Scope) -> Element {
+        cx.render(rsx! {
+            (0..1000).map(|i| rsx!( div { key: "{i}", Child {} } ))
+        })
+    }
+
+    let mut dom = VirtualDom::new(list);
+    dom.reserve_scopes(1000);
+    assert!(dom.scope_capacity() >= 1000);
+
+    // Mounting everything that was reserved for fits without growing the storage
+    let capacity = dom.scope_capacity() + dom.stats().scopes;
+    let _ = dom.rebuild();
+    assert_eq!(dom.scope_capacity() + dom.stats().scopes, capacity);
+}
+
+#[test]
+fn lists_keep_depth_first_scope_ids() {
+    fn list(cx: Scope) -> Element {
+        cx.render(rsx! {
+            (0..100).map(|i| rsx!( div { key: "{i}", Wrapper {} } ))
+        })
+    }
+
+    fn Wrapper(cx: Scope) -> Element {
+        cx.render(rsx!(Child {}))
+    }
+
+    let mut dom = VirtualDom::new(list);
+    let _ = dom.rebuild();
+
+    // Reserving room for the whole list up front doesn't change the order scopes are created in
+    for i in 0..100 {
+        let wrapper = ScopeId(1 + 2 * i);
+        let child = ScopeId(2 + 2 * i);
+        assert_eq!(dom.get_scope(wrapper).unwrap().parent(), Some(ScopeId(0)));
+        assert_eq!(dom.get_scope(child).unwrap().parent(), Some(wrapper));
+    }
+}
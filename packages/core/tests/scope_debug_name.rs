@@ -0,0 +1,35 @@
+#![allow(non_snake_case)]
+
+//! Scopes can be given a name that's more useful than their component's name
+
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!(TreeNode { path: "/a".to_string() }))
+}
+
+#[inline_props]
+fn TreeNode(cx: Scope, path: String) -> Element {
+    cx.set_debug_name(format!("TreeNode[path={}]", path));
+
+    let child = (path.len() < 4).then(|| format!("{}/b", path));
+    cx.render(rsx! {
+        child.map(|path| rsx!( TreeNode { path: path } ))
+    })
+}
+
+#[test]
+fn debug_names_replace_the_component_name() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    assert_eq!(dom.base_scope().name(), "app");
+    assert_eq!(
+        dom.get_scope(ScopeId(1)).unwrap().name(),
+        "TreeNode[path=/a]"
+    );
+    assert_eq!(
+        dom.get_scope(ScopeId(2)).unwrap().name(),
+        "TreeNode[path=/a/b]"
+    );
+}
@@ -0,0 +1,27 @@
+#![allow(non_snake_case)]
+
+//! Components rendered inside keyed list items should carry the key of their item
+
+use dioxus::prelude::*;
+
+#[test]
+fn keyed_components_carry_key() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            ["a", "b"].iter().map(|k| rsx! {
+                div { key: "{k}", Child {} }
+            })
+        })
+    }
+
+    fn Child(cx: Scope) -> Element {
+        cx.render(rsx! { "child" })
+    }
+
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    assert_eq!(dom.base_scope().key(), None);
+    assert_eq!(dom.get_scope(ScopeId(1)).unwrap().key(), Some("a"));
+    assert_eq!(dom.get_scope(ScopeId(2)).unwrap().key(), Some("b"));
+}
@@ -0,0 +1,67 @@
+#![allow(non_snake_case)]
+
+//! Verify the memory reported for each scope
+
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    let renders = cx.use_hook(|| 0);
+    *renders += 1;
+
+    // The child is only mounted on the first render
+    let show_child = *renders == 1;
+
+    cx.render(rsx!(
+        div {
+            show_child.then(|| rsx!( Child {} ))
+        }
+    ))
+}
+
+fn Child(cx: Scope) -> Element {
+    cx.render(rsx!("child"))
+}
+
+#[test]
+fn memory_adds_up_to_the_dom_total() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    let root = dom.scope_memory(ScopeId(0)).unwrap();
+    let child = dom.scope_memory(ScopeId(1)).unwrap();
+    assert!(root.current_frame > 0);
+    assert_eq!(
+        root.total(),
+        root.current_frame + root.previous_frame + root.hooks
+    );
+    assert_eq!(root.total() + child.total(), dom.stats().allocated_bytes);
+}
+
+#[test]
+fn unmounted_scopes_have_no_memory() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    assert!(dom.scope_memory(ScopeId(1)).is_some());
+
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+    assert!(dom.scope_memory(ScopeId(1)).is_none());
+}
+
+#[test]
+fn subtree_memory_includes_descendants() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    let child = dom.scope_memory(ScopeId(1)).unwrap().total();
+    assert_eq!(dom.subtree_memory(ScopeId(0)), dom.stats().allocated_bytes);
+    assert_eq!(dom.subtree_memory(ScopeId(1)), child);
+
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+    assert_eq!(dom.subtree_memory(ScopeId(1)), 0);
+    assert_eq!(
+        dom.subtree_memory(ScopeId(0)),
+        dom.scope_memory(ScopeId(0)).unwrap().total()
+    );
+}
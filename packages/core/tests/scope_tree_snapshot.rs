@@ -0,0 +1,49 @@
+#![allow(non_snake_case)]
+
+//! The scope tree snapshot should list every mounted scope with enough to rebuild the tree
+
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx! {
+        Parent {}
+    })
+}
+
+fn Parent(cx: Scope) -> Element {
+    cx.render(rsx! {
+        Child {}
+        Child {}
+    })
+}
+
+fn Child(cx: Scope) -> Element {
+    cx.render(rsx!(div {}))
+}
+
+#[test]
+fn snapshot_lists_every_scope() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    dom.mark_dirty(ScopeId(1));
+    let _ = dom.render_immediate();
+
+    let snapshot = dom.scope_tree_snapshot();
+    let ids: Vec<_> = snapshot.iter().map(|scope| scope.id).collect();
+    assert_eq!(ids, [ScopeId(0), ScopeId(1), ScopeId(2), ScopeId(3)]);
+
+    let parent = &snapshot[1];
+    assert_eq!(parent.name, "Parent");
+    assert_eq!(parent.parent, Some(ScopeId(0)));
+    assert_eq!(parent.height, 1);
+    assert_eq!(parent.render_count, 2);
+    assert!(parent.memory.total() > 0);
+
+    for child in &snapshot[2..] {
+        assert_eq!(child.name, "Child");
+        assert_eq!(child.parent, Some(ScopeId(1)));
+        assert_eq!(child.height, 2);
+    }
+
+    assert_eq!(snapshot[0].parent, None);
+}
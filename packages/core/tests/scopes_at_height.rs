@@ -0,0 +1,56 @@
+#![allow(non_snake_case)]
+
+//! Verify that scopes can be looked up by their depth in the tree
+
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!(
+        Parent {}
+        Parent {}
+    ))
+}
+
+fn Parent(cx: Scope) -> Element {
+    cx.render(rsx!(Child {}))
+}
+
+fn Child(cx: Scope) -> Element {
+    cx.render(rsx!("child"))
+}
+
+#[test]
+fn groups_scopes_by_height() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    assert_eq!(dom.scopes_at_height(0), vec![ScopeId(0)]);
+
+    let parents = dom.scopes_at_height(1);
+    let children = dom.scopes_at_height(2);
+    assert_eq!(parents.len(), 2);
+    assert_eq!(children.len(), 2);
+
+    for id in children {
+        assert_eq!(dom.get_scope(id).unwrap().name(), "Child");
+    }
+
+    assert!(dom.scopes_at_height(3).is_empty());
+}
+
+#[test]
+fn walks_ancestors_to_root() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    for child in dom.scopes_at_height(2) {
+        let parent = dom.get_scope(child).unwrap().parent().unwrap();
+        assert_eq!(
+            dom.ancestors(child).collect::<Vec<_>>(),
+            vec![parent, ScopeId(0)]
+        );
+    }
+
+    assert_eq!(dom.ancestors(ScopeId(0)).count(), 0);
+    assert_eq!(dom.ancestors(ScopeId(100)).count(), 0);
+}
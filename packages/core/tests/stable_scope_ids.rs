@@ -0,0 +1,46 @@
+#![allow(non_snake_case)]
+
+//! With stable scope ids, removed scopes shouldn't give their ids to new scopes
+
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    let renders = cx.use_hook(|| 0);
+    *renders += 1;
+
+    // The child is unmounted on the second render and mounted again on the third
+    let show_child = *renders != 2;
+
+    cx.render(rsx!(
+        div {
+            show_child.then(|| rsx!( Child {} ))
+        }
+    ))
+}
+
+fn Child(cx: Scope) -> Element {
+    cx.render(rsx!("child"))
+}
+
+fn remount_child(dom: &mut VirtualDom) -> Vec<ScopeId> {
+    let _ = dom.rebuild();
+    for _ in 0..2 {
+        dom.mark_dirty(ScopeId(0));
+        let _ = dom.render_immediate();
+    }
+
+    dom.scopes_at_height(1)
+}
+
+#[test]
+fn removed_ids_are_reused_by_default() {
+    let mut dom = VirtualDom::new(app);
+    assert_eq!(remount_child(&mut dom), [ScopeId(1)]);
+}
+
+#[test]
+fn stable_ids_are_never_reused() {
+    let mut dom = VirtualDom::new(app).with_stable_scope_ids(true);
+    assert_eq!(remount_child(&mut dom), [ScopeId(2)]);
+    assert!(dom.get_scope(ScopeId(1)).is_none());
+}
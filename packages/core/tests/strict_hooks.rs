@@ -0,0 +1,45 @@
+//! Strict hooks mode should stay out of the way of components that use hooks properly
+
+use dioxus::prelude::*;
+
+#[test]
+fn consistent_hooks_pass() {
+    fn app(cx: Scope) -> Element {
+        let count = cx.use_hook(|| 0);
+        let name = cx.use_hook(|| "dioxus");
+        *count += 1;
+
+        cx.render(rsx!( div { "{name} {count}" } ))
+    }
+
+    let mut dom = VirtualDom::new(app).with_strict_hooks(true);
+    let _ = dom.rebuild();
+
+    for _ in 0..3 {
+        dom.mark_dirty(ScopeId(0));
+        let _ = dom.render_immediate();
+    }
+
+    assert_eq!(dom.base_scope().generation(), 4);
+}
+
+#[test]
+#[should_panic(expected = "used 1 hooks in this render but 2 in the last render")]
+fn conditional_hooks_panic() {
+    fn app(cx: Scope) -> Element {
+        let count = cx.use_hook(|| 0);
+        *count += 1;
+
+        if *count == 1 {
+            cx.use_hook(|| "only on the first render");
+        }
+
+        cx.render(rsx!( div { "{count}" } ))
+    }
+
+    let mut dom = VirtualDom::new(app).with_strict_hooks(true);
+    let _ = dom.rebuild();
+
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+}
@@ -1,5 +1,5 @@
 use dioxus::core::ElementId;
-use dioxus::core::{Mutation::*, SuspenseContext};
+use dioxus::core::{Mutation::*, SuspenseContext, SuspenseId};
 use dioxus::prelude::*;
 use std::future::IntoFuture;
 use std::rc::Rc;
@@ -41,6 +41,29 @@ async fn it_works() {
     dom.wait_for_work().await;
 }
 
+#[tokio::test]
+async fn suspended_leaves_are_reported() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    // app -> suspense_boundary -> async_child
+    assert!(!dom.is_suspended(ScopeId(0)));
+    assert!(!dom.is_suspended(ScopeId(1)));
+    assert!(dom.is_suspended(ScopeId(2)));
+}
+
+#[tokio::test]
+async fn suspense_dependencies_point_at_boundary() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    // The async child is held up by the boundary right above it, not the root
+    assert_eq!(
+        dom.suspense_dependencies(),
+        vec![(SuspenseId(0), ScopeId(2), ScopeId(1))]
+    );
+}
+
 fn app(cx: Scope) -> Element {
     cx.render(rsx!(
         div {
@@ -0,0 +1,110 @@
+//! Suspended components can be aborted or resolved by hand
+
+use dioxus::core::{SnapshotNode, SuspenseContext};
+use dioxus::prelude::*;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::task::Poll;
+
+thread_local! {
+    static READY: Cell<bool> = Cell::new(false);
+    static DROPPED: Cell<bool> = Cell::new(false);
+}
+
+struct DropGuard;
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        DROPPED.with(|dropped| dropped.set(true));
+    }
+}
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!(
+        div {
+            "Waiting for child..."
+            suspense_boundary {}
+        }
+    ))
+}
+
+fn suspense_boundary(cx: Scope) -> Element {
+    cx.use_hook(|| {
+        cx.provide_context(Rc::new(SuspenseContext::new(cx.scope_id())));
+    });
+
+    cx.render(rsx!(async_child {}))
+}
+
+async fn async_child(cx: Scope<'_>) -> Element {
+    let _guard = DropGuard;
+
+    // Only ready when the test says so, without ever waking up on its own
+    std::future::poll_fn(|_| {
+        if READY.with(Cell::get) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await;
+
+    cx.render(rsx!("ready"))
+}
+
+fn only_leaf(dom: &VirtualDom) -> dioxus::core::SuspenseId {
+    let dependencies = dom.suspense_dependencies();
+    assert_eq!(dependencies.len(), 1);
+    dependencies[0].0
+}
+
+#[test]
+fn aborted_leaves_drop_their_future() {
+    READY.with(|ready| ready.set(false));
+    DROPPED.with(|dropped| dropped.set(false));
+
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    let leaf = only_leaf(&dom);
+
+    assert!(dom.abort_suspense(leaf));
+    assert!(DROPPED.with(Cell::get));
+    assert!(!dom.is_suspended(ScopeId(2)));
+    assert_eq!(
+        dom.capture_render(ScopeId(2)).roots,
+        [SnapshotNode::Placeholder]
+    );
+
+    // There's nothing left to abort
+    assert!(!dom.abort_suspense(leaf));
+}
+
+#[test]
+fn resolved_leaves_render_if_they_are_ready() {
+    READY.with(|ready| ready.set(false));
+
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    READY.with(|ready| ready.set(true));
+    assert!(dom.resolve_suspense(only_leaf(&dom)));
+    assert_eq!(
+        dom.capture_render(ScopeId(2)).roots,
+        [SnapshotNode::Text("ready".to_string())]
+    );
+}
+
+#[test]
+fn resolved_leaves_fall_back_to_the_placeholder() {
+    READY.with(|ready| ready.set(false));
+
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    assert!(!dom.resolve_suspense(only_leaf(&dom)));
+    assert!(!dom.is_suspended(ScopeId(2)));
+    assert_eq!(
+        dom.capture_render(ScopeId(2)).roots,
+        [SnapshotNode::Placeholder]
+    );
+}
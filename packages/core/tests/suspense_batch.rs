@@ -0,0 +1,57 @@
+//! Suspended components that wake up together should resolve in a stable order
+
+use dioxus::prelude::*;
+use std::cell::RefCell;
+use tokio::sync::oneshot;
+
+thread_local! {
+    static FIRST: RefCell<Option<oneshot::Receiver<()>>> = RefCell::new(None);
+    static SECOND: RefCell<Option<oneshot::Receiver<()>>> = RefCell::new(None);
+    static RESOLVED: RefCell<Vec<&'static str>> = RefCell::new(Vec::new());
+}
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!(
+        div {
+            first {}
+            second {}
+        }
+    ))
+}
+
+async fn first(cx: Scope<'_>) -> Element {
+    let rx = FIRST.with(|rx| rx.borrow_mut().take().unwrap());
+    rx.await.unwrap();
+    RESOLVED.with(|resolved| resolved.borrow_mut().push("first"));
+    cx.render(rsx!("first"))
+}
+
+async fn second(cx: Scope<'_>) -> Element {
+    let rx = SECOND.with(|rx| rx.borrow_mut().take().unwrap());
+    rx.await.unwrap();
+    RESOLVED.with(|resolved| resolved.borrow_mut().push("second"));
+    cx.render(rsx!("second"))
+}
+
+#[tokio::test]
+async fn leaves_resolve_in_the_order_they_suspended() {
+    let (first_tx, first_rx) = oneshot::channel();
+    let (second_tx, second_rx) = oneshot::channel();
+    FIRST.with(|rx| *rx.borrow_mut() = Some(first_rx));
+    SECOND.with(|rx| *rx.borrow_mut() = Some(second_rx));
+
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    assert!(dom.is_suspended(ScopeId(1)));
+    assert!(dom.is_suspended(ScopeId(2)));
+
+    // Wake the second component up before the first
+    second_tx.send(()).unwrap();
+    first_tx.send(()).unwrap();
+
+    dom.wait_for_work().await;
+
+    RESOLVED.with(|resolved| assert_eq!(*resolved.borrow(), ["first", "second"]));
+    assert!(!dom.is_suspended(ScopeId(1)));
+    assert!(!dom.is_suspended(ScopeId(2)));
+}
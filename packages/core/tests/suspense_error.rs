@@ -0,0 +1,50 @@
+//! Async components that fail should hand their error to the nearest error boundary
+
+use dioxus::core::{ErrorBoundary, SnapshotNode, SuspenseContext};
+use dioxus::prelude::*;
+use std::rc::Rc;
+use std::time::Duration;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!(
+        div {
+            suspense_boundary {}
+        }
+    ))
+}
+
+fn suspense_boundary(cx: Scope) -> Element {
+    cx.use_hook(|| {
+        cx.provide_context(Rc::new(SuspenseContext::new(cx.scope_id())));
+    });
+
+    cx.render(rsx!(failing_child {}))
+}
+
+async fn failing_child(cx: Scope<'_>) -> Result<Element, String> {
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    Err::<(), _>("fetch failed".to_string())?;
+    Ok(cx.render(rsx!("loaded")))
+}
+
+#[tokio::test]
+async fn failed_futures_reach_the_error_boundary() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    assert!(dom.is_suspended(ScopeId(2)));
+
+    tokio::time::timeout(Duration::from_secs(5), dom.wait_for_work())
+        .await
+        .expect("the child should resolve");
+
+    let boundary = dom.base_scope().has_context::<Rc<ErrorBoundary>>().unwrap();
+    let captured = boundary.take_error().unwrap();
+    assert_eq!(captured.scope, ScopeId(2));
+    assert_eq!(format!("{:?}", captured.error), r#""fetch failed""#);
+
+    assert!(!dom.is_suspended(ScopeId(2)));
+    assert_eq!(
+        dom.capture_render(ScopeId(2)).roots,
+        [SnapshotNode::Placeholder]
+    );
+}
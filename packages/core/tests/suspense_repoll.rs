@@ -0,0 +1,79 @@
+//! A suspended component that keeps waking itself up shouldn't hang the VirtualDom
+
+use dioxus::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::Poll;
+
+static POLLS: AtomicUsize = AtomicUsize::new(0);
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!(spinning_child {}))
+}
+
+async fn spinning_child(cx: Scope<'_>) -> Element {
+    std::future::poll_fn(|cx| {
+        POLLS.fetch_add(1, Ordering::SeqCst);
+        cx.waker().wake_by_ref();
+        Poll::<()>::Pending
+    })
+    .await;
+
+    cx.render(rsx!("never"))
+}
+
+#[test]
+fn self_waking_futures_are_parked() {
+    let mut dom = VirtualDom::new(app).with_suspense_repoll_limit(10);
+    let _ = dom.rebuild();
+
+    // The first poll plus ten repolls
+    assert_eq!(POLLS.load(Ordering::SeqCst), 11);
+    assert_eq!(dom.suspense_repolls(), 10);
+    assert!(dom.is_suspended(ScopeId(1)));
+}
+
+thread_local! {
+    static WAKER: std::cell::RefCell<Option<std::task::Waker>> = Default::default();
+}
+
+static COALESCED_POLLS: AtomicUsize = AtomicUsize::new(0);
+
+fn coalescing_app(cx: Scope) -> Element {
+    cx.render(rsx!(waking_child {}))
+}
+
+async fn waking_child(cx: Scope<'_>) -> Element {
+    std::future::poll_fn(|cx| match COALESCED_POLLS.fetch_add(1, Ordering::SeqCst) {
+        // Wake up right away, which is handled by polling again while rendering
+        0 => {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+        // Wait for the test to wake us up
+        1 => {
+            WAKER.with(|waker| *waker.borrow_mut() = Some(cx.waker().clone()));
+            Poll::Pending
+        }
+        _ => Poll::Ready(()),
+    })
+    .await;
+
+    cx.render(rsx!("done"))
+}
+
+#[test]
+fn handled_wakeups_are_not_polled_again() {
+    let mut dom = VirtualDom::new(coalescing_app);
+    let _ = dom.rebuild();
+    assert_eq!(COALESCED_POLLS.load(Ordering::SeqCst), 2);
+
+    // The wakeup from the first poll is still queued, but it was already handled
+    dom.process_events();
+    assert_eq!(COALESCED_POLLS.load(Ordering::SeqCst), 2);
+    assert!(dom.is_suspended(ScopeId(1)));
+
+    WAKER.with(|waker| waker.borrow_mut().take().unwrap().wake());
+    dom.process_events();
+    assert_eq!(COALESCED_POLLS.load(Ordering::SeqCst), 3);
+    assert!(!dom.is_suspended(ScopeId(1)));
+}
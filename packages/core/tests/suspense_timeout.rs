@@ -0,0 +1,83 @@
+//! Suspended components that never resolve should be replaced with a placeholder after the suspense timeout
+
+use dioxus::core::{SnapshotNode, SuspenseContext};
+use dioxus::prelude::*;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+thread_local! {
+    static DROPPED: Cell<bool> = Cell::new(false);
+}
+
+struct DropGuard;
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        DROPPED.with(|dropped| dropped.set(true));
+    }
+}
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!(
+        div {
+            "Waiting for child..."
+            suspense_boundary {}
+        }
+    ))
+}
+
+fn suspense_boundary(cx: Scope) -> Element {
+    cx.use_hook(|| {
+        cx.provide_context(Rc::new(SuspenseContext::new(cx.scope_id())));
+    });
+
+    cx.render(rsx!(async_child {}))
+}
+
+async fn async_child(cx: Scope<'_>) -> Element {
+    let _guard = DropGuard;
+    std::future::pending::<()>().await;
+    cx.render(rsx!("never"))
+}
+
+#[tokio::test]
+async fn pending_components_time_out() {
+    let mut dom = VirtualDom::new(app).with_suspense_timeout(Duration::from_millis(50));
+    let _ = dom.rebuild();
+    assert!(dom.is_suspended(ScopeId(2)));
+
+    tokio::time::timeout(Duration::from_secs(5), dom.wait_for_work())
+        .await
+        .expect("the suspense timeout should wake the VirtualDom");
+
+    assert!(!dom.is_suspended(ScopeId(2)));
+    assert_eq!(
+        dom.capture_render(ScopeId(2)).roots,
+        [SnapshotNode::Placeholder]
+    );
+}
+
+#[tokio::test]
+async fn timed_out_futures_are_dropped() {
+    let mut dom = VirtualDom::new(app).with_suspense_timeout(Duration::from_millis(50));
+    let _ = dom.rebuild();
+    assert!(!DROPPED.with(Cell::get));
+
+    tokio::time::timeout(Duration::from_secs(5), dom.wait_for_work())
+        .await
+        .expect("the suspense timeout should wake the VirtualDom");
+
+    assert!(DROPPED.with(Cell::get));
+    assert!(dom.suspense_dependencies().is_empty());
+}
+
+#[tokio::test]
+async fn components_stay_suspended_without_a_timeout() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    let waited = tokio::time::timeout(Duration::from_millis(100), dom.wait_for_work()).await;
+    assert!(waited.is_err());
+    assert!(dom.is_suspended(ScopeId(2)));
+}
@@ -40,3 +40,201 @@ fn app(cx: Scope) -> Element {
 
     cx.render(rsx!(()))
 }
+
+#[tokio::test]
+async fn cancelled_wait_keeps_work() {
+    fn idle(cx: Scope) -> Element {
+        cx.render(rsx!(div {}))
+    }
+
+    let mut dom = VirtualDom::new(idle);
+    let _ = dom.rebuild();
+
+    // Nothing to do yet, so the timeout wins and the wait is dropped
+    tokio::select! {
+        _ = dom.wait_for_work() => panic!("there should be no work"),
+        _ = tokio::time::sleep(Duration::from_millis(10)) => {}
+    };
+
+    // The update that arrives afterwards still wakes up the next wait
+    dom.base_scope().schedule_update()();
+
+    tokio::select! {
+        _ = dom.wait_for_work() => {}
+        _ = tokio::time::sleep(Duration::from_millis(500)) => panic!("the update was lost"),
+    };
+}
+
+#[tokio::test]
+async fn run_applies_updates_until_shutdown() {
+    fn ticker(cx: Scope) -> Element {
+        let count = cx.use_hook(|| 0);
+        *count += 1;
+
+        cx.use_hook(|| {
+            let update = cx.schedule_update();
+            cx.spawn(async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                update();
+            });
+        });
+
+        cx.render(rsx!( div { "{count}" } ))
+    }
+
+    let mut dom = VirtualDom::new(ticker);
+    let _ = dom.rebuild();
+
+    let mut texts = Vec::new();
+    dom.run(
+        |mutations| {
+            for edit in mutations.edits {
+                if let dioxus::core::Mutation::SetText { value, .. } = edit {
+                    texts.push(value.to_string());
+                }
+            }
+        },
+        tokio::time::sleep(Duration::from_millis(200)),
+    )
+    .await;
+
+    assert_eq!(texts, vec!["2".to_string()]);
+}
+
+#[test]
+fn shutdown_drops_tasks_and_hooks() {
+    use std::{cell::RefCell, rc::Rc};
+
+    struct Guard(&'static str, Rc<RefCell<Vec<&'static str>>>);
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            self.1.borrow_mut().push(self.0);
+        }
+    }
+
+    fn app(cx: Scope<Rc<RefCell<Vec<&'static str>>>>) -> Element {
+        cx.use_hook(|| {
+            let guard = Guard("task", cx.props.clone());
+            cx.spawn(async move {
+                let _guard = guard;
+                std::future::pending::<()>().await;
+            });
+            Guard("hook", cx.props.clone())
+        });
+
+        cx.render(rsx!(div {}))
+    }
+
+    let dropped = Rc::new(RefCell::new(Vec::new()));
+    let mut dom = VirtualDom::new_with_props(app, dropped.clone());
+    let _ = dom.rebuild();
+
+    dom.shutdown();
+    assert_eq!(*dropped.borrow(), ["task", "hook"]);
+
+    // Shutting down again (or dropping the dom) doesn't drop anything twice
+    dom.shutdown();
+    drop(dom);
+    assert_eq!(dropped.borrow().len(), 2);
+}
+
+#[test]
+fn task_limit_drops_oldest_tasks() {
+    fn app(cx: Scope) -> Element {
+        for _ in 0..5 {
+            cx.spawn(std::future::pending());
+        }
+
+        cx.render(rsx!(()))
+    }
+
+    let mut dom = VirtualDom::new(app).with_task_limit(2);
+    let _ = dom.rebuild();
+    assert_eq!(dom.stats().tasks, 2);
+
+    // Every render spawns more tasks, but the scope stays at the limit
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+    assert_eq!(dom.stats().tasks, 2);
+
+    let mut unlimited = VirtualDom::new(app);
+    let _ = unlimited.rebuild();
+    assert_eq!(unlimited.stats().tasks, 5);
+}
+
+#[test]
+fn unmounting_a_scope_cancels_its_tasks() {
+    use std::{cell::RefCell, rc::Rc};
+
+    struct Guard(Rc<RefCell<bool>>);
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            *self.0.borrow_mut() = true;
+        }
+    }
+
+    fn app(cx: Scope<Rc<RefCell<bool>>>) -> Element {
+        let renders = cx.use_hook(|| 0);
+        *renders += 1;
+
+        // The child is only mounted on the first render
+        cx.render(rsx! {
+            (*renders == 1).then(|| rsx!( task_owner { dropped: cx.props.clone() } ))
+        })
+    }
+
+    #[inline_props]
+    fn task_owner(cx: Scope, dropped: Rc<RefCell<bool>>) -> Element {
+        cx.use_hook(|| {
+            let guard = Guard(dropped.clone());
+            cx.spawn(async move {
+                let _guard = guard;
+                std::future::pending::<()>().await;
+            });
+        });
+
+        cx.render(rsx!(div {}))
+    }
+
+    let dropped = Rc::new(RefCell::new(false));
+    let mut dom = VirtualDom::new_with_props(app, dropped.clone());
+    let _ = dom.rebuild();
+    assert_eq!(dom.stats().tasks, 1);
+    assert!(!*dropped.borrow());
+
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+    assert_eq!(dom.stats().tasks, 0);
+    assert!(*dropped.borrow());
+}
+
+#[tokio::test]
+async fn wait_for_task_waits_for_a_spawned_effect() {
+    use std::{cell::Cell, rc::Rc};
+
+    fn app(cx: Scope<Rc<Cell<bool>>>) -> Element {
+        cx.use_hook(|| {
+            let done = cx.props.clone();
+            cx.spawn(async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                done.set(true);
+            });
+        });
+
+        cx.render(rsx!(div {}))
+    }
+
+    let done = Rc::new(Cell::new(false));
+    let mut dom = VirtualDom::new_with_props(app, done.clone());
+    let _ = dom.rebuild();
+
+    let tasks = dom.base_scope().spawned_task_ids();
+    assert_eq!(tasks.len(), 1);
+
+    dom.wait_for_task(tasks[0]).await;
+    assert!(done.get());
+    assert!(dom.base_scope().spawned_task_ids().is_empty());
+
+    // Waiting on a task that already finished returns right away
+    dom.wait_for_task(tasks[0]).await;
+}
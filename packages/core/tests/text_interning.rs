@@ -0,0 +1,56 @@
+//! Formatted text should be kept across renders when interning is on, without changing what's rendered
+
+use dioxus::core::{ElementId, Mutation::*};
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    let renders = cx.use_hook(|| 0);
+    *renders += 1;
+    let label = "unchanged";
+
+    cx.render(rsx!(
+        div { "{label} text" }
+        div { "render {renders}" }
+    ))
+}
+
+#[test]
+fn interned_text_renders_the_same() {
+    let mut dom = VirtualDom::new(app).with_text_interning(1024);
+    let _ = dom.rebuild();
+    assert_eq!(
+        dom.base_scope().interned_text_bytes(),
+        "unchanged text".len() + "render 1".len()
+    );
+
+    // The unchanged text is found in the cache, so only the new text is added
+    dom.mark_dirty(ScopeId(0));
+    let edits = dom.render_immediate().santize();
+    assert_eq!(
+        edits.edits,
+        [SetText { value: "render 2", id: ElementId(4) }]
+    );
+    assert_eq!(
+        dom.base_scope().interned_text_bytes(),
+        "unchanged text".len() + "render 1".len() + "render 2".len()
+    );
+}
+
+#[test]
+fn full_caches_fall_back_to_the_frame() {
+    let mut dom = VirtualDom::new(app).with_text_interning("unchanged text".len());
+    let _ = dom.rebuild();
+
+    for render in 2..5 {
+        dom.mark_dirty(ScopeId(0));
+        let edits = dom.render_immediate().santize();
+        let text = format!("render {}", render);
+        assert_eq!(edits.edits, [SetText { value: &text, id: ElementId(4) }]);
+    }
+
+    assert_eq!(
+        dom.base_scope().interned_text_bytes(),
+        "unchanged text".len()
+    );
+    assert_eq!(VirtualDom::new(app).base_scope().interned_text_bytes(), 0);
+}
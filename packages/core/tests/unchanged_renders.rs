@@ -0,0 +1,27 @@
+//! Verify that renders without any mutations are counted per scope
+#![cfg(feature = "profile")]
+
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    let count = cx.use_hook(|| 0);
+    *count += 1;
+
+    // Only every other render actually changes the text
+    let shown = *count / 2;
+    cx.render(rsx!( div { "{shown}" } ))
+}
+
+#[test]
+fn counts_renders_without_changes() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    for _ in 0..4 {
+        dom.mark_dirty(ScopeId(0));
+        let _ = dom.render_immediate();
+    }
+
+    // count goes 2, 3, 4, 5, so shown goes 1, 1, 2, 2 - the renders to 3 and 5 changed nothing
+    assert_eq!(dom.base_scope().unchanged_renders(), 2);
+}
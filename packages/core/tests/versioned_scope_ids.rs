@@ -0,0 +1,43 @@
+#![allow(non_snake_case)]
+
+//! Verify that versioned scope ids don't alias recycled scope slots
+
+use dioxus::core::VersionedScopeId;
+use dioxus::prelude::*;
+
+fn app(cx: Scope) -> Element {
+    // Mount the child on every other render so its slot gets freed and reused
+    if cx.generation() % 2 == 0 {
+        cx.render(rsx!(Child {}))
+    } else {
+        cx.render(rsx!(div {}))
+    }
+}
+
+fn Child(cx: Scope) -> Element {
+    cx.render(rsx!("child"))
+}
+
+#[test]
+fn recycled_slot_rejects_old_id() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    let old: VersionedScopeId = dom.versioned_scope_id(ScopeId(1)).unwrap();
+    assert!(dom.get_scope_versioned(old).is_some());
+
+    // Unmount the child
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+    assert!(dom.get_scope_versioned(old).is_none());
+
+    // Mount a new child into the same slot
+    dom.mark_dirty(ScopeId(0));
+    let _ = dom.render_immediate();
+    assert!(dom.get_scope(ScopeId(1)).is_some());
+    assert!(dom.get_scope_versioned(old).is_none());
+
+    let new = dom.versioned_scope_id(ScopeId(1)).unwrap();
+    assert_ne!(old, new);
+    assert_eq!(dom.get_scope_versioned(new).unwrap().scope_id(), ScopeId(1));
+}
@@ -0,0 +1,313 @@
+//! Bake a directory of assets into a Dioxus desktop app at compile time.
+//!
+//! Call [`embed_dir`] from your build script and `dioxus_desktop::embed_assets!` from your app with the same directory:
+//!
+//! ```rust, ignore
+//! // build.rs
+//! fn main() {
+//!     dioxus_desktop_embed::embed_dir("assets").unwrap();
+//! }
+//!
+//! // main.rs
+//! let config = Config::new().with_asset_provider(dioxus_desktop::embed_assets!("assets"));
+//! ```
+//!
+//! This crate is separate from `dioxus-desktop` so build scripts don't have to build the webview and windowing crates
+//! just to list some files. Its only dependencies are the content type sniffer and, with the `precompress` feature,
+//! the compressors.
+//!
+//! Every file is read and hashed when the build script runs, and included with `include_bytes!` when the app
+//! compiles. Both scale with the total size of the directory, so large media can noticeably slow down builds and
+//! always grows the binary by its full size. Precompressed copies add the time to compress every text asset at the
+//! highest quality, and the size of the copies to the binary. The build script reruns whenever anything in the
+//! directory changes.
+
+#[doc(hidden)]
+pub mod mime;
+
+use std::fmt::Write;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Embed every file in a directory, relative to the crate's manifest, so `dioxus_desktop::embed_assets!` can serve it.
+///
+/// This is [`EmbedDir`] with the default options.
+pub fn embed_dir(dir: &str) -> io::Result<()> {
+    EmbedDir::new(dir).embed()
+}
+
+/// A directory to embed from a build script, with options for how it's embedded
+#[derive(Clone, Debug)]
+pub struct EmbedDir {
+    dir: String,
+    precompressed: bool,
+}
+
+impl EmbedDir {
+    /// Embed a directory relative to the crate's manifest, like `assets`.
+    ///
+    /// The directory can't be absolute or climb out of the crate with `..`, since its name is also where the list of
+    /// assets is written inside `OUT_DIR`.
+    pub fn new(dir: impl Into<String>) -> Self {
+        Self {
+            dir: dir.into(),
+            precompressed: false,
+        }
+    }
+
+    /// Store brotli and gzip copies of every text asset, like scripts, styles and SVGs, that compresses smaller than
+    /// the original. Requests that accept one of the encodings are answered with the copy, without compressing
+    /// anything at runtime.
+    ///
+    /// Media and archives are already compressed and are never given copies. Defaults to false.
+    #[cfg(feature = "precompress")]
+    pub fn with_precompressed(mut self, precompressed: bool) -> Self {
+        self.precompressed = precompressed;
+        self
+    }
+
+    /// Write the list of assets into `OUT_DIR` along with their content types and hashes, and tell cargo to rerun the
+    /// build script when the directory changes.
+    ///
+    /// This must be called from a build script.
+    pub fn embed(self) -> io::Result<()> {
+        check_dir(&self.dir)?;
+
+        let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR").ok_or_else(|| {
+            io::Error::new(io::ErrorKind::Other, "embed_dir must run in a build script")
+        })?;
+        let out_dir = std::env::var_os("OUT_DIR").ok_or_else(|| {
+            io::Error::new(io::ErrorKind::Other, "embed_dir must run in a build script")
+        })?;
+
+        let root = Path::new(&manifest_dir).join(&self.dir);
+        println!("cargo:rerun-if-changed={}", root.display());
+
+        let out_dir = Path::new(&out_dir).join("dioxus-assets");
+        let out = out_dir.join(format!("{}.rs", self.dir));
+        if let Some(parent) = out.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let precompressed_dir = out_dir.join("precompressed");
+        let precompressed_dir = self.precompressed.then(|| precompressed_dir.as_path());
+
+        std::fs::write(out, generate_asset_list(&root, precompressed_dir)?)
+    }
+}
+
+/// Make sure the list of assets for a directory is written inside `OUT_DIR/dioxus-assets`
+fn check_dir(dir: &str) -> io::Result<()> {
+    let mut components = Path::new(dir).components().peekable();
+    let inside = components.peek().is_some()
+        && components.all(|part| matches!(part, Component::Normal(_) | Component::CurDir));
+
+    if inside {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "embed_dir needs a directory inside the crate without `..`, not {:?}",
+                dir
+            ),
+        ))
+    }
+}
+
+/// Generate the Rust source for a sorted slice of `EmbeddedAsset`s covering every file under `root`.
+///
+/// With a `precompressed_dir`, compressed copies of the text assets are written there and included alongside them.
+fn generate_asset_list(root: &Path, precompressed_dir: Option<&Path>) -> io::Result<String> {
+    let mut files = Vec::new();
+    collect_files(root, &mut files)?;
+
+    let mut assets = Vec::new();
+    for file in files {
+        let canonical = file.canonicalize()?;
+        let relative = file
+            .strip_prefix(root)
+            .expect("files to be collected from under the root")
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let bytes = std::fs::read(&canonical)?;
+        let mime = mime::get_mime_from_bytes(&relative, &bytes, false);
+        let hash = fnv1a(&bytes);
+        let etag = format!("\"{:016x}\"", hash);
+
+        let (brotli, gzip) = match precompressed_dir {
+            Some(dir) if mime::is_compressible(mime) => precompress(dir, hash, &bytes)?,
+            _ => (None, None),
+        };
+
+        assets.push((relative, canonical, mime, etag, brotli, gzip));
+    }
+
+    assets.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut out = String::from("&[\n");
+    for (relative, canonical, mime, etag, brotli, gzip) in assets {
+        writeln!(
+            out,
+            "    EmbeddedAsset {{ path: {:?}, bytes: include_bytes!({:?}), mime: {:?}, etag: {:?}, brotli: {}, gzip: {} }},",
+            relative,
+            canonical.to_string_lossy(),
+            mime,
+            etag,
+            include_optional(brotli.as_deref()),
+            include_optional(gzip.as_deref())
+        )
+        .unwrap();
+    }
+    out.push(']');
+
+    Ok(out)
+}
+
+fn include_optional(path: Option<&Path>) -> String {
+    match path {
+        Some(path) => format!(
+            "Some(include_bytes!({:?}) as &[u8])",
+            path.to_string_lossy()
+        ),
+        None => "None".to_string(),
+    }
+}
+
+/// Write the brotli and gzip copies of an asset into `dir`, named after the hash of its contents so identical assets
+/// share copies. A copy that isn't smaller than the original is skipped.
+#[cfg(feature = "precompress")]
+fn precompress(
+    dir: &Path,
+    hash: u64,
+    bytes: &[u8],
+) -> io::Result<(Option<PathBuf>, Option<PathBuf>)> {
+    use std::io::Write;
+
+    std::fs::create_dir_all(dir)?;
+
+    let mut brotli = brotli::CompressorWriter::new(Vec::new(), 4096, 11, 22);
+    brotli.write_all(bytes)?;
+    let brotli = brotli.into_inner();
+
+    let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+    gzip.write_all(bytes)?;
+    let gzip = gzip.finish()?;
+
+    let write_smaller = |compressed: Vec<u8>, extension: &str| -> io::Result<Option<PathBuf>> {
+        if compressed.len() >= bytes.len() {
+            return Ok(None);
+        }
+
+        let path = dir.join(format!("{:016x}.{}", hash, extension));
+        std::fs::write(&path, compressed)?;
+        Ok(Some(path))
+    };
+
+    Ok((write_smaller(brotli, "br")?, write_smaller(gzip, "gz")?))
+}
+
+#[cfg(not(feature = "precompress"))]
+fn precompress(
+    _dir: &Path,
+    _hash: u64,
+    _bytes: &[u8],
+) -> io::Result<(Option<PathBuf>, Option<PathBuf>)> {
+    Ok((None, None))
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// A hash that's stable across builds and platforms, unlike the std hasher
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// A directory for one test, unique to the process and deleted when it's dropped
+#[cfg(test)]
+struct TestDir(PathBuf);
+
+#[cfg(test)]
+impl TestDir {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "dioxus-desktop-embed-{}-{}",
+            name,
+            std::process::id()
+        ));
+        _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    fn write(&self, relative: &str, contents: impl AsRef<[u8]>) {
+        let path = self.0.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn generated_list_is_sorted_and_typed() {
+    let dir = TestDir::new("sorted");
+    dir.write("styles/main.css", "body {}");
+    dir.write("app.js", "main()");
+    let root = &dir.0;
+
+    let list = generate_asset_list(root, None).unwrap();
+
+    let app = list.find("path: \"app.js\"").unwrap();
+    let styles = list.find("path: \"styles/main.css\"").unwrap();
+    assert!(app < styles);
+    assert!(list.contains("mime: \"text/css; charset=utf-8\""));
+    assert!(list.contains(&format!("etag: \"\\\"{:016x}\\\"\"", fnv1a(b"body {}"))));
+    assert!(list.contains("brotli: None, gzip: None"));
+}
+
+#[test]
+fn dirs_outside_of_the_crate_are_rejected() {
+    assert!(check_dir("assets").is_ok());
+    assert!(check_dir("./assets/images").is_ok());
+
+    for dir in ["", "..", "../assets", "assets/../../escape", "/assets"] {
+        let err = check_dir(dir).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
+
+#[test]
+#[cfg(feature = "precompress")]
+fn text_assets_get_precompressed_copies() {
+    let dir = TestDir::new("precompressed");
+    dir.write("assets/app.js", "main();\n".repeat(100));
+    dir.write("assets/logo.png", b"\x89PNG\r\n\x1a\n");
+
+    let list = generate_asset_list(&dir.0.join("assets"), Some(&dir.0.join("out"))).unwrap();
+    let (app, logo) = list.split_at(list.find("path: \"logo.png\"").unwrap());
+
+    assert!(app.contains("brotli: Some(include_bytes!("));
+    assert!(app.contains("gzip: Some(include_bytes!("));
+    assert!(logo.contains("brotli: None, gzip: None"));
+}
@@ -0,0 +1,108 @@
+//! Content types for assets, shared by the desktop protocol and the assets embedded by [`crate::embed_dir`] so both
+//! serve a file with the same type.
+
+/// Get the mime type for an asset that only exists in memory, sniffing its contents before falling back to the extension
+pub fn get_mime_from_bytes(trimmed: &str, bytes: &[u8], unknown_as_html: bool) -> &'static str {
+    if let Some(mime) = get_trusted_mime(trimmed) {
+        return mime;
+    }
+
+    match infer::get(bytes).map(|f| f.mime_type()) {
+        Some(t) if t != "text/plain" => with_charset(t),
+        _ => get_mime_by_ext(trimmed, unknown_as_html),
+    }
+}
+
+/// Get the mime type for formats whose extension is trusted over sniffing their contents.
+///
+/// SVGs sniff as XML, and `WebAssembly.instantiateStreaming` refuses anything not served as `application/wasm`.
+pub fn get_trusted_mime(trimmed: &str) -> Option<&'static str> {
+    match trimmed.rsplit_once('.').map(|(_, ext)| ext) {
+        Some("svg") => Some("image/svg+xml"),
+        Some("wasm") => Some("application/wasm"),
+        _ => None,
+    }
+}
+
+/// Files that are conventionally plain text even though they don't have an extension
+const TEXT_FILE_NAMES: &[&str] = &["LICENSE", "README", "CHANGELOG"];
+
+/// Get the mime type from a URI using its extension.
+///
+/// Unknown extensions are served as `application/octet-stream` so the webview never renders them as a page, unless
+/// `unknown_as_html` opts into treating them as HTML.
+pub fn get_mime_by_ext(trimmed: &str, unknown_as_html: bool) -> &'static str {
+    let file_name = trimmed.rsplit('/').next().unwrap_or(trimmed);
+    if TEXT_FILE_NAMES.contains(&file_name) {
+        return "text/plain; charset=utf-8";
+    }
+
+    let suffix = file_name.rsplit_once('.').map(|(_, ext)| ext);
+    let mime = match suffix {
+        Some("avif") => "image/avif",
+        Some("bin") => "application/octet-stream",
+        Some("css") => "text/css",
+        Some("csv") => "text/csv",
+        Some("gif") => "image/gif",
+        Some("html") => "text/html",
+        Some("ico") => "image/vnd.microsoft.icon",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("js") => "text/javascript",
+        Some("json") => "application/json",
+        Some("jsonld") => "application/ld+json",
+        Some("map") => "application/json",
+        Some("mjs") => "text/javascript",
+        Some("mp3") => "audio/mpeg",
+        Some("mp4") => "video/mp4",
+        Some("ogg") => "audio/ogg",
+        Some("otf") => "font/otf",
+        Some("png") => "image/png",
+        Some("rtf") => "application/rtf",
+        Some("svg") => "image/svg+xml",
+        Some("ttf") => "font/ttf",
+        Some("wasm") => "application/wasm",
+        Some("wav") => "audio/wav",
+        Some("webp") => "image/webp",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        // Only assume HTML for a TLD like `dioxus:://dioxuslabs.app` | `dioxus://hello.com` when asked to
+        Some(_) if unknown_as_html => "text/html",
+        // https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/MIME_types/Common_types
+        // using octet stream according to this:
+        Some(_) | None => "application/octet-stream",
+    };
+
+    with_charset(mime)
+}
+
+/// Declare that text formats are UTF-8, so the webview doesn't guess the encoding of non-ASCII content. Binary formats
+/// are left alone.
+pub fn with_charset(mime: &'static str) -> &'static str {
+    match mime {
+        "application/json" => "application/json; charset=utf-8",
+        "application/ld+json" => "application/ld+json; charset=utf-8",
+        "application/xml" => "application/xml; charset=utf-8",
+        "text/css" => "text/css; charset=utf-8",
+        "text/csv" => "text/csv; charset=utf-8",
+        "text/html" => "text/html; charset=utf-8",
+        "text/javascript" => "text/javascript; charset=utf-8",
+        "text/plain" => "text/plain; charset=utf-8",
+        "text/xml" => "text/xml; charset=utf-8",
+        mime => mime,
+    }
+}
+
+/// Check if a content type is worth compressing. Media and archives are compressed already.
+pub fn is_compressible(mime: &str) -> bool {
+    let mime = mime.split(';').next().unwrap_or_default().trim();
+
+    mime.starts_with("text/")
+        || matches!(
+            mime,
+            "application/javascript"
+                | "application/json"
+                | "application/xml"
+                | "application/wasm"
+                | "image/svg+xml"
+        )
+}
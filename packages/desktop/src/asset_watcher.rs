@@ -0,0 +1,153 @@
+//! Tell the webview to reload stylesheets and images when their files change on disk.
+//!
+//! The protocol handler already reads assets straight from disk, so a re-fetch is all the webview needs to see an
+//! edit. The watcher polls the modification times of every file under the asset roots rather than pulling in a
+//! platform file watcher, since it only ever runs in debug builds.
+
+use crate::desktop_context::UserWindowEvent;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use wry::application::event_loop::EventLoopProxy;
+
+/// How often the asset roots are scanned for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Watch the asset roots from a background thread until the event loop shuts down.
+///
+/// Each root is paired with the URL prefix it's served under, which is empty for the resource directories. Without
+/// any roots there's nothing to watch, so no thread is started.
+pub(crate) fn watch(roots: Vec<(String, PathBuf)>, proxy: EventLoopProxy<UserWindowEvent>) {
+    if roots.is_empty() {
+        log::warn!("Asset hot reloading needs a resource directory or an asset mount to watch");
+        return;
+    }
+
+    std::thread::spawn(move || {
+        let mut known = scan(&roots);
+
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+
+            let current = scan(&roots);
+            let changed: Vec<String> = current
+                .iter()
+                .filter(|(path, modified)| known.get(*path) != Some(modified))
+                .map(|(path, _)| path.clone())
+                .collect();
+            known = current;
+
+            if !changed.is_empty()
+                && proxy
+                    .send_event(UserWindowEvent::AssetsChanged(changed))
+                    .is_err()
+            {
+                // The event loop is gone, so there's no webview left to reload
+                break;
+            }
+        }
+    });
+}
+
+/// Get the modification time of every file under the roots, keyed by the path it's requested with
+fn scan(roots: &[(String, PathBuf)]) -> HashMap<String, SystemTime> {
+    let mut files = HashMap::new();
+    for (prefix, root) in roots {
+        scan_dir(prefix, root, &mut files);
+    }
+    files
+}
+
+fn scan_dir(prefix: &str, dir: &Path, files: &mut HashMap<String, SystemTime>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+
+        // A root can be the crate itself, so skip the build output and hidden directories
+        if name.starts_with('.') || name == "target" {
+            continue;
+        }
+
+        let path = match prefix {
+            "" => name,
+            prefix => format!("{}/{}", prefix, name),
+        };
+
+        match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => scan_dir(&path, &entry.path(), files),
+            Ok(metadata) => {
+                if let Ok(modified) = metadata.modified() {
+                    files.insert(path, modified);
+                }
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+/// Build the script that swaps the changed stylesheets and images for fresh copies.
+///
+/// A query string that changes every time keeps the webview from reusing its cached copy.
+pub(crate) fn reload_script(changed: &[String]) -> String {
+    format!(
+        r#"(function(changed) {{
+            const matches = (url) => {{
+                try {{
+                    const path = decodeURIComponent(new URL(url, location.href).pathname).replace(/^\/+/, "");
+                    return changed.includes(path);
+                }} catch (e) {{
+                    return false;
+                }}
+            }};
+            const bust = (url) => {{
+                const fresh = new URL(url, location.href);
+                fresh.searchParams.set("dioxus-reload", Date.now());
+                return fresh.href;
+            }};
+            document.querySelectorAll("link[rel=stylesheet]").forEach((link) => {{
+                if (matches(link.href)) link.href = bust(link.href);
+            }});
+            document.querySelectorAll("img").forEach((img) => {{
+                if (matches(img.src)) img.src = bust(img.src);
+            }});
+        }})({});"#,
+        serde_json::to_string(changed).expect("paths to always serialize")
+    )
+}
+
+#[test]
+fn changed_files_are_keyed_by_request_path() {
+    let root = crate::fixture::TestDir::new("asset-watcher");
+    root.write("css/main.css", "body {}");
+    root.write("target/out.css", "body {}");
+
+    let files = scan(&[
+        (String::new(), root.to_path_buf()),
+        ("app/assets".to_string(), root.to_path_buf()),
+    ]);
+
+    assert!(files.contains_key("css/main.css"));
+    assert!(files.contains_key("app/assets/css/main.css"));
+    assert!(!files.keys().any(|path| path.contains("target")));
+}
+
+#[test]
+fn only_configured_roots_are_watched() {
+    assert!(crate::Config::new().watched_roots().is_empty());
+
+    let roots = crate::Config::new()
+        .with_resource_directory("public")
+        .with_asset_mount("/app/assets/", "shared")
+        .watched_roots();
+    assert_eq!(
+        roots,
+        [
+            (String::new(), PathBuf::from("public")),
+            ("app/assets".to_string(), PathBuf::from("shared")),
+        ]
+    );
+}
@@ -0,0 +1,170 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::{Arc, RwLock};
+use wry::http::Response;
+
+/// Turns the contents of an asset into new contents and the content type to serve them with
+pub(crate) type AssetTransform = Rc<dyn Fn(&[u8]) -> std::io::Result<(Vec<u8>, &'static str)>>;
+
+/// Builds the whole response for a path, or returns `None` to let the filesystem serve it
+pub(crate) type AssetResolver = Rc<dyn Fn(&str) -> Option<Response<Vec<u8>>>>;
+
+/// Builds the whole response for a path asynchronously, like by querying a database, or returns `None` to serve it as
+/// usual
+pub(crate) type AsyncAssetResolver =
+    Rc<dyn Fn(String) -> Pin<Box<dyn Future<Output = Option<Response<Vec<u8>>>>>>>;
+
+/// A source of assets that aren't read from the filesystem, like an archive or a set of bytes baked into the binary.
+///
+/// Providers are checked before the asset directories, in the order they were added with
+/// [`crate::Config::with_asset_provider`].
+pub trait AssetProvider {
+    /// Get the asset at a path relative to the root of the `dioxus://` scheme, like `images/logo.png`.
+    ///
+    /// Return `None` to let the next provider or the filesystem handle the request.
+    fn get(&self, path: &str) -> Option<ProvidedAsset>;
+}
+
+/// The contents of an asset served by an [`AssetProvider`]
+#[derive(Clone, Debug)]
+pub struct ProvidedAsset {
+    /// The raw bytes of the asset
+    pub bytes: Cow<'static, [u8]>,
+
+    /// The content type of the asset.
+    ///
+    /// If this is `None`, the content type is sniffed from the bytes, falling back to the extension of the path.
+    pub mime: Option<Cow<'static, str>>,
+
+    /// A quoted tag that changes whenever the contents do, like `"abc123"`.
+    ///
+    /// If this is set, it's sent as the `ETag` header and requests with a matching `If-None-Match` header are answered
+    /// with a `304 Not Modified`.
+    pub etag: Option<Cow<'static, str>>,
+
+    /// Copies of the asset that were compressed ahead of time, keyed by their `Content-Encoding` like `br` or `gzip`,
+    /// most preferred first.
+    ///
+    /// The first copy in an encoding the request accepts is served in place of `bytes`, with the content type of the
+    /// original.
+    pub precompressed: Vec<(&'static str, Cow<'static, [u8]>)>,
+}
+
+/// An [`AssetProvider`] that serves assets out of memory
+#[derive(Clone, Debug, Default)]
+pub struct MemoryAssets {
+    assets: HashMap<String, ProvidedAsset>,
+}
+
+impl MemoryAssets {
+    /// Create an empty set of assets
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve the bytes at the given path, guessing the content type when it's requested
+    pub fn with_asset(self, path: impl Into<String>, bytes: impl Into<Cow<'static, [u8]>>) -> Self {
+        self.insert(path.into(), bytes.into(), None)
+    }
+
+    /// Serve the bytes at the given path with an explicit content type
+    pub fn with_asset_and_mime(
+        self,
+        path: impl Into<String>,
+        bytes: impl Into<Cow<'static, [u8]>>,
+        mime: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.insert(path.into(), bytes.into(), Some(mime.into()))
+    }
+
+    fn insert(
+        mut self,
+        path: String,
+        bytes: Cow<'static, [u8]>,
+        mime: Option<Cow<'static, str>>,
+    ) -> Self {
+        let path = path.trim_start_matches('/').to_string();
+        self.assets.insert(
+            path,
+            ProvidedAsset {
+                bytes,
+                mime,
+                etag: None,
+                precompressed: Vec::new(),
+            },
+        );
+        self
+    }
+}
+
+impl AssetProvider for MemoryAssets {
+    fn get(&self, path: &str) -> Option<ProvidedAsset> {
+        self.assets.get(path).cloned()
+    }
+}
+
+/// The current theme, used to serve themed variants of assets like `logo.dark.svg` in place of `logo.svg`.
+///
+/// This is a cheap handle that can be cloned and updated from any thread, like when the OS switches between light and
+/// dark mode. Requests made after the theme changes get the variants for the new theme.
+#[derive(Clone, Debug, Default)]
+pub struct AssetTheme {
+    current: Arc<RwLock<Option<String>>>,
+}
+
+impl AssetTheme {
+    /// Create a handle with no theme set, which serves the base assets
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the theme, like `dark` or `light`
+    pub fn set(&self, theme: impl Into<String>) {
+        *self.current.write().unwrap() = Some(theme.into());
+    }
+
+    /// Clear the theme to go back to serving the base assets
+    pub fn clear(&self) {
+        *self.current.write().unwrap() = None;
+    }
+
+    /// Get the current theme
+    pub fn get(&self) -> Option<String> {
+        self.current.read().unwrap().clone()
+    }
+}
+
+/// The set of flags that pick which variant of a flagged asset is served, for things like A/B testing a stylesheet.
+///
+/// This is a cheap handle that can be cloned and shared with any thread. The protocol handler reads the flags on every
+/// request, so enabling or disabling a flag takes effect for the next request without rebuilding the webview. Flags
+/// are behind a lock, so avoid holding onto them across long operations.
+#[derive(Clone, Debug, Default)]
+pub struct AssetFlags {
+    enabled: Arc<RwLock<HashSet<String>>>,
+}
+
+impl AssetFlags {
+    /// Create a handle with no flags enabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable a flag
+    pub fn enable(&self, flag: impl Into<String>) {
+        self.enabled.write().unwrap().insert(flag.into());
+    }
+
+    /// Disable a flag
+    pub fn disable(&self, flag: &str) {
+        self.enabled.write().unwrap().remove(flag);
+    }
+
+    /// Check if a flag is enabled
+    pub fn is_enabled(&self, flag: &str) -> bool {
+        self.enabled.read().unwrap().contains(flag)
+    }
+}
@@ -1,4 +1,20 @@
-use std::path::PathBuf;
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::assets::{
+    AssetFlags, AssetProvider, AssetResolver, AssetTheme, AssetTransform, AsyncAssetResolver,
+};
+#[cfg(feature = "compression")]
+use crate::compression::{Compression, Encoding};
+use crate::protocol::{
+    AssetMount, FlaggedAsset, LoaderScripts, Preload, ProtocolOptions, Redirect, RootElement,
+};
+use crate::protocol_log::{RequestObserver, ServedRequest};
+use std::collections::HashMap;
 
 use wry::application::window::Icon;
 use wry::{
@@ -14,18 +30,55 @@ use wry::{
 pub struct Config {
     pub(crate) window: WindowBuilder,
     pub(crate) file_drop_handler: Option<DropHandler>,
+    pub(crate) ipc_observer: Option<IpcObserver>,
     pub(crate) protocols: Vec<WryProtocol>,
     pub(crate) pre_rendered: Option<String>,
     // pub(crate) event_handler: Option<Box<DynEventHandlerFn>>,
     pub(crate) disable_context_menu: bool,
-    pub(crate) resource_dir: Option<PathBuf>,
+    pub(crate) scheme: Option<String>,
+    pub(crate) resource_dirs: Vec<PathBuf>,
     pub(crate) custom_head: Option<String>,
     pub(crate) custom_index: Option<String>,
+    pub(crate) not_found: Option<String>,
+    pub(crate) forbidden_body: Option<String>,
+    pub(crate) forbidden_content_type: Option<String>,
+    pub(crate) favicon: Option<Vec<u8>>,
     pub(crate) root_name: String,
+    pub(crate) root_element: RootElement,
+    pub(crate) initialize_params: serde_json::Map<String, serde_json::Value>,
+    pub(crate) asset_mounts: Vec<AssetMount>,
+    pub(crate) asset_providers: Vec<Rc<dyn AssetProvider>>,
+    pub(crate) asset_transforms: HashMap<String, AssetTransform>,
+    pub(crate) asset_resolver: Option<AssetResolver>,
+    pub(crate) async_asset_resolver: Option<AsyncAssetResolver>,
+    pub(crate) request_observer: Option<RequestObserver>,
+    pub(crate) provided_assets_only: bool,
+    pub(crate) content_security_policy: Option<String>,
+    pub(crate) loader_scripts: LoaderScripts,
+    pub(crate) interpreter_js: Option<String>,
+    pub(crate) redirects: HashMap<String, Redirect>,
+    pub(crate) asset_theme: Option<AssetTheme>,
+    pub(crate) asset_headers: HashMap<String, Vec<(String, String)>>,
+    pub(crate) asset_flags: AssetFlags,
+    pub(crate) flagged_assets: HashMap<String, Vec<FlaggedAsset>>,
+    pub(crate) server_side_includes: bool,
+    pub(crate) cache_max_age: Option<Duration>,
+    pub(crate) max_response_size: Option<u64>,
+    pub(crate) asset_hot_reload: bool,
+    pub(crate) dev_cors: bool,
+    pub(crate) dev_symlinks: bool,
+    pub(crate) directory_listing: bool,
+    pub(crate) unknown_extensions_as_html: bool,
+    pub(crate) spa_fallback: bool,
+    pub(crate) preloads: HashMap<String, Vec<Preload>>,
+    #[cfg(feature = "compression")]
+    pub(crate) compression: Option<Compression>,
 }
 
 type DropHandler = Box<dyn Fn(&Window, FileDropEvent) -> bool>;
 
+type IpcObserver = Box<dyn Fn(&str)>;
+
 pub(crate) type WryProtocol = (
     String,
     Box<dyn Fn(&HttpRequest<Vec<u8>>) -> WryResult<HttpResponse<Vec<u8>>> + 'static>,
@@ -42,18 +95,369 @@ impl Config {
             window,
             protocols: Vec::new(),
             file_drop_handler: None,
+            ipc_observer: None,
             pre_rendered: None,
             disable_context_menu: !cfg!(debug_assertions),
-            resource_dir: None,
+            scheme: None,
+            resource_dirs: Vec::new(),
             custom_head: None,
             custom_index: None,
+            not_found: None,
+            forbidden_body: None,
+            forbidden_content_type: None,
+            favicon: None,
             root_name: "main".to_string(),
+            root_element: RootElement::default(),
+            initialize_params: Default::default(),
+            asset_mounts: Vec::new(),
+            asset_providers: Vec::new(),
+            asset_transforms: HashMap::new(),
+            asset_resolver: None,
+            async_asset_resolver: None,
+            request_observer: None,
+            provided_assets_only: false,
+            content_security_policy: None,
+            loader_scripts: LoaderScripts::default(),
+            interpreter_js: None,
+            redirects: HashMap::new(),
+            asset_theme: None,
+            asset_headers: HashMap::new(),
+            asset_flags: AssetFlags::default(),
+            flagged_assets: HashMap::new(),
+            server_side_includes: false,
+            cache_max_age: None,
+            max_response_size: None,
+            asset_hot_reload: false,
+            dev_cors: false,
+            dev_symlinks: false,
+            directory_listing: false,
+            unknown_extensions_as_html: false,
+            spa_fallback: false,
+            preloads: HashMap::new(),
+            #[cfg(feature = "compression")]
+            compression: None,
         }
     }
 
     /// set the directory from which assets will be searched in release mode
-    pub fn with_resource_directory(mut self, path: impl Into<PathBuf>) -> Self {
-        self.resource_dir = Some(path.into());
+    pub fn with_resource_directory(self, path: impl Into<PathBuf>) -> Self {
+        self.with_resource_directories([path])
+    }
+
+    /// Search several directories for assets, in order, like a user-editable theme directory before the built-in one.
+    ///
+    /// Each request is served from the first directory that has the file. A file found through a symlink that leads
+    /// out of its own directory is skipped rather than served, even if it lands inside another of the directories.
+    pub fn with_resource_directories(
+        mut self,
+        paths: impl IntoIterator<Item = impl Into<PathBuf>>,
+    ) -> Self {
+        self.resource_dirs = paths.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Register the app's protocol under a different scheme than `dioxus`, like `myapp` to load the app from
+    /// `myapp://index.html/`.
+    ///
+    /// The index and module loader use relative paths, so they work under any scheme. Absolute URLs built for the
+    /// protocol, like the targets of redirects, use the configured scheme.
+    pub fn with_custom_scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.scheme = Some(scheme.into());
+        self
+    }
+
+    /// Serve the assets under a URL prefix out of a specific directory.
+    ///
+    /// For example, mounting `app/assets` to `./shared` serves `dioxus://index.html/app/assets/logo.png` from
+    /// `./shared/logo.png`. The same directory can be mounted under several prefixes. Requests can't escape the mounted
+    /// directory, even with `..` in the path.
+    ///
+    /// If the prefixes of several mounts match a request, the longest prefix wins. Requests that don't match any mount
+    /// are served from the resource directory.
+    pub fn with_asset_mount(mut self, prefix: impl Into<String>, root: impl Into<PathBuf>) -> Self {
+        self.asset_mounts.push(AssetMount {
+            prefix: prefix.into().trim_matches('/').to_string(),
+            root: root.into(),
+        });
+        self
+    }
+
+    /// Serve assets from a provider instead of the filesystem.
+    ///
+    /// Providers are checked in the order they were added, before any asset directory.
+    pub fn with_asset_provider(mut self, provider: impl AssetProvider + 'static) -> Self {
+        self.asset_providers.push(Rc::new(provider));
+        self
+    }
+
+    /// Only serve assets from the [`AssetProvider`]s and the asset resolver, never from the filesystem. Off by default.
+    ///
+    /// Combined with [`crate::embed_assets!`] this builds a single portable executable: the asset root next to the
+    /// executable is never looked up, and requests nothing provides are answered with a `404` instead of reading from
+    /// disk. Paths that try to climb out of the root with `..` are answered with a `403`, like they are on disk.
+    pub fn with_provided_assets_only(mut self, enabled: bool) -> Self {
+        self.provided_assets_only = enabled;
+        self
+    }
+
+    /// Build the response for a path yourself before it's looked up on the filesystem, like serving a user-uploaded
+    /// image by its key without writing it to disk first.
+    ///
+    /// The resolver gets the path relative to the root of the `dioxus://` scheme, like `uploads/42`, and its response
+    /// is sent as-is. It's called after the [`AssetProvider`]s, and returning `None` lets the asset directories serve
+    /// the request as usual. Setting a new resolver replaces the old one.
+    pub fn with_asset_resolver(
+        mut self,
+        resolver: impl Fn(&str) -> Option<HttpResponse<Vec<u8>>> + 'static,
+    ) -> Self {
+        self.asset_resolver = Some(Rc::new(resolver));
+        self
+    }
+
+    /// Build the response for a path with a future, like reading an asset out of a database, before anything else
+    /// tries to serve it.
+    ///
+    /// The resolver gets the path relative to the root of the `dioxus://` scheme, and returning `None` lets the request
+    /// be served as usual. The future is driven on the thread that handles the request rather than the tokio runtime,
+    /// so send work that needs the runtime to it over a channel and await the answer. Wry still waits for the response
+    /// before handling the next request, so a slow resolver holds up the other assets on the page.
+    pub fn with_async_asset_resolver<F, Fut>(mut self, resolver: F) -> Self
+    where
+        F: Fn(String) -> Fut + 'static,
+        Fut: Future<Output = Option<HttpResponse<Vec<u8>>>> + 'static,
+    {
+        self.async_asset_resolver = Some(Rc::new(move |path| Box::pin(resolver(path))));
+        self
+    }
+
+    /// Get told about every request the protocol answers: the path, the status and size of the response, and whether
+    /// it was served from the filesystem, a provider, the index, or nothing at all.
+    ///
+    /// The observer is called on the event loop thread right before each response is sent, so keep it quick.
+    pub fn with_request_observer(mut self, observer: impl Fn(&ServedRequest) + 'static) -> Self {
+        self.request_observer = Some(Rc::new(observer));
+        self
+    }
+
+    /// Transform every asset with the given extension before serving it, like compiling `scss` to CSS.
+    ///
+    /// The transform gets the contents of the file and returns the new contents along with the content type to serve
+    /// them as. Results are cached until the file's modification time changes. If the transform fails, the request is
+    /// answered with a `500` and the error message.
+    ///
+    /// This only applies to assets read from the filesystem, not from an [`AssetProvider`].
+    pub fn with_asset_transform(
+        mut self,
+        extension: impl Into<String>,
+        transform: impl Fn(&[u8]) -> std::io::Result<(Vec<u8>, &'static str)> + 'static,
+    ) -> Self {
+        let extension = extension.into().trim_start_matches('.').to_string();
+        self.asset_transforms.insert(extension, Rc::new(transform));
+        self
+    }
+
+    /// Redirect requests for one asset path to another, like `old-name.js` to `new-name.js`.
+    ///
+    /// Permanent redirects are answered with a `301` and temporary ones with a `302`. Redirects are checked before any
+    /// asset provider or directory, so the old path doesn't need to exist.
+    pub fn with_redirect(
+        mut self,
+        from: impl Into<String>,
+        to: impl Into<String>,
+        permanent: bool,
+    ) -> Self {
+        let from = from.into().trim_start_matches('/').to_string();
+        let to = to.into().trim_start_matches('/').to_string();
+        self.redirects.insert(from, Redirect { to, permanent });
+        self
+    }
+
+    /// Serve themed variants of assets from the filesystem based on the current theme of the handle.
+    ///
+    /// When the theme is set to `dark`, a request for `logo.svg` is served from `logo.dark.svg` if it exists and from
+    /// `logo.svg` otherwise. Keep a clone of the handle to update the theme when the OS theme changes.
+    pub fn with_asset_theme(mut self, theme: AssetTheme) -> Self {
+        self.asset_theme = Some(theme);
+        self
+    }
+
+    /// Add a header to every response for an asset path, like `Cross-Origin-Resource-Policy` on `app.wasm`.
+    ///
+    /// Headers added here are applied last, so they replace any header Dioxus sets on its own, like `Content-Type`.
+    /// Invalid header names or values are skipped with a warning.
+    pub fn with_asset_header(
+        mut self,
+        path: impl Into<String>,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        let path = path.into().trim_start_matches('/').to_string();
+        self.asset_headers
+            .entry(path)
+            .or_default()
+            .push((name.into(), value.into()));
+        self
+    }
+
+    /// Compress responses for webviews that send an `Accept-Encoding` header allowing one of `encodings`.
+    ///
+    /// `encodings` are in order of preference, so the first one the request accepts is used. Bodies smaller than
+    /// `min_size` bytes are sent as they are. Only text-like content is compressed, like HTML, JavaScript, CSS, JSON,
+    /// SVG, and wasm. Images, media, and fonts are compressed already and partial responses to `Range` requests have
+    /// to match their `Content-Range`, so they're never compressed.
+    ///
+    /// ```rust, ignore
+    /// Config::new().with_compression([Encoding::Brotli, Encoding::Gzip], 1024)
+    /// ```
+    #[cfg(feature = "compression")]
+    pub fn with_compression(
+        mut self,
+        encodings: impl IntoIterator<Item = Encoding>,
+        min_size: usize,
+    ) -> Self {
+        self.compression = Some(Compression {
+            encodings: encodings.into_iter().collect(),
+            min_size,
+        });
+        self
+    }
+
+    /// Tell the webview to start loading `path` as soon as it receives `entry`, with a `Link: rel=preload` header.
+    ///
+    /// `destination` is the kind of resource, like `style`, `script`, `font`, or `image`. Use an empty entry for the
+    /// index page. Preloads for the same entry are sent in a single header, in the order they were added.
+    ///
+    /// Chromium-based webviews (WebView2 on Windows) honor preload headers. WebKit-based webviews (macOS, iOS, and
+    /// Linux) may ignore them, in which case the dependencies are loaded when the entry references them as usual.
+    pub fn with_preload(
+        mut self,
+        entry: impl Into<String>,
+        path: impl Into<String>,
+        destination: impl Into<String>,
+    ) -> Self {
+        let entry = entry.into().trim_start_matches('/').to_string();
+        self.preloads.entry(entry).or_default().push(Preload {
+            path: path.into().trim_start_matches('/').to_string(),
+            destination: destination.into(),
+        });
+        self
+    }
+
+    /// Use a shared set of flags to pick between the variants added with [`Config::with_flagged_asset`].
+    ///
+    /// Keep a clone of the handle to enable or disable flags at runtime, from any thread.
+    pub fn with_asset_flags(mut self, flags: AssetFlags) -> Self {
+        self.asset_flags = flags;
+        self
+    }
+
+    /// Serve `variant` in place of `path` while `flag` is enabled, like `new-ui.css` in place of `old-ui.css`.
+    ///
+    /// The variant is resolved like any other request, so it can come from a provider, a mount, or the resource
+    /// directory. If several variants of a path have their flag enabled, the one added first wins.
+    pub fn with_flagged_asset(
+        mut self,
+        path: impl Into<String>,
+        flag: impl Into<String>,
+        variant: impl Into<String>,
+    ) -> Self {
+        let path = path.into().trim_start_matches('/').to_string();
+        self.flagged_assets
+            .entry(path)
+            .or_default()
+            .push(FlaggedAsset {
+                flag: flag.into(),
+                variant: variant.into().trim_start_matches('/').to_string(),
+            });
+        self
+    }
+
+    /// Expand `<!--#include file="header.html" -->` directives in HTML files served from disk. Off by default.
+    ///
+    /// Included paths are relative to the file that includes them and can't escape its asset directory. Includes
+    /// may be nested a few levels deep, and directives that can't be expanded are replaced with an HTML comment.
+    pub fn with_server_side_includes(mut self, enabled: bool) -> Self {
+        self.server_side_includes = enabled;
+        self
+    }
+
+    /// Reload stylesheets and images in the webview when their files change on disk. Off by default.
+    ///
+    /// Every file under the resource directories and asset mounts is checked for changes a couple of times a second,
+    /// and `<link rel="stylesheet">` and `<img>` elements that point at a changed file fetch it again without
+    /// reloading the page. Only directories set with [`Config::with_resource_directory`] or an asset mount are
+    /// watched. The default asset root is the whole crate directory in development, so nothing is watched without
+    /// one. This only has an effect in debug builds, so it's safe to leave on in release builds.
+    pub fn with_asset_hot_reload(mut self, enabled: bool) -> Self {
+        self.asset_hot_reload = enabled;
+        self
+    }
+
+    /// Let pages from any origin fetch assets from the app, like browser-based dev tooling. Off by default.
+    ///
+    /// Every response gets `Access-Control-Allow-*` headers, and `OPTIONS` preflight requests are answered with a
+    /// `204`. This lets any page read everything the protocol serves, so only turn it on while developing.
+    pub fn with_dev_cors(mut self, enabled: bool) -> Self {
+        self.dev_cors = enabled;
+        self
+    }
+
+    /// Serve assets that are symlinks to files outside of the asset directories, like a directory linked in from
+    /// elsewhere in a monorepo. Off by default.
+    ///
+    /// Requested paths still can't use `..` to leave the asset directories. This only has an effect in debug builds,
+    /// so release builds always refuse assets that resolve outside of their directory.
+    pub fn with_dev_symlinks(mut self, enabled: bool) -> Self {
+        self.dev_symlinks = enabled;
+        self
+    }
+
+    /// List the contents of asset directories when one is requested instead of a file, to check what the protocol
+    /// can see when an asset doesn't load. Off by default.
+    ///
+    /// Only directories inside the asset directories can be listed. This only has an effect in debug builds.
+    pub fn with_directory_listing(mut self, enabled: bool) -> Self {
+        self.directory_listing = enabled;
+        self
+    }
+
+    /// Serve files with an extension the protocol doesn't recognize as HTML instead of `application/octet-stream`.
+    /// Off by default.
+    ///
+    /// This lets pages with URLs like `dioxus://hello.com` load, where the "extension" is really a TLD. Any unknown
+    /// file would be rendered as a page too, so only turn it on if your assets are named that way.
+    pub fn with_unknown_extensions_as_html(mut self, enabled: bool) -> Self {
+        self.unknown_extensions_as_html = enabled;
+        self
+    }
+
+    /// Serve the index page for paths that don't match an asset, so deep links into a client-side router like
+    /// `dioxus://index.html/users/42` load the app. Off by default.
+    ///
+    /// Only paths without a file extension fall back to the index, so a missing image or script is still a `404`.
+    pub fn with_spa_fallback(mut self, enabled: bool) -> Self {
+        self.spa_fallback = enabled;
+        self
+    }
+
+    /// Read no more than `bytes` of a file from disk for a single response.
+    ///
+    /// The webview's protocol handler has to answer with the whole body at once, so a large video would otherwise be
+    /// read into memory in full. With a limit, audio and video files and any request with a `Range` header are sent as
+    /// `206 Partial Content` responses of at most `bytes`, and the webview fetches the rest as it plays. Other files,
+    /// like scripts and stylesheets, can't be loaded in parts, so they're always sent whole.
+    pub fn with_max_response_size(mut self, bytes: u64) -> Self {
+        self.max_response_size = Some(bytes);
+        self
+    }
+
+    /// Let the webview reuse files served from disk for `max_age` without asking for them again.
+    ///
+    /// Files are always sent with a weak `ETag` built from their size and modification time, and requests whose
+    /// `If-None-Match` matches it are answered with an empty `304 Not Modified`. Without a max age the files are sent
+    /// with `Cache-Control: no-cache`, so the webview checks the `ETag` before every reuse and always sees edits.
+    pub fn with_cache_max_age(mut self, max_age: Duration) -> Self {
+        self.cache_max_age = Some(max_age);
         self
     }
 
@@ -95,6 +499,15 @@ impl Config {
         self
     }
 
+    /// Observe every IPC message the webview sends, before it's parsed and dispatched.
+    ///
+    /// This is a single point for logging or auditing the raw messages, including malformed ones that are dropped
+    /// afterwards. The observer can't change or consume the message - it's handled as usual once the observer returns.
+    pub fn with_ipc_observer(mut self, observer: impl Fn(&str) + 'static) -> Self {
+        self.ipc_observer = Some(Box::new(observer));
+        self
+    }
+
     /// Set a custom protocol
     pub fn with_custom_protocol<F>(mut self, name: String, handler: F) -> Self
     where
@@ -110,9 +523,53 @@ impl Config {
         self
     }
 
+    /// Send a `Content-Security-Policy` header with the index page.
+    ///
+    /// The inline script that loads the interpreter gets a random nonce every time the index is served, and the nonce
+    /// is added to the policy's `script-src` directive (or a new one if the policy doesn't have it), so the app keeps
+    /// working without allowing `unsafe-inline`. Scripts added with [`Config::with_custom_head`] or a custom index
+    /// aren't given the nonce, so the policy has to allow them some other way.
+    ///
+    /// ```rust, ignore
+    /// Config::new().with_content_security_policy("default-src 'self'; img-src 'self' data:")
+    /// ```
+    pub fn with_content_security_policy(mut self, policy: impl Into<String>) -> Self {
+        self.content_security_policy = Some(policy.into());
+        self
+    }
+
+    /// Run a script right before the interpreter is loaded, like a polyfill or a global error handler.
+    ///
+    /// The script is inlined into the same `<script>` tag as the interpreter, so it gets the nonce from
+    /// [`Config::with_content_security_policy`] too. It must not contain a closing `</script>` tag.
+    pub fn with_script_before_loader(mut self, script: impl Into<String>) -> Self {
+        self.loader_scripts.before = Some(script.into());
+        self
+    }
+
+    /// Run a script right after the interpreter is created and the app is asked to initialize, like reporting
+    /// startup telemetry.
+    ///
+    /// Like [`Config::with_script_before_loader`], the script is inlined into the loader's `<script>` tag.
+    pub fn with_script_after_loader(mut self, script: impl Into<String>) -> Self {
+        self.loader_scripts.after = Some(script.into());
+        self
+    }
+
+    /// Replace the bundled interpreter that applies mutations to the DOM, like a patched copy with experimental
+    /// operations.
+    ///
+    /// The replacement is served from `index.js` and inlined into the module loader in place of the bundled one, so it
+    /// has to define an `Interpreter` class and the `serializeIpcMessage` function the loader calls.
+    pub fn with_interpreter_js(mut self, interpreter: impl Into<String>) -> Self {
+        self.interpreter_js = Some(interpreter.into());
+        self
+    }
+
     /// Inject additional content into the document's HEAD.
     ///
-    /// This is useful for loading CSS libraries, JS libraries, etc.
+    /// This is useful for loading CSS libraries, JS libraries, etc. With a [`Config::with_custom_index`], the content
+    /// replaces a `<!-- CUSTOM HEAD -->` comment if the index has one, and otherwise goes right before `</head>`.
     pub fn with_custom_head(mut self, head: String) -> Self {
         self.custom_head = Some(head);
         self
@@ -122,13 +579,44 @@ impl Config {
     ///
     /// Make sure your index.html is valid HTML.
     ///
-    /// Dioxus injects some loader code into the closing body tag. Your document
-    /// must include a body element!
+    /// Dioxus injects some loader code in place of a `<!-- MODULE LOADER -->`
+    /// comment, or before the last closing body tag if there isn't one. Content
+    /// from [`Config::with_custom_head`] is injected into the head, so include a
+    /// head element to use both.
     pub fn with_custom_index(mut self, index: String) -> Self {
         self.custom_index = Some(index);
         self
     }
 
+    /// Serve this HTML as the body of `404` responses for missing assets instead of a plain `Not Found`.
+    pub fn with_not_found_page(mut self, html: impl Into<String>) -> Self {
+        self.not_found = Some(html.into());
+        self
+    }
+
+    /// Serve these bytes as `favicon.ico`, which the webview requests on its own.
+    ///
+    /// Without one, a `favicon.ico` in the asset directories is served like any other asset, and if there isn't one
+    /// either, a default Dioxus icon is served instead of a `404`.
+    pub fn with_favicon(mut self, icon: impl Into<Vec<u8>>) -> Self {
+        self.favicon = Some(icon.into());
+        self
+    }
+
+    /// Serve this as the body of `403` responses for paths that try to escape the asset directories, instead of a
+    /// plain `Forbidden`.
+    pub fn with_forbidden_body(mut self, body: impl Into<String>) -> Self {
+        self.forbidden_body = Some(body.into());
+        self
+    }
+
+    /// Set the `Content-Type` of `403` responses, like `application/json` for a body set with
+    /// [`Config::with_forbidden_body`]
+    pub fn with_forbidden_content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.forbidden_content_type = Some(content_type.into());
+        self
+    }
+
     /// Set the name of the element that Dioxus will use as the root.
     ///
     /// This is akint to calling React.render() on the element with the specified name.
@@ -136,8 +624,180 @@ impl Config {
         self.root_name = name.into();
         self
     }
+
+    /// Set the tag of the root element in the default index, like `main` or `section`. Defaults to `div`.
+    ///
+    /// The root element always uses the name from [`Config::with_root_name`] as its id. This has no effect if a custom
+    /// index is provided.
+    pub fn with_root_tag(mut self, tag: impl Into<String>) -> Self {
+        self.root_element.tag = tag.into();
+        self
+    }
+
+    /// Set the class of the root element in the default index.
+    pub fn with_root_class(mut self, class: impl Into<String>) -> Self {
+        self.root_element.class = Some(class.into());
+        self
+    }
+
+    /// Add an attribute to the root element in the default index, like `aria-label` or `data-theme`.
+    ///
+    /// The value is escaped, but the name is inserted as-is.
+    pub fn with_root_attribute(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.root_element
+            .attributes
+            .push((name.into(), value.into()));
+        self
+    }
+
+    /// Attach an extra field to the `initialize` message the webview sends once the interpreter has loaded.
+    ///
+    /// This is useful for identifying which webview just initialized in multi-window apps - a window id or session
+    /// token can be passed along without a separate handshake. The value is serialized as JSON into the generated
+    /// loader script.
+    pub fn with_initialize_field(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<serde_json::Value>,
+    ) -> Self {
+        self.initialize_params.insert(key.into(), value.into());
+        self
+    }
+
+    /// Check that every configured asset root exists and can be canonicalized.
+    ///
+    /// Asset roots are otherwise only resolved when a request comes in, which turns packaging mistakes into
+    /// confusing 404s. In debug builds this is called automatically when the app is launched.
+    pub fn validate(&self) -> Result<(), AssetRootError> {
+        let invalid: Vec<_> = self
+            .resource_dirs
+            .iter()
+            .chain(self.asset_mounts.iter().map(|mount| &mount.root))
+            .filter_map(|root| {
+                check_asset_root(root).err().map(|error| InvalidAssetRoot {
+                    path: root.clone(),
+                    error,
+                })
+            })
+            .collect();
+
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            Err(AssetRootError { invalid })
+        }
+    }
+
+    /// The directories [`Config::with_asset_hot_reload`] watches, along with the URL prefix each one is served under
+    #[cfg(debug_assertions)]
+    pub(crate) fn watched_roots(&self) -> Vec<(String, PathBuf)> {
+        self.resource_dirs
+            .iter()
+            .map(|root| (String::new(), root.clone()))
+            .chain(
+                self.asset_mounts
+                    .iter()
+                    .map(|mount| (mount.prefix.clone(), mount.root.clone())),
+            )
+            .collect()
+    }
+
+    /// Copy out everything the `dioxus://` protocol handler needs
+    pub(crate) fn protocol_options(&self) -> ProtocolOptions {
+        ProtocolOptions {
+            scheme: self.scheme.clone(),
+            asset_roots: self.resource_dirs.clone(),
+            custom_head: self.custom_head.clone(),
+            custom_index: self.custom_index.clone(),
+            not_found: self.not_found.clone(),
+            forbidden_body: self.forbidden_body.clone(),
+            forbidden_content_type: self.forbidden_content_type.clone(),
+            favicon: self.favicon.clone(),
+            root_name: self.root_name.clone(),
+            root_element: self.root_element.clone(),
+            initialize_params: self.initialize_params.clone(),
+            asset_mounts: self.asset_mounts.clone(),
+            asset_providers: self.asset_providers.clone(),
+            asset_transforms: self.asset_transforms.clone(),
+            asset_resolver: self.asset_resolver.clone(),
+            async_asset_resolver: self.async_asset_resolver.clone(),
+            request_observer: self.request_observer.clone(),
+            provided_assets_only: self.provided_assets_only,
+            content_security_policy: self.content_security_policy.clone(),
+            loader_scripts: self.loader_scripts.clone(),
+            interpreter_js: self.interpreter_js.clone(),
+            transform_cache: Default::default(),
+            redirects: self.redirects.clone(),
+            asset_theme: self.asset_theme.clone(),
+            asset_headers: self.asset_headers.clone(),
+            asset_flags: self.asset_flags.clone(),
+            flagged_assets: self.flagged_assets.clone(),
+            server_side_includes: self.server_side_includes,
+            cache_max_age: self.cache_max_age,
+            max_response_size: self.max_response_size,
+            dev_cors: self.dev_cors,
+            dev_symlinks: self.dev_symlinks,
+            directory_listing: self.directory_listing,
+            unknown_extensions_as_html: self.unknown_extensions_as_html,
+            spa_fallback: self.spa_fallback,
+            preloads: self.preloads.clone(),
+            #[cfg(feature = "compression")]
+            compression: self.compression.clone(),
+            ..Default::default()
+        }
+    }
 }
 
+fn check_asset_root(root: &Path) -> std::io::Result<()> {
+    let canonical = root.canonicalize()?;
+
+    if !canonical.is_dir() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "asset root is not a directory",
+        ));
+    }
+
+    Ok(())
+}
+
+/// An asset root that could not be resolved.
+#[derive(Debug)]
+pub struct InvalidAssetRoot {
+    /// The path as it was configured
+    pub path: PathBuf,
+
+    /// Why the path could not be used
+    pub error: std::io::Error,
+}
+
+/// The error returned by [`Config::validate`], listing every asset root that could not be resolved.
+#[derive(Debug)]
+pub struct AssetRootError {
+    /// The asset roots that failed validation
+    pub invalid: Vec<InvalidAssetRoot>,
+}
+
+impl fmt::Display for AssetRootError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} asset root(s) could not be resolved:",
+            self.invalid.len()
+        )?;
+        for root in &self.invalid {
+            writeln!(f, "  {}: {}", root.path.display(), root.error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for AssetRootError {}
+
 impl Default for Config {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,147 @@
+use crate::protocol::accepts_encoding;
+use dioxus_desktop_embed::mime::is_compressible;
+use std::io::Write;
+use wry::http::{header::HeaderValue, Request, Response, StatusCode};
+
+/// An algorithm responses can be compressed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// `Content-Encoding: gzip`
+    Gzip,
+
+    /// `Content-Encoding: br`
+    Brotli,
+}
+
+impl Encoding {
+    /// The name of the encoding in the `Accept-Encoding` and `Content-Encoding` headers
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            Encoding::Brotli => "br",
+        }
+    }
+
+    fn compress(&self, bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Encoding::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes)?;
+                encoder.finish()
+            }
+            Encoding::Brotli => {
+                // Quality 5 is a fraction of the cost of the maximum and compresses text nearly as well
+                let mut writer = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+                writer.write_all(bytes)?;
+                Ok(writer.into_inner())
+            }
+        }
+    }
+}
+
+/// How responses are compressed, from [`crate::Config::with_compression`]
+#[derive(Debug, Clone)]
+pub(crate) struct Compression {
+    /// The encodings to use, most preferred first
+    pub encodings: Vec<Encoding>,
+
+    /// Bodies smaller than this are sent as they are since compressing them saves next to nothing
+    pub min_size: usize,
+}
+
+impl Compression {
+    /// Compress the body of a response with the most preferred encoding the request accepts.
+    ///
+    /// Only complete responses of text-like content are compressed. Media and archives are compressed already, and
+    /// partial content has to be sent byte-for-byte to match its `Content-Range`.
+    pub fn apply(&self, request: &Request<Vec<u8>>, response: &mut Response<Vec<u8>>) {
+        if response.status() != StatusCode::OK
+            || response.body().len() < self.min_size
+            || response.headers().contains_key("Content-Encoding")
+        {
+            return;
+        }
+
+        let compressible = response
+            .headers()
+            .get("Content-Type")
+            .and_then(|mime| mime.to_str().ok())
+            .map_or(false, is_compressible);
+
+        if !compressible {
+            return;
+        }
+
+        let accepted = match request
+            .headers()
+            .get("Accept-Encoding")
+            .and_then(|header| header.to_str().ok())
+        {
+            Some(accepted) => accepted,
+            None => return,
+        };
+
+        let encoding = match self
+            .encodings
+            .iter()
+            .find(|encoding| accepts_encoding(accepted, encoding.name()))
+        {
+            Some(encoding) => encoding,
+            None => return,
+        };
+
+        match encoding.compress(response.body()) {
+            Ok(compressed) => {
+                *response.body_mut() = compressed;
+                let headers = response.headers_mut();
+                headers.insert(
+                    "Content-Encoding",
+                    HeaderValue::from_static(encoding.name()),
+                );
+                headers.insert("Vary", HeaderValue::from_static("Accept-Encoding"));
+            }
+            Err(err) => log::warn!("Failed to compress response with {:?}: {}", encoding, err),
+        }
+    }
+}
+
+#[test]
+fn only_large_text_responses_are_compressed() {
+    let compression = Compression {
+        encodings: vec![Encoding::Brotli, Encoding::Gzip],
+        min_size: 16,
+    };
+    let request = Request::builder()
+        .header("Accept-Encoding", "gzip")
+        .body(Vec::new())
+        .unwrap();
+
+    let respond = |mime: &str, body: &[u8]| {
+        let mut response = Response::builder()
+            .header("Content-Type", mime)
+            .body(body.to_vec())
+            .unwrap();
+        compression.apply(&request, &mut response);
+        response
+    };
+
+    let text = "body { color: red; }".repeat(10);
+    let response = respond("text/css", text.as_bytes());
+    assert_eq!(response.headers()["Content-Encoding"], "gzip");
+
+    let mut decoded = String::new();
+    std::io::Read::read_to_string(
+        &mut flate2::read::GzDecoder::new(&response.body()[..]),
+        &mut decoded,
+    )
+    .unwrap();
+    assert_eq!(decoded, text);
+
+    assert!(!respond("text/css", b"body {}")
+        .headers()
+        .contains_key("Content-Encoding"));
+    assert!(!respond("image/png", text.as_bytes())
+        .headers()
+        .contains_key("Content-Encoding"));
+}
@@ -1,4 +1,6 @@
 use crate::desktop_context::{DesktopContext, UserWindowEvent};
+#[cfg(feature = "protocol-stats")]
+use crate::protocol_stats::ProtocolMetrics;
 use dioxus_core::*;
 use dioxus_html::HtmlEvent;
 use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
@@ -30,6 +32,14 @@ pub(super) struct DesktopController {
     #[cfg(debug_assertions)]
     pub(super) templates_tx: UnboundedSender<Template<'static>>,
 
+    // The latest stats of the VirtualDom, refreshed after every render for the stats route
+    #[cfg(debug_assertions)]
+    pub(super) dom_stats: Arc<Mutex<DomStats>>,
+
+    // Timings for the requests served to every webview, read through the DesktopContext
+    #[cfg(feature = "protocol-stats")]
+    pub(super) protocol_metrics: Arc<Mutex<ProtocolMetrics>>,
+
     #[cfg(target_os = "ios")]
     pub(super) views: Vec<*mut Object>,
 }
@@ -51,6 +61,16 @@ impl DesktopController {
         let desktop_context_proxy = proxy.clone();
         let (eval_sender, eval_reciever) = tokio::sync::mpsc::unbounded_channel::<Value>();
 
+        #[cfg(debug_assertions)]
+        let dom_stats = Arc::new(Mutex::new(DomStats::default()));
+        #[cfg(debug_assertions)]
+        let latest_stats = dom_stats.clone();
+
+        #[cfg(feature = "protocol-stats")]
+        let protocol_metrics = Arc::new(Mutex::new(ProtocolMetrics::default()));
+        #[cfg(feature = "protocol-stats")]
+        let context_metrics = protocol_metrics.clone();
+
         std::thread::spawn(move || {
             // We create the runtime as multithreaded, so you can still "tokio::spawn" onto multiple threads
             // I'd personally not require tokio to be built-in to Dioxus-Desktop, but the DX is worse without it
@@ -62,7 +82,12 @@ impl DesktopController {
 
             runtime.block_on(async move {
                 let mut dom = VirtualDom::new_with_props(root, props)
-                    .with_root_context(DesktopContext::new(desktop_context_proxy, eval_reciever));
+                    .with_root_context(DesktopContext::new(
+                        desktop_context_proxy,
+                        eval_reciever,
+                        #[cfg(feature = "protocol-stats")]
+                        context_metrics,
+                    ));
                 {
                     let edits = dom.rebuild();
                     let mut queue = edit_queue.lock().unwrap();
@@ -70,6 +95,11 @@ impl DesktopController {
                     proxy.send_event(UserWindowEvent::EditsReady).unwrap();
                 }
 
+                #[cfg(debug_assertions)]
+                {
+                    *latest_stats.lock().unwrap() = dom.stats();
+                }
+
                 loop {
                     tokio::select! {
                         template = {
@@ -107,6 +137,11 @@ impl DesktopController {
                         .unwrap()
                         .push(serde_json::to_string(&muts).unwrap());
                     let _ = proxy.send_event(UserWindowEvent::EditsReady);
+
+                    #[cfg(debug_assertions)]
+                    {
+                        *latest_stats.lock().unwrap() = dom.stats();
+                    }
                 }
             })
         });
@@ -121,6 +156,10 @@ impl DesktopController {
             event_tx,
             #[cfg(debug_assertions)]
             templates_tx,
+            #[cfg(debug_assertions)]
+            dom_stats,
+            #[cfg(feature = "protocol-stats")]
+            protocol_metrics,
             #[cfg(target_os = "ios")]
             views: vec![],
         }
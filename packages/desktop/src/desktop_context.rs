@@ -1,12 +1,16 @@
 use std::rc::Rc;
 
 use crate::controller::DesktopController;
+#[cfg(feature = "protocol-stats")]
+use crate::protocol_stats::{ProtocolMetrics, ProtocolStats};
 use dioxus_core::ScopeState;
 use serde::de::Error;
 use serde_json::Value;
 use std::future::Future;
 use std::future::IntoFuture;
 use std::pin::Pin;
+#[cfg(feature = "protocol-stats")]
+use std::sync::{Arc, Mutex};
 use wry::application::dpi::LogicalSize;
 use wry::application::event_loop::ControlFlow;
 use wry::application::event_loop::EventLoopProxy;
@@ -42,19 +46,33 @@ pub struct DesktopContext {
     /// The wry/tao proxy to the current window
     pub proxy: ProxyType,
     pub(super) eval_reciever: Rc<tokio::sync::Mutex<tokio::sync::mpsc::UnboundedReceiver<Value>>>,
+    #[cfg(feature = "protocol-stats")]
+    pub(super) protocol_metrics: Arc<Mutex<ProtocolMetrics>>,
 }
 
 impl DesktopContext {
     pub(crate) fn new(
         proxy: ProxyType,
         eval_reciever: tokio::sync::mpsc::UnboundedReceiver<Value>,
+        #[cfg(feature = "protocol-stats")] protocol_metrics: Arc<Mutex<ProtocolMetrics>>,
     ) -> Self {
         Self {
             proxy,
             eval_reciever: Rc::new(tokio::sync::Mutex::new(eval_reciever)),
+            #[cfg(feature = "protocol-stats")]
+            protocol_metrics,
         }
     }
 
+    /// Get the request count, latency percentiles, and status codes of the assets served to the webview so far.
+    ///
+    /// Latencies are calculated over the most recent requests, while the counts cover every request. This is only
+    /// available with the `protocol-stats` feature enabled.
+    #[cfg(feature = "protocol-stats")]
+    pub fn protocol_stats(&self) -> ProtocolStats {
+        self.protocol_metrics.lock().unwrap().stats()
+    }
+
     /// trigger the drag-window event
     ///
     /// Moves the window with the left mouse button until the button is released.
@@ -201,6 +219,9 @@ pub enum UserWindowEvent {
 
     Eval(String),
 
+    /// Files under the asset roots changed, keyed by the path they're requested with
+    AssetsChanged(Vec<String>),
+
     #[cfg(target_os = "ios")]
     PushView(objc_id::ShareId<objc::runtime::Object>),
     #[cfg(target_os = "ios")]
@@ -257,6 +278,16 @@ impl DesktopController {
                     log::warn!("Eval script error: {e}");
                 }
             }
+            AssetsChanged(changed) => {
+                #[cfg(debug_assertions)]
+                if let Err(e) =
+                    webview.evaluate_script(&crate::asset_watcher::reload_script(&changed))
+                {
+                    log::warn!("Reloading changed assets failed: {e}");
+                }
+                #[cfg(not(debug_assertions))]
+                let _ = changed;
+            }
             CursorVisible(state) => window.set_cursor_visible(state),
             CursorGrab(state) => {
                 let _ = window.set_cursor_grab(state);
@@ -0,0 +1,128 @@
+//! Serve assets baked into the binary at compile time.
+//!
+//! The build script half lives in the `dioxus-desktop-embed` crate, so build scripts don't have to build the webview
+//! and windowing crates. Add it as a build dependency, call `embed_dir` from your build script, and
+//! [`crate::embed_assets!`] from your app with the same directory:
+//!
+//! ```rust, ignore
+//! // build.rs
+//! fn main() {
+//!     dioxus_desktop_embed::embed_dir("assets").unwrap();
+//! }
+//!
+//! // main.rs
+//! let config = Config::new().with_asset_provider(dioxus_desktop::embed_assets!("assets"));
+//! ```
+//!
+//! Assets the provider doesn't have still fall back to the filesystem. Add
+//! [`Config::with_provided_assets_only`](crate::Config::with_provided_assets_only) to ship a single executable that
+//! never looks for an assets directory. See the `dioxus-desktop-embed` docs for what embedding costs at build time.
+
+use crate::assets::{AssetProvider, ProvidedAsset};
+use std::borrow::Cow;
+
+/// An asset that was baked into the binary by `dioxus_desktop_embed::embed_dir`
+#[derive(Clone, Copy, Debug)]
+pub struct EmbeddedAsset {
+    /// The path of the asset relative to the embedded directory, like `images/logo.png`
+    pub path: &'static str,
+
+    /// The contents of the asset
+    pub bytes: &'static [u8],
+
+    /// The content type, worked out when the asset was embedded
+    pub mime: &'static str,
+
+    /// A quoted hash of the contents, used to answer conditional requests without re-sending the asset
+    pub etag: &'static str,
+
+    /// The contents compressed with brotli when the asset was embedded, if it was embedded with precompressed copies
+    /// and compressing made it smaller
+    pub brotli: Option<&'static [u8]>,
+
+    /// The contents compressed with gzip when the asset was embedded, like [`EmbeddedAsset::brotli`]
+    pub gzip: Option<&'static [u8]>,
+}
+
+/// An [`AssetProvider`] that serves assets baked into the binary, created with [`crate::embed_assets!`]
+#[derive(Clone, Copy, Debug)]
+pub struct EmbeddedAssets {
+    // Sorted by path so lookups can binary search
+    assets: &'static [EmbeddedAsset],
+}
+
+impl EmbeddedAssets {
+    /// Serve a list of embedded assets. The list must be sorted by path, which `dioxus_desktop_embed::embed_dir` takes
+    /// care of.
+    pub const fn new(assets: &'static [EmbeddedAsset]) -> Self {
+        Self { assets }
+    }
+}
+
+impl AssetProvider for EmbeddedAssets {
+    fn get(&self, path: &str) -> Option<ProvidedAsset> {
+        let idx = self
+            .assets
+            .binary_search_by_key(&path, |asset| asset.path)
+            .ok()?;
+        let asset = &self.assets[idx];
+
+        // Brotli is preferred over gzip, like with precompressed files on disk
+        let precompressed = [("br", asset.brotli), ("gzip", asset.gzip)]
+            .iter()
+            .filter_map(|(encoding, bytes)| Some((*encoding, Cow::Borrowed((*bytes)?))))
+            .collect();
+
+        Some(ProvidedAsset {
+            bytes: Cow::Borrowed(asset.bytes),
+            mime: Some(Cow::Borrowed(asset.mime)),
+            etag: Some(Cow::Borrowed(asset.etag)),
+            precompressed,
+        })
+    }
+}
+
+/// Serve the assets embedded from a directory by `dioxus_desktop_embed::embed_dir` in the build script.
+///
+/// The directory must be the same string that was passed to `embed_dir`.
+#[macro_export]
+macro_rules! embed_assets {
+    ($dir:literal) => {
+        $crate::embed::EmbeddedAssets::new({
+            // The generated list refers to the asset type by name since `$crate` can't be used in an included file
+            use $crate::embed::EmbeddedAsset;
+            include!(concat!(env!("OUT_DIR"), "/dioxus-assets/", $dir, ".rs"))
+        })
+    };
+}
+
+#[test]
+fn embedded_assets_are_served_with_etags() {
+    static ASSETS: &[EmbeddedAsset] = &[
+        EmbeddedAsset {
+            path: "app.js",
+            bytes: b"main()",
+            mime: "text/javascript",
+            etag: "\"1\"",
+            brotli: None,
+            gzip: None,
+        },
+        EmbeddedAsset {
+            path: "styles/main.css",
+            bytes: b"body {}",
+            mime: "text/css",
+            etag: "\"2\"",
+            brotli: None,
+            gzip: Some(b"gzipped" as &[u8]),
+        },
+    ];
+
+    let assets = EmbeddedAssets::new(ASSETS);
+    let css = assets.get("styles/main.css").unwrap();
+    assert_eq!(&*css.bytes, b"body {}");
+    assert_eq!(css.etag.as_deref(), Some("\"2\""));
+    assert_eq!(css.precompressed.len(), 1);
+    assert_eq!(css.precompressed[0].0, "gzip");
+    assert_eq!(&*css.precompressed[0].1, b"gzipped");
+    assert!(assets.get("missing.js").is_none());
+}
@@ -0,0 +1,67 @@
+//! Shared setup for tests that serve files from disk
+
+use crate::protocol::{desktop_handler, ProtocolOptions};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use wry::http::{Request, Response};
+
+/// A directory of files for one test, deleted when it's dropped.
+///
+/// The name is unique to the process and the test, so files left behind by an earlier run or written by another
+/// checkout running its tests at the same time can't show up in it.
+pub(crate) struct TestDir {
+    path: PathBuf,
+}
+
+impl TestDir {
+    pub(crate) fn new(name: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        let path = std::env::temp_dir().join(format!(
+            "dioxus-desktop-{}-{}-{}",
+            name,
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        // A crashed run with the same process id may have left this behind
+        _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+
+        Self { path }
+    }
+
+    /// Write a file relative to the directory, creating its parents first
+    pub(crate) fn write(&self, relative: &str, contents: impl AsRef<[u8]>) -> PathBuf {
+        let path = self.path.join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+}
+
+impl Deref for TestDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Request a path relative to the root of the `dioxus://` scheme, like `styles/main.css`
+pub(crate) fn get(options: &ProtocolOptions, path: &str) -> Response<Vec<u8>> {
+    let request = Request::builder()
+        .uri(format!("dioxus://index.html/{}", path))
+        .body(Vec::new())
+        .unwrap();
+    desktop_handler(&request, options).unwrap()
+}
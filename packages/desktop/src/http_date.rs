@@ -0,0 +1,140 @@
+//! Format and parse the dates used by `Last-Modified` and `If-Modified-Since`.
+//!
+//! Only the preferred IMF-fixdate format from RFC 7231, like `Sun, 06 Nov 1994 08:49:37 GMT`, is produced or
+//! understood. A date in one of the obsolete formats is treated like a missing header, which just means the asset is
+//! sent again.
+
+use std::time::{Duration, SystemTime};
+
+const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Format a time as an IMF-fixdate, dropping anything below a second. Times before 1970 are clamped to 1970.
+pub(crate) fn format_http_date(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let days = secs / SECONDS_PER_DAY;
+    let secs_of_day = secs % SECONDS_PER_DAY;
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        // The epoch was a Thursday
+        WEEKDAYS[(days % 7) as usize],
+        day,
+        MONTHS[month as usize - 1],
+        year,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60
+    )
+}
+
+/// Parse an IMF-fixdate. The day of the week isn't checked against the date.
+pub(crate) fn parse_http_date(date: &str) -> Option<SystemTime> {
+    let mut parts = date.split_whitespace();
+
+    let weekday = parts.next()?.strip_suffix(',')?;
+    if !WEEKDAYS.contains(&weekday) {
+        return None;
+    }
+
+    let day: u64 = parts.next()?.parse().ok()?;
+    let month_name = parts.next()?;
+    let month = MONTHS.iter().position(|month| *month == month_name)? as u64 + 1;
+    let year: u64 = parts.next()?.parse().ok()?;
+
+    let mut time = parts
+        .next()?
+        .split(':')
+        .map(|part| part.parse::<u64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+
+    if parts.next()? != "GMT" || parts.next().is_some() || time.next().is_some() {
+        return None;
+    }
+    if year < 1970 || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let secs =
+        days_from_civil(year, month, day) * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second;
+
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+/// Check if a file modified at `modified` is unchanged since the `If-Modified-Since` date `since`.
+///
+/// The date only has whole seconds, so the modification time is compared without its fraction of a second.
+pub(crate) fn unmodified_since(modified: SystemTime, since: SystemTime) -> bool {
+    let secs = |time: SystemTime| {
+        time.duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    };
+
+    secs(modified) <= secs(since)
+}
+
+/// Turn days since 1970-01-01 into a `(year, month, day)` date in the proleptic Gregorian calendar.
+///
+/// This counts in 400 year eras starting on March 1st, so the leap day falls at the end of each year.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    // Shift the epoch to 0000-03-01
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/// Turn a `(year, month, day)` date into days since 1970-01-01, the inverse of [`civil_from_days`]
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let shifted_month = (month + 9) % 12;
+    let day_of_year = (153 * shifted_month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[test]
+fn dates_round_trip() {
+    let time = SystemTime::UNIX_EPOCH + Duration::from_secs(784_111_777);
+    assert_eq!(format_http_date(time), "Sun, 06 Nov 1994 08:49:37 GMT");
+    assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"), Some(time));
+
+    // A leap day, and the epoch itself
+    let leap_day = SystemTime::UNIX_EPOCH + Duration::from_secs(951_782_400);
+    assert_eq!(format_http_date(leap_day), "Tue, 29 Feb 2000 00:00:00 GMT");
+    assert_eq!(
+        format_http_date(SystemTime::UNIX_EPOCH),
+        "Thu, 01 Jan 1970 00:00:00 GMT"
+    );
+
+    // Obsolete formats aren't understood
+    assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+    assert_eq!(parse_http_date("Sun Nov  6 08:49:37 1994"), None);
+}
@@ -3,12 +3,25 @@
 #![doc(html_favicon_url = "https://avatars.githubusercontent.com/u/79236386")]
 #![deny(missing_docs)]
 
+#[cfg(debug_assertions)]
+mod asset_watcher;
+mod assets;
 mod cfg;
+#[cfg(feature = "compression")]
+mod compression;
 mod controller;
 mod desktop_context;
+pub mod embed;
 mod escape;
 mod events;
+#[cfg(test)]
+mod fixture;
+mod http_date;
 mod protocol;
+mod protocol_log;
+#[cfg(feature = "protocol-stats")]
+mod protocol_stats;
+mod range;
 
 #[cfg(all(feature = "hot-reload", debug_assertions))]
 mod hot_reload;
@@ -22,10 +35,17 @@ use futures_channel::mpsc::UnboundedSender;
 pub use wry;
 pub use wry::application as tao;
 
-pub use cfg::Config;
+pub use assets::{AssetFlags, AssetProvider, AssetTheme, MemoryAssets, ProvidedAsset};
+pub use cfg::{AssetRootError, Config, InvalidAssetRoot};
+#[cfg(feature = "compression")]
+pub use compression::Encoding;
 use controller::DesktopController;
 use dioxus_core::*;
 use events::parse_ipc_message;
+pub use protocol_log::{ResponseSource, ServedRequest};
+#[cfg(feature = "protocol-stats")]
+pub use protocol_stats::{Latency, ProtocolStats};
+pub use range::{parse_range, RangeError};
 pub use tao::dpi::{LogicalSize, PhysicalSize};
 pub use tao::window::WindowBuilder;
 use tao::{
@@ -103,9 +123,21 @@ pub fn launch_cfg(root: Component, config_builder: Config) {
 /// }
 /// ```
 pub fn launch_with_props<P: 'static + Send>(root: Component<P>, props: P, mut cfg: Config) {
+    // Surface packaging mistakes at startup instead of as per-request 404s
+    #[cfg(debug_assertions)]
+    if let Err(err) = cfg.validate() {
+        panic!("{}", err);
+    }
+
     let event_loop = EventLoop::with_user_event();
     let mut desktop = DesktopController::new_on_tokio(root, props, event_loop.create_proxy());
 
+    // One watcher for the whole app, no matter how many windows it opens
+    #[cfg(debug_assertions)]
+    if cfg.asset_hot_reload {
+        asset_watcher::watch(cfg.watched_roots(), event_loop.create_proxy());
+    }
+
     #[cfg(debug_assertions)]
     hot_reload::init(desktop.templates_tx.clone());
 
@@ -147,6 +179,10 @@ impl DesktopController {
             self.proxy.clone(),
             self.eval_sender.clone(),
             self.event_tx.clone(),
+            #[cfg(debug_assertions)]
+            self.dom_stats.clone(),
+            #[cfg(feature = "protocol-stats")]
+            self.protocol_metrics.clone(),
         );
 
         self.webviews.insert(webview.window().id(), webview);
@@ -160,14 +196,26 @@ fn build_webview(
     proxy: tao::event_loop::EventLoopProxy<UserWindowEvent>,
     eval_sender: tokio::sync::mpsc::UnboundedSender<serde_json::Value>,
     event_tx: UnboundedSender<serde_json::Value>,
+    #[cfg(debug_assertions)] dom_stats: Arc<std::sync::Mutex<DomStats>>,
+    #[cfg(feature = "protocol-stats")] protocol_metrics: Arc<
+        std::sync::Mutex<protocol_stats::ProtocolMetrics>,
+    >,
 ) -> wry::webview::WebView {
     let builder = cfg.window.clone();
     let window = builder.build(event_loop).unwrap();
     let file_handler = cfg.file_drop_handler.take();
-    let custom_head = cfg.custom_head.clone();
-    let resource_dir = cfg.resource_dir.clone();
-    let index_file = cfg.custom_index.clone();
-    let root_name = cfg.root_name.clone();
+    let ipc_observer = cfg.ipc_observer.take();
+    let protocol_options = cfg.protocol_options();
+    #[cfg(debug_assertions)]
+    let protocol_options = protocol::ProtocolOptions {
+        dom_stats,
+        ..protocol_options
+    };
+    #[cfg(feature = "protocol-stats")]
+    let protocol_options = protocol::ProtocolOptions {
+        protocol_metrics,
+        ..protocol_options
+    };
 
     // We assume that if the icon is None in cfg, then the user just didnt set it
     if cfg.window.window.window_icon.is_none() {
@@ -181,12 +229,18 @@ fn build_webview(
         ));
     }
 
+    let scheme = protocol_options.scheme().to_string();
+
     let mut webview = WebViewBuilder::new(window)
         .unwrap()
         .with_transparent(cfg.window.window.transparent)
-        .with_url("dioxus://index.html/")
+        .with_url(&format!("{}://index.html/", scheme))
         .unwrap()
         .with_ipc_handler(move |_window: &Window, payload: String| {
+            if let Some(observer) = &ipc_observer {
+                observer(&payload);
+            }
+
             let message = match parse_ipc_message(&payload) {
                 Some(message) => message,
                 None => {
@@ -204,6 +258,7 @@ fn build_webview(
                     _ = event_tx.unbounded_send(message.params());
                 }
                 "initialize" => {
+                    log::trace!("Webview initialized: {:?}", message.params());
                     is_ready.store(true, std::sync::atomic::Ordering::Relaxed);
                     let _ = proxy.send_event(UserWindowEvent::EditsReady);
                 }
@@ -222,14 +277,8 @@ fn build_webview(
                 _ => (),
             }
         })
-        .with_custom_protocol(String::from("dioxus"), move |r| {
-            protocol::desktop_handler(
-                r,
-                resource_dir.clone(),
-                custom_head.clone(),
-                index_file.clone(),
-                &root_name,
-            )
+        .with_custom_protocol(scheme, move |r| {
+            protocol::block_on(protocol::desktop_handler_async(r, &protocol_options))
         })
         .with_file_drop_handler(move |window, evet| {
             file_handler
@@ -1,167 +1,2833 @@
+use crate::assets::{
+    AssetFlags, AssetProvider, AssetResolver, AssetTheme, AssetTransform, AsyncAssetResolver,
+};
+use crate::http_date::{format_http_date, parse_http_date, unmodified_since};
+use crate::protocol_log::{RequestObserver, ResponseSource, ServedRequest};
+use crate::range::{parse_range, RangeError};
+use dioxus_desktop_embed::mime::{
+    get_mime_by_ext, get_mime_from_bytes, get_trusted_mime, with_charset,
+};
 use dioxus_interpreter_js::INTERPRETER_JS;
+use serde_json::{Map, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::Thread;
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "compression")]
+use crate::compression::Compression;
+#[cfg(test)]
+use crate::fixture::{get, TestDir};
+#[cfg(feature = "protocol-stats")]
+use crate::protocol_stats::{ProtocolMetrics, RequestTimer};
+#[cfg(debug_assertions)]
+use dioxus_core::DomStats;
+#[cfg(any(debug_assertions, feature = "protocol-stats"))]
+use std::sync::Mutex;
 use wry::{
-    http::{status::StatusCode, Request, Response},
+    http::{
+        header::{HeaderName, HeaderValue},
+        response::Builder as ResponseBuilder,
+        status::StatusCode,
+        Method, Request, Response,
+    },
     Result,
 };
 
-fn module_loader(root_name: &str) -> String {
+/// Scripts that run around the module loader, from [`crate::Config::with_script_before_loader`] and
+/// [`crate::Config::with_script_after_loader`]
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LoaderScripts {
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+fn module_loader(
+    root_name: &str,
+    initialize_params: &Map<String, Value>,
+    interpreter: &str,
+    scripts: &LoaderScripts,
+    nonce: Option<&str>,
+) -> String {
+    let nonce = match nonce {
+        Some(nonce) => format!(" nonce=\"{}\"", nonce),
+        None => String::new(),
+    };
+
+    // Without any scripts the loader is exactly the same as it's always been
+    let before = match &scripts.before {
+        Some(before) => format!("{}\n    ", before),
+        None => String::new(),
+    };
+    let after = match &scripts.after {
+        Some(after) => format!("    {}\n", after),
+        None => String::new(),
+    };
+
     format!(
         r#"
-<script>
-    {INTERPRETER_JS}
+<script{}>
+    {}{}
 
-    let rootname = "{}";
+    let rootname = {};
     let root = window.document.getElementById(rootname);
     if (root != null) {{
         window.interpreter = new Interpreter(root);
-        window.ipc.postMessage(serializeIpcMessage("initialize"));
+        window.ipc.postMessage(serializeIpcMessage("initialize", {}));
     }}
-</script>
+{}</script>
 "#,
-        root_name
+        nonce,
+        before,
+        interpreter,
+        // The root name comes from the config, so it's written as a JSON string rather than trusted to be a safe literal
+        script_json(&root_name),
+        script_json(initialize_params),
+        after
     )
 }
 
+/// Generate a nonce that's different for every index that's served.
+///
+/// Content injected into the page must not be able to guess it, so it's 128 bits from the OS random number generator,
+/// base64 encoded as the CSP spec asks for.
+fn generate_nonce() -> Result<String> {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).map_err(std::io::Error::from)?;
+    Ok(STANDARD.encode(bytes))
+}
+
+/// Allow scripts with the nonce in a Content-Security-Policy, adding a `script-src` directive if there isn't one
+fn policy_with_nonce(policy: &str, nonce: &str) -> String {
+    let source = format!("'nonce-{}'", nonce);
+    let mut found = false;
+
+    let mut directives: Vec<String> = policy
+        .split(';')
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .map(|directive| {
+            let is_script_src = directive
+                .split_whitespace()
+                .next()
+                .map_or(false, |name| name.eq_ignore_ascii_case("script-src"));
+
+            if is_script_src {
+                found = true;
+                format!("{} {}", directive, source)
+            } else {
+                directive.to_string()
+            }
+        })
+        .collect();
+
+    if !found {
+        directives.push(format!("script-src {}", source));
+    }
+
+    directives.join("; ")
+}
+
+/// Serialize a value to JSON that can be safely inlined into a `<script>` tag
+fn script_json(value: &impl serde::Serialize) -> String {
+    // A literal `</script>` inside a string would close the tag early, but `<\/` means the same thing to JSON
+    serde_json::to_string(value)
+        .expect("JSON values to always serialize")
+        .replace("</", "<\\/")
+}
+
+/// The scheme the protocol is registered under unless [`crate::Config::with_custom_scheme`] picks another one
+const DEFAULT_SCHEME: &str = "dioxus";
+
+/// The path webviews request their icon from without being asked to
+const FAVICON_PATH: &str = "favicon.ico";
+
+/// Everything the protocol handler needs from the [`crate::Config`], copied out once per webview
+#[derive(Default)]
+pub(crate) struct ProtocolOptions {
+    pub scheme: Option<String>,
+    pub asset_roots: Vec<PathBuf>,
+    pub custom_head: Option<String>,
+    pub custom_index: Option<String>,
+    pub not_found: Option<String>,
+    pub forbidden_body: Option<String>,
+    pub forbidden_content_type: Option<String>,
+    pub favicon: Option<Vec<u8>>,
+    pub root_name: String,
+    pub root_element: RootElement,
+    pub initialize_params: Map<String, Value>,
+    pub asset_mounts: Vec<AssetMount>,
+    pub asset_providers: Vec<Rc<dyn AssetProvider>>,
+    pub asset_transforms: HashMap<String, AssetTransform>,
+    pub asset_resolver: Option<AssetResolver>,
+    pub async_asset_resolver: Option<AsyncAssetResolver>,
+    pub request_observer: Option<RequestObserver>,
+    pub provided_assets_only: bool,
+    pub content_security_policy: Option<String>,
+    pub loader_scripts: LoaderScripts,
+    pub interpreter_js: Option<String>,
+    pub transform_cache: RefCell<HashMap<PathBuf, TransformedAsset>>,
+    pub redirects: HashMap<String, Redirect>,
+    pub asset_theme: Option<AssetTheme>,
+    pub asset_headers: HashMap<String, Vec<(String, String)>>,
+    pub asset_flags: AssetFlags,
+    pub flagged_assets: HashMap<String, Vec<FlaggedAsset>>,
+    pub server_side_includes: bool,
+    pub cache_max_age: Option<Duration>,
+    pub max_response_size: Option<u64>,
+    pub dev_cors: bool,
+    pub dev_symlinks: bool,
+    pub directory_listing: bool,
+    pub unknown_extensions_as_html: bool,
+    pub spa_fallback: bool,
+    pub preloads: HashMap<String, Vec<Preload>>,
+
+    #[cfg(feature = "compression")]
+    pub compression: Option<Compression>,
+
+    #[cfg(debug_assertions)]
+    pub dom_stats: Arc<Mutex<DomStats>>,
+
+    #[cfg(feature = "protocol-stats")]
+    pub protocol_metrics: Arc<Mutex<ProtocolMetrics>>,
+}
+
+impl ProtocolOptions {
+    /// The scheme requests are made to, without the `://`
+    pub fn scheme(&self) -> &str {
+        self.scheme.as_deref().unwrap_or(DEFAULT_SCHEME)
+    }
+
+    /// The interpreter served to the webview, which is the bundled one unless the config replaces it
+    pub fn interpreter_js(&self) -> &str {
+        self.interpreter_js.as_deref().unwrap_or(INTERPRETER_JS)
+    }
+
+    /// The percent-decoded path of a request relative to the root of the scheme, like `index.html/my image.png`.
+    ///
+    /// The query string and fragment are dropped since assets are looked up by path alone, so cache-busting URLs like
+    /// `app.js?v=abcd1234` still find `app.js`. Paths with invalid escapes are left as
+    /// they are, so they won't match any asset.
+    fn request_path(&self, request: &Request<Vec<u8>>) -> String {
+        let uri = request
+            .uri()
+            .to_string()
+            .replace(&format!("{}://", self.scheme()), "");
+        let path = uri
+            .split(|c| c == '?' || c == '#')
+            .next()
+            .unwrap_or_default();
+
+        percent_decode(path).unwrap_or_else(|| path.to_string())
+    }
+}
+
+/// Where to send requests for an asset that moved
+#[derive(Clone, Debug)]
+pub(crate) struct Redirect {
+    /// The new path of the asset, relative to the root of the `dioxus://` scheme
+    pub to: String,
+    pub permanent: bool,
+}
+
+/// The output of an [`AssetTransform`], kept around until the source file changes
+pub(crate) struct TransformedAsset {
+    modified: SystemTime,
+    bytes: Vec<u8>,
+    mime: &'static str,
+}
+
+/// A variant of an asset that's served while a flag is enabled
+#[derive(Clone, Debug)]
+pub(crate) struct FlaggedAsset {
+    pub flag: String,
+    pub variant: String,
+}
+
+/// A dependency the webview should start loading as soon as it receives an asset
+#[derive(Clone, Debug)]
+pub(crate) struct Preload {
+    /// The path of the dependency, relative to the root of the `dioxus://` scheme
+    pub path: String,
+
+    /// The `as` of the preload, like `style` or `script`
+    pub destination: String,
+}
+
+/// The element the default index mounts the app into
+#[derive(Clone, Debug)]
+pub(crate) struct RootElement {
+    pub tag: String,
+    pub class: Option<String>,
+    pub attributes: Vec<(String, String)>,
+}
+
+impl Default for RootElement {
+    fn default() -> Self {
+        Self {
+            tag: "div".to_string(),
+            class: None,
+            attributes: Vec::new(),
+        }
+    }
+}
+
+impl RootElement {
+    /// Render the empty element with the given id, escaping every attribute value
+    fn render(&self, id: &str) -> String {
+        let mut out = format!("<{} id=\"{}\"", self.tag, escape_attribute(id));
+        if let Some(class) = &self.class {
+            out += &format!(" class=\"{}\"", escape_attribute(class));
+        }
+        for (name, value) in &self.attributes {
+            out += &format!(" {}=\"{}\"", name, escape_attribute(value));
+        }
+        out += &format!("></{}>", self.tag);
+        out
+    }
+}
+
+/// Escape a value so it can be placed in a double quoted HTML attribute
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// A URL prefix whose assets are served out of a specific directory
+#[derive(Clone, Debug)]
+pub(crate) struct AssetMount {
+    /// The prefix without leading or trailing slashes, like `app/assets`
+    pub prefix: String,
+    pub root: PathBuf,
+}
+
+/// Serve a request with the async asset resolver if there is one, falling back to [`desktop_handler`]
+pub(super) async fn desktop_handler_async(
+    request: &Request<Vec<u8>>,
+    options: &ProtocolOptions,
+) -> Result<Response<Vec<u8>>> {
+    if let Some(resolver) = &options.async_asset_resolver {
+        let path = options.request_path(request);
+        let trimmed = path.trim_start_matches("index.html/");
+        if let Some(response) = resolver(trimmed.to_string()).await {
+            let (status, bytes) = (response.status().as_u16(), response.body().len());
+            observe(
+                options,
+                request,
+                status,
+                bytes,
+                ResponseSource::AsyncResolver,
+            );
+            return Ok(response);
+        }
+    }
+
+    desktop_handler(request, options)
+}
+
+/// Drive a future to completion on the current thread, parking the thread while the future waits to be woken
+pub(super) fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        std::thread::park();
+    }
+}
+
+/// Serve a request made to the `dioxus://` scheme, or whichever scheme the protocol was registered under.
 pub(super) fn desktop_handler(
     request: &Request<Vec<u8>>,
-    asset_root: Option<PathBuf>,
-    custom_head: Option<String>,
-    custom_index: Option<String>,
-    root_name: &str,
+    options: &ProtocolOptions,
+) -> Result<Response<Vec<u8>>> {
+    #[cfg(feature = "protocol-stats")]
+    let mut timer = RequestTimer::start();
+
+    let mut source = ResponseSource::NotFound;
+    let served = serve(
+        request,
+        options,
+        &mut source,
+        #[cfg(feature = "protocol-stats")]
+        &mut timer,
+    );
+    let mut response = match served {
+        Ok(mut response) => {
+            // Only static assets can be served in parts, so let the webview know not to ask for anything else
+            if !response.headers().contains_key("Accept-Ranges") {
+                response
+                    .headers_mut()
+                    .insert("Accept-Ranges", HeaderValue::from_static("none"));
+            }
+            response
+        }
+        Err(err) => {
+            // The webview never gets a response for a failed request, so it's counted as a server error
+            #[cfg(feature = "protocol-stats")]
+            options
+                .protocol_metrics
+                .lock()
+                .unwrap()
+                .record(timer, 500, 0);
+            observe(options, request, 500, 0, source);
+            return Err(err);
+        }
+    };
+
+    let path = options.request_path(request);
+    let trimmed = path.trim_start_matches("index.html/");
+
+    // Let the webview start fetching the asset's dependencies before it finds them in the asset itself
+    if let Some(preloads) = options.preloads.get(trimmed) {
+        if response.status().is_success() {
+            let link = preloads
+                .iter()
+                .map(|preload| {
+                    format!(
+                        "</{}>; rel=preload; as={}",
+                        preload.path, preload.destination
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            match HeaderValue::from_str(&link) {
+                Ok(link) => {
+                    response.headers_mut().insert("Link", link);
+                }
+                Err(_) => log::warn!("Skipping invalid preloads for {}: {}", trimmed, link),
+            }
+        }
+    }
+
+    // Headers configured for a specific path win over anything we set while serving it
+    if let Some(headers) = options.asset_headers.get(trimmed) {
+        for (name, value) in headers {
+            match (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                (Ok(name), Ok(value)) => {
+                    response.headers_mut().insert(name, value);
+                }
+                _ => log::warn!(
+                    "Skipping invalid header for {}: {}: {}",
+                    trimmed,
+                    name,
+                    value
+                ),
+            }
+        }
+    }
+
+    if options.dev_cors {
+        add_cors_headers(request, &mut response);
+    }
+
+    #[cfg(feature = "compression")]
+    if let Some(compression) = &options.compression {
+        compression.apply(request, &mut response);
+    }
+
+    // Set last so it counts the bytes actually sent, after compression has had its say. Files aren't read for a HEAD
+    // request, so those keep the length their GET would have had.
+    let status = response.status();
+    let head = request.method() == Method::HEAD;
+    let has_length = response.headers().contains_key("Content-Length");
+    if status != StatusCode::NO_CONTENT
+        && status != StatusCode::NOT_MODIFIED
+        && !(head && has_length)
+    {
+        let length = HeaderValue::from(response.body().len());
+        response.headers_mut().insert("Content-Length", length);
+    }
+
+    // A HEAD request gets the same status and headers as a GET, just without the body
+    if head {
+        response.body_mut().clear();
+    }
+
+    #[cfg(feature = "protocol-stats")]
+    options.protocol_metrics.lock().unwrap().record(
+        timer,
+        response.status().as_u16(),
+        response.body().len(),
+    );
+
+    observe(
+        options,
+        request,
+        response.status().as_u16(),
+        response.body().len(),
+        source,
+    );
+
+    Ok(response)
+}
+
+/// Tell the request observer, if there is one, how a request was answered
+fn observe(
+    options: &ProtocolOptions,
+    request: &Request<Vec<u8>>,
+    status: u16,
+    bytes: usize,
+    source: ResponseSource,
+) {
+    if let Some(observer) = &options.request_observer {
+        let path = options.request_path(request);
+        observer(&ServedRequest {
+            path: path.trim_start_matches("index.html/").to_string(),
+            status,
+            bytes,
+            source,
+        });
+    }
+}
+
+/// Let any origin read the response, echoing back the headers a preflight asked to send
+fn add_cors_headers(request: &Request<Vec<u8>>, response: &mut Response<Vec<u8>>) {
+    let allow_headers = request
+        .headers()
+        .get("Access-Control-Request-Headers")
+        .cloned()
+        .unwrap_or_else(|| HeaderValue::from_static("*"));
+
+    let headers = response.headers_mut();
+    headers.insert("Access-Control-Allow-Origin", HeaderValue::from_static("*"));
+    headers.insert(
+        "Access-Control-Allow-Methods",
+        HeaderValue::from_static("GET, HEAD, OPTIONS"),
+    );
+    headers.insert("Access-Control-Allow-Headers", allow_headers);
+}
+
+fn serve(
+    request: &Request<Vec<u8>>,
+    options: &ProtocolOptions,
+    source: &mut ResponseSource,
+    #[cfg(feature = "protocol-stats")] timer: &mut RequestTimer,
 ) -> Result<Response<Vec<u8>>> {
     // Any content that uses the `dioxus://` scheme will be shuttled through this handler as a "special case".
     // For now, we only serve two pieces of content which get included as bytes into the final binary.
-    let path = request.uri().to_string().replace("dioxus://", "");
+    let path = options.request_path(request);
 
     // all assets should be called from index.html
     let trimmed = path.trim_start_matches("index.html/");
 
+    // Preflights only need the CORS headers, which are added to every response
+    if options.dev_cors && request.method() == Method::OPTIONS {
+        *source = ResponseSource::Internal;
+        return Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .body(Vec::new())
+            .map_err(From::from);
+    }
+
+    // Debug builds report the health of the VirtualDom so it can be watched from the devtools or an external poller
+    #[cfg(debug_assertions)]
+    if trimmed == "__dioxus/stats" {
+        *source = ResponseSource::Internal;
+        let stats = *options.dom_stats.lock().unwrap();
+        return Response::builder()
+            .header("Content-Type", "application/json")
+            .body(serde_json::to_vec(&stats).expect("stats to always serialize"))
+            .map_err(From::from);
+    }
+
+    // Swap in the variant of the asset for the active flags before resolving it
+    let trimmed = flagged_variant(options, trimmed).unwrap_or(trimmed);
+
     if trimmed.is_empty() {
-        // If a custom index is provided, just defer to that, expecting the user to know what they're doing.
-        // we'll look for the closing </body> tag and insert our little module loader there.
-        if let Some(custom_index) = custom_index {
-            let rendered = custom_index
-                .replace("</body>", &format!("{}</body>", module_loader(root_name)))
-                .into_bytes();
-            Response::builder()
-                .header("Content-Type", "text/html")
-                .body(rendered)
-                .map_err(From::from)
+        *source = ResponseSource::Index;
+        serve_index(options)
+    } else if trimmed == "index.js" {
+        *source = ResponseSource::Interpreter;
+        Response::builder()
+            .header("Content-Type", "text/javascript; charset=utf-8")
+            .body(options.interpreter_js().as_bytes().to_vec())
+            .map_err(From::from)
+    } else if let Some(favicon) = options.favicon.as_ref().filter(|_| trimmed == FAVICON_PATH) {
+        *source = ResponseSource::Internal;
+        serve_favicon(favicon.clone())
+    } else if let Some(redirect) = options.redirects.get(trimmed) {
+        *source = ResponseSource::Redirect;
+        let status = if redirect.permanent {
+            StatusCode::MOVED_PERMANENTLY
         } else {
-            // Otherwise, we'll serve the default index.html and apply a custom head if that's specified.
-            let mut template = include_str!("./index.html").to_string();
-            if let Some(custom_head) = custom_head {
-                template = template.replace("<!-- CUSTOM HEAD -->", &custom_head);
-            }
-            template = template.replace("<!-- MODULE LOADER -->", &module_loader(root_name));
+            StatusCode::FOUND
+        };
 
-            Response::builder()
-                .header("Content-Type", "text/html")
-                .body(template.into_bytes())
-                .map_err(From::from)
-        }
-    } else if trimmed == "index.js" {
         Response::builder()
-            .header("Content-Type", "text/javascript")
-            .body(dioxus_interpreter_js::INTERPRETER_JS.as_bytes().to_vec())
+            .status(status)
+            .header(
+                "Location",
+                format!("{}://index.html/{}", options.scheme(), redirect.to),
+            )
+            .body(Vec::new())
             .map_err(From::from)
+    } else if let Some(asset) = options.asset_providers.iter().find_map(|p| p.get(trimmed)) {
+        *source = ResponseSource::Provider;
+        #[cfg(feature = "protocol-stats")]
+        timer.resolved();
+
+        let mime = match &asset.mime {
+            Some(mime) => mime.to_string(),
+            None => get_mime_from_bytes(trimmed, &asset.bytes, options.unknown_extensions_as_html)
+                .to_string(),
+        };
+
+        let mut response = Response::builder().header("Content-Type", mime);
+
+        if let Some(etag) = &asset.etag {
+            // The webview already has this exact version cached
+            let cached = request
+                .headers()
+                .get("If-None-Match")
+                .map_or(false, |tag| tag.as_bytes() == etag.as_bytes());
+
+            if cached {
+                return Response::builder()
+                    .status(StatusCode::NOT_MODIFIED)
+                    .header("ETag", etag.as_ref())
+                    .body(Vec::new())
+                    .map_err(From::from);
+            }
+
+            response = response.header("ETag", etag.as_ref());
+        }
+
+        // Serve the copy that was compressed ahead of time, but keep the type of the original
+        let accepted = request
+            .headers()
+            .get("Accept-Encoding")
+            .and_then(|header| header.to_str().ok());
+        let precompressed = accepted.and_then(|accepted| {
+            asset
+                .precompressed
+                .into_iter()
+                .find(|(encoding, _)| accepts_encoding(accepted, encoding))
+        });
+
+        match precompressed {
+            Some((encoding, bytes)) => {
+                let response = response
+                    .header("Content-Encoding", encoding)
+                    .header("Vary", "Accept-Encoding");
+                serve_bytes(request, response, bytes.into_owned())
+            }
+            None => serve_bytes(request, response, asset.bytes.into_owned()),
+        }
+    } else if let Some(response) = options
+        .asset_resolver
+        .as_ref()
+        .and_then(|resolver| resolver(trimmed))
+    {
+        *source = ResponseSource::Resolver;
+        #[cfg(feature = "protocol-stats")]
+        timer.resolved();
+
+        Ok(response)
+    } else if has_parent_segment(trimmed) {
+        // Refuse to climb out of the root before touching the filesystem, even if the path would end up back inside it
+        *source = ResponseSource::Forbidden;
+        forbidden(options)
+    } else if options.provided_assets_only {
+        if options.spa_fallback && is_route(trimmed) {
+            *source = ResponseSource::Index;
+            return serve_index(options);
+        }
+
+        if trimmed == FAVICON_PATH {
+            *source = ResponseSource::Internal;
+            return serve_favicon(default_favicon());
+        }
+
+        *source = ResponseSource::NotFound;
+        not_found(options)
     } else {
-        let asset_root = asset_root
-            .unwrap_or_else(|| get_asset_root().unwrap_or_else(|| Path::new(".").to_path_buf()))
-            .canonicalize()?;
+        // Mounted prefixes get resolved against their own root, everything else goes through the default roots
+        let (asset_roots, relative) = match find_mount(&options.asset_mounts, trimmed) {
+            Some((mount, relative)) => (vec![mount.root.clone()], relative),
+            None if options.asset_roots.is_empty() => (
+                vec![get_asset_root().unwrap_or_else(|| Path::new(".").to_path_buf())],
+                trimmed,
+            ),
+            None => (options.asset_roots.clone(), trimmed),
+        };
+
+        let theme = options.asset_theme.as_ref().and_then(|theme| theme.get());
 
-        let asset = asset_root.join(trimmed).canonicalize()?;
+        let follow_symlinks = cfg!(debug_assertions) && options.dev_symlinks;
 
-        if !asset.starts_with(asset_root) {
+        let (asset_root, asset) =
+            match find_asset(&asset_roots, relative, theme.as_deref(), follow_symlinks)? {
+                AssetLookup::Found { root, asset } => (root, asset),
+                AssetLookup::Escaped => {
+                    *source = ResponseSource::Forbidden;
+                    return forbidden(options);
+                }
+                AssetLookup::Missing if options.spa_fallback && is_route(trimmed) => {
+                    *source = ResponseSource::Index;
+                    return serve_index(options);
+                }
+                AssetLookup::Missing if trimmed == FAVICON_PATH => {
+                    *source = ResponseSource::Internal;
+                    return serve_favicon(default_favicon());
+                }
+                AssetLookup::Missing => {
+                    *source = ResponseSource::NotFound;
+                    return not_found(options);
+                }
+            };
+        *source = ResponseSource::Filesystem;
+
+        #[cfg(feature = "protocol-stats")]
+        timer.resolved();
+
+        // The lookup already held the directory to its root, so only what's under the asset directories is listed
+        if cfg!(debug_assertions) && options.directory_listing && asset.is_dir() {
             return Response::builder()
-                .status(StatusCode::FORBIDDEN)
-                .body(String::from("Forbidden").into_bytes())
+                .header("Content-Type", "text/html; charset=utf-8")
+                .body(directory_listing(options, trimmed, &asset)?.into_bytes())
                 .map_err(From::from);
         }
 
-        if !asset.exists() {
-            return Response::builder()
-                .status(StatusCode::NOT_FOUND)
-                .body(String::from("Not Found").into_bytes())
+        let transform = asset
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| options.asset_transforms.get(ext));
+
+        if let Some(transform) = transform {
+            return match transform_asset(&asset, transform, &options.transform_cache) {
+                Ok((bytes, mime)) => Response::builder()
+                    .header("Content-Type", mime)
+                    .body(bytes)
+                    .map_err(From::from),
+                Err(err) => Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(err.to_string().into_bytes())
+                    .map_err(From::from),
+            };
+        }
+
+        let mime = get_mime_from_path(trimmed, &asset, options.unknown_extensions_as_html);
+
+        let response = Response::builder().header("Content-Type", mime);
+
+        if options.server_side_includes && mime.starts_with("text/html") {
+            return response
+                .body(expand_includes(&asset_root, &asset, 0)?.into_bytes())
                 .map_err(From::from);
         }
 
-        Response::builder()
-            .header("Content-Type", get_mime_from_path(trimmed)?)
-            .body(std::fs::read(asset)?)
-            .map_err(From::from)
+        // Serve the copy the build already compressed, but keep the type of the original
+        let (asset, response) = match find_precompressed(request, &asset_root, &asset) {
+            Some((compressed, encoding)) => (
+                compressed,
+                response
+                    .header("Content-Encoding", encoding)
+                    .header("Vary", "Accept-Encoding"),
+            ),
+            None => (asset, response),
+        };
+
+        let metadata = asset.metadata()?;
+        let etag = file_etag(&metadata);
+        let modified = metadata.modified().ok();
+        let cache_control = match options.cache_max_age {
+            Some(max_age) => format!("max-age={}", max_age.as_secs()),
+            None => "no-cache".to_string(),
+        };
+
+        // The webview already has this version of the file cached. The date is only checked when there's no ETag to
+        // compare, since the ETag is the more precise of the two.
+        let headers = request.headers();
+        let cached = match headers.get("If-None-Match") {
+            Some(header) => header
+                .to_str()
+                .map_or(false, |header| etag_matches(header, &etag)),
+            None => headers
+                .get("If-Modified-Since")
+                .and_then(|header| header.to_str().ok())
+                .and_then(parse_http_date)
+                .zip(modified)
+                .map_or(false, |(since, modified)| unmodified_since(modified, since)),
+        };
+
+        let mut response = response
+            .header("ETag", &etag)
+            .header("Cache-Control", &cache_control);
+        if let Some(modified) = modified {
+            response = response.header("Last-Modified", format_http_date(modified));
+        }
+
+        if cached {
+            let mut not_modified = Response::builder()
+                .status(StatusCode::NOT_MODIFIED)
+                .header("ETag", etag)
+                .header("Cache-Control", cache_control);
+            if let Some(modified) = modified {
+                not_modified = not_modified.header("Last-Modified", format_http_date(modified));
+            }
+
+            return not_modified.body(Vec::new()).map_err(From::from);
+        }
+
+        // Only media elements and requests that already ask for ranges know to fetch the rest of a partial response
+        let partial_ok = mime.starts_with("video/")
+            || mime.starts_with("audio/")
+            || request.headers().contains_key("Range");
+        let max_span = options.max_response_size.filter(|_| partial_ok);
+
+        serve_file(request, response, &asset, max_span)
     }
 }
 
-#[allow(unreachable_code)]
-fn get_asset_root() -> Option<PathBuf> {
-    /*
-    We're matching exactly how cargo-bundle works.
+/// Render the index page with the module loader injected
+fn serve_index(options: &ProtocolOptions) -> Result<Response<Vec<u8>>> {
+    // A fresh nonce for every load of the index lets the module loader run under a strict CSP
+    let nonce = options
+        .content_security_policy
+        .as_ref()
+        .map(|_| generate_nonce())
+        .transpose()?;
+    let loader = module_loader(
+        &options.root_name,
+        &options.initialize_params,
+        options.interpreter_js(),
+        &options.loader_scripts,
+        nonce.as_deref(),
+    );
 
-    - [x] macOS
-    - [ ] Windows
-    - [ ] Linux (rpm)
-    - [ ] Linux (deb)
-    - [ ] iOS
-    - [ ] Android
+    // If a custom index is provided, just defer to that, expecting the user to know what they're doing.
+    // The module loader goes where the index asks for it, or before the last closing </body> tag, since
+    // earlier ones might be inside a script.
+    let rendered = if let Some(custom_index) = &options.custom_index {
+        let mut index = if custom_index.contains("<!-- MODULE LOADER -->") {
+            custom_index.replace("<!-- MODULE LOADER -->", &loader)
+        } else if let Some(body_end) = custom_index.rfind("</body>") {
+            let mut index = custom_index.clone();
+            index.insert_str(body_end, &loader);
+            index
+        } else {
+            format!("{}{}", custom_index, loader)
+        };
 
-    */
+        // The custom head goes where the index asks for it, or at the end of its head
+        if let Some(custom_head) = &options.custom_head {
+            index = if index.contains("<!-- CUSTOM HEAD -->") {
+                index.replace("<!-- CUSTOM HEAD -->", custom_head)
+            } else {
+                index.replacen("</head>", &format!("{}</head>", custom_head), 1)
+            };
+        }
 
-    if std::env::var_os("CARGO").is_some() {
-        return None;
+        index
+    } else {
+        // Otherwise, we'll serve the default index.html and apply a custom head if that's specified.
+        let mut template = include_str!("./index.html").to_string();
+        if let Some(custom_head) = &options.custom_head {
+            template = template.replace("<!-- CUSTOM HEAD -->", custom_head);
+        }
+        template = template.replace(
+            "<!-- ROOT ELEMENT -->",
+            &options.root_element.render(&options.root_name),
+        );
+        template.replace("<!-- MODULE LOADER -->", &loader)
+    };
+
+    let mut response = Response::builder().header("Content-Type", "text/html; charset=utf-8");
+    if let (Some(policy), Some(nonce)) = (&options.content_security_policy, &nonce) {
+        response = response.header("Content-Security-Policy", policy_with_nonce(policy, nonce));
     }
 
-    // TODO: support for other platforms
-    #[cfg(target_os = "macos")]
-    {
-        let bundle = core_foundation::bundle::CFBundle::main_bundle();
-        let bundle_path = bundle.path()?;
-        let resources_path = bundle.resources_path()?;
-        let absolute_resources_root = bundle_path.join(resources_path);
-        let canonical_resources_root = dunce::canonicalize(absolute_resources_root).ok()?;
+    response.body(rendered.into_bytes()).map_err(From::from)
+}
 
-        return Some(canonical_resources_root);
+/// Answer a request for a missing asset with the configured 404 page, or plain text if there isn't one
+fn not_found(options: &ProtocolOptions) -> Result<Response<Vec<u8>>> {
+    let response = Response::builder().status(StatusCode::NOT_FOUND);
+    match &options.not_found {
+        Some(page) => response
+            .header("Content-Type", "text/html; charset=utf-8")
+            .body(page.clone().into_bytes()),
+        None => response.body(String::from("Not Found").into_bytes()),
     }
+    .map_err(From::from)
+}
 
-    None
+/// Answer the webview's request for its icon
+fn serve_favicon(icon: Vec<u8>) -> Result<Response<Vec<u8>>> {
+    Response::builder()
+        .header("Content-Type", "image/vnd.microsoft.icon")
+        .body(icon)
+        .map_err(From::from)
 }
 
-/// Get the mime type from a path-like string
-fn get_mime_from_path(trimmed: &str) -> Result<&str> {
-    if trimmed.ends_with(".svg") {
-        return Ok("image/svg+xml");
+/// Wrap the default window icon in an ICO file.
+///
+/// ICO files can hold a PNG as-is, so this is just the 6 byte header and a 16 byte directory entry in front of it. A
+/// width and height of 0 means 256 pixels or more, with the real size read from the PNG.
+fn default_favicon() -> Vec<u8> {
+    let png: &[u8] = include_bytes!("./assets/default_icon.png");
+
+    let mut icon = Vec::with_capacity(22 + png.len());
+    // Reserved, an icon rather than a cursor, and one image
+    icon.extend_from_slice(&[0, 0, 1, 0, 1, 0]);
+    // Width, height, no palette, reserved, one color plane, and 32 bits per pixel
+    icon.extend_from_slice(&[0, 0, 0, 0, 1, 0, 32, 0]);
+    icon.extend_from_slice(&(png.len() as u32).to_le_bytes());
+    icon.extend_from_slice(&22u32.to_le_bytes());
+    icon.extend_from_slice(png);
+    icon
+}
+
+/// Refuse a request for a path outside the asset directories with the configured body, or plain text if there isn't one
+fn forbidden(options: &ProtocolOptions) -> Result<Response<Vec<u8>>> {
+    let mut response = Response::builder().status(StatusCode::FORBIDDEN);
+    if let Some(content_type) = &options.forbidden_content_type {
+        response = response.header("Content-Type", content_type);
     }
 
-    let res = match infer::get_from_path(trimmed)?.map(|f| f.mime_type()) {
-        Some(t) if t == "text/plain" => get_mime_by_ext(trimmed),
-        Some(f) => f,
-        None => get_mime_by_ext(trimmed),
-    };
+    let body = options.forbidden_body.as_deref().unwrap_or("Forbidden");
+    response.body(body.as_bytes().to_vec()).map_err(From::from)
+}
+
+/// Render an HTML page linking to every entry of an asset directory, with directories marked by a trailing `/`
+fn directory_listing(
+    options: &ProtocolOptions,
+    trimmed: &str,
+    dir: &Path,
+) -> std::io::Result<String> {
+    let mut entries = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let mut name = entry.file_name().to_string_lossy().into_owned();
+        if entry.file_type()?.is_dir() {
+            name.push('/');
+        }
+        entries.push(name);
+    }
+    entries.sort();
+
+    let base = trimmed.trim_end_matches('/');
+    let mut html = format!(
+        "<!DOCTYPE html><html><body><h1>/{}</h1><ul>",
+        escape_attribute(base)
+    );
+    for name in entries {
+        let href = match base {
+            "" => name.clone(),
+            base => format!("{}/{}", base, name),
+        };
+        html.push_str(&format!(
+            r#"<li><a href="{}://index.html/{}">{}</a></li>"#,
+            options.scheme(),
+            escape_attribute(&href),
+            escape_attribute(&name)
+        ));
+    }
+    html.push_str("</ul></body></html>");
+
+    Ok(html)
+}
+
+/// Whether a missing path looks like a client-side route rather than an asset, which always has an extension
+fn is_route(trimmed: &str) -> bool {
+    let file_name = trimmed.rsplit('/').next().unwrap_or(trimmed);
+    !file_name.contains('.')
+}
+
+/// Where an asset was found by [`find_asset`]
+enum AssetLookup {
+    /// The canonical asset and the canonical root it was found in
+    Found { root: PathBuf, asset: PathBuf },
+
+    /// The asset was only found outside of the root it was looked up in, like through a symlink
+    Escaped,
+
+    /// None of the roots have the asset
+    Missing,
+}
+
+/// Look for an asset in each root in turn, preferring the variant for the theme within each root.
+///
+/// Every candidate is checked against the root it was found in, so one root can't be used to reach into another.
+/// With `follow_symlinks`, a candidate that a symlink resolved outside of its root is allowed as long as the
+/// requested path doesn't climb out of it. A path that doesn't exist in a root, or that runs through a file like
+/// `notes.txt/inner.css`, is missing from that root. Any other IO error, like a permission error, also moves on to
+/// the next root, but it's returned instead of treating the asset as missing if no root has the asset.
+fn find_asset(
+    roots: &[PathBuf],
+    relative: &str,
+    theme: Option<&str>,
+    follow_symlinks: bool,
+) -> std::io::Result<AssetLookup> {
+    let mut escaped = false;
+    let mut error = None;
+
+    for root in roots {
+        let root = match root.canonicalize() {
+            Ok(root) => root,
+            Err(err) if is_missing_path(&err) => continue,
+            Err(err) => {
+                error.get_or_insert(err);
+                continue;
+            }
+        };
+
+        let variant = theme.and_then(|theme| themed_variant(&root, relative, theme));
+        let asset = match variant {
+            Some(variant) => variant,
+            None => match root.join(relative).canonicalize() {
+                Ok(asset) => asset,
+                Err(err) if is_missing_path(&err) => continue,
+                Err(err) => {
+                    error.get_or_insert(err);
+                    continue;
+                }
+            },
+        };
+
+        let contained =
+            asset.starts_with(&root) || (follow_symlinks && !has_parent_segment(relative));
+        if !contained {
+            escaped = true;
+            continue;
+        }
 
-    Ok(res)
+        return Ok(AssetLookup::Found { root, asset });
+    }
+
+    if let Some(err) = error {
+        return Err(err);
+    }
+
+    Ok(if escaped {
+        AssetLookup::Escaped
+    } else {
+        AssetLookup::Missing
+    })
+}
+
+/// Check if an IO error means there's nothing at a path, either because it doesn't exist or because one of its
+/// parents is a file rather than a directory
+fn is_missing_path(err: &std::io::Error) -> bool {
+    // `ErrorKind::NotADirectory` is newer than the minimum supported Rust version, so match on the OS error instead
+    #[cfg(unix)]
+    const ENOTDIR: i32 = 20;
+
+    #[cfg(unix)]
+    let not_a_directory = err.raw_os_error() == Some(ENOTDIR);
+    #[cfg(not(unix))]
+    let not_a_directory = false;
+
+    err.kind() == ErrorKind::NotFound || not_a_directory
+}
+
+/// Find a copy of an asset that was compressed ahead of time, like `app.js.br` next to `app.js`, in an encoding the
+/// request accepts. Brotli is preferred over gzip.
+///
+/// The copy is held to the same root as the asset, so a symlinked copy can't be used to reach outside of it.
+fn find_precompressed(
+    request: &Request<Vec<u8>>,
+    root: &Path,
+    asset: &Path,
+) -> Option<(PathBuf, &'static str)> {
+    let accepted = request.headers().get("Accept-Encoding")?.to_str().ok()?;
+
+    [("br", "br"), ("gzip", "gz")]
+        .iter()
+        .filter(|(encoding, _)| accepts_encoding(accepted, encoding))
+        .find_map(|(encoding, extension)| {
+            let mut path = asset.as_os_str().to_owned();
+            path.push(".");
+            path.push(extension);
+
+            let path = Path::new(&path).canonicalize().ok()?;
+            (path.starts_with(root) && path.is_file()).then(|| (path, *encoding))
+        })
+}
+
+/// Check if an `Accept-Encoding` header allows an encoding, either by name or with a wildcard
+pub(crate) fn accepts_encoding(header: &str, name: &str) -> bool {
+    header.split(',').any(|item| {
+        let mut parts = item.split(';');
+        let coding = parts.next().unwrap_or_default().trim();
+
+        // `q=0` means the encoding is explicitly refused
+        let refused = parts.any(|param| {
+            param
+                .trim()
+                .strip_prefix("q=")
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .map_or(false, |q| q == 0.0)
+        });
+
+        (coding.eq_ignore_ascii_case(name) || coding == "*") && !refused
+    })
 }
 
-/// Get the mime type from a URI using its extension
-fn get_mime_by_ext(trimmed: &str) -> &str {
-    let suffix = trimmed.split('.').last();
-    match suffix {
-        Some("bin") => "application/octet-stream",
-        Some("css") => "text/css",
-        Some("csv") => "text/csv",
-        Some("html") => "text/html",
-        Some("ico") => "image/vnd.microsoft.icon",
-        Some("js") => "text/javascript",
-        Some("json") => "application/json",
-        Some("jsonld") => "application/ld+json",
-        Some("mjs") => "text/javascript",
-        Some("rtf") => "application/rtf",
-        Some("svg") => "image/svg+xml",
-        Some("mp4") => "video/mp4",
-        // Assume HTML when a TLD is found for eg. `dioxus:://dioxuslabs.app` | `dioxus://hello.com`
-        Some(_) => "text/html",
-        // https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/MIME_types/Common_types
-        // using octet stream according to this:
-        None => "application/octet-stream",
+/// Decode the `%XX` escapes in a path, or `None` if an escape is malformed or the result isn't UTF-8
+fn percent_decode(path: &str) -> Option<String> {
+    if !path.contains('%') {
+        return Some(path.to_string());
+    }
+
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut rest = path.as_bytes();
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail.get(..2)?;
+            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                return None;
+            }
+            bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
     }
+
+    String::from_utf8(bytes).ok()
+}
+
+/// Check if a path has a `..` component, with either kind of separator
+fn has_parent_segment(path: &str) -> bool {
+    path.split(|c| c == '/' || c == '\\')
+        .any(|segment| segment == "..")
+}
+
+/// Build a weak `ETag` for a file from its length and modification time, without reading it
+fn file_etag(metadata: &std::fs::Metadata) -> String {
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .unwrap_or_default();
+
+    format!("W/\"{:x}-{:x}\"", metadata.len(), modified.as_nanos())
+}
+
+/// Check an `If-None-Match` header against an `ETag`, using the weak comparison
+fn etag_matches(header: &str, etag: &str) -> bool {
+    let etag = etag.trim_start_matches("W/");
+    header
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+/// Respond with the bytes of a static asset, or just the part of it asked for by a `Range` header.
+fn serve_bytes(
+    request: &Request<Vec<u8>>,
+    response: ResponseBuilder,
+    bytes: Vec<u8>,
+) -> Result<Response<Vec<u8>>> {
+    let total = bytes.len() as u64;
+    serve_span(request, response, total, None, |span| match span {
+        Some((start, end)) => Ok(bytes[start as usize..=end as usize].to_vec()),
+        None => Ok(bytes),
+    })
+}
+
+/// Respond with a file on disk, reading only the part asked for by a `Range` header.
+///
+/// Seeking in a `<video>` or `<audio>` element asks for a small part of what may be a very large file, so we seek to
+/// the start of the range instead of reading everything before it. No more than `max_span` bytes are read at once.
+fn serve_file(
+    request: &Request<Vec<u8>>,
+    response: ResponseBuilder,
+    path: &Path,
+    max_span: Option<u64>,
+) -> Result<Response<Vec<u8>>> {
+    let mut file = std::fs::File::open(path)?;
+    let total = file.metadata()?.len();
+    serve_span(request, response, total, max_span, |span| match span {
+        Some((start, end)) => {
+            file.seek(SeekFrom::Start(start))?;
+            let mut bytes = vec![0; (end - start + 1) as usize];
+            file.read_exact(&mut bytes)?;
+            Ok(bytes)
+        }
+        None => {
+            let mut bytes = Vec::with_capacity(total as usize);
+            file.read_to_end(&mut bytes)?;
+            Ok(bytes)
+        }
+    })
+}
+
+/// Answer a request for content that's `total` bytes long, calling `read` with the inclusive span to send, or `None`
+/// for all of it.
+///
+/// Only the first range is served if several are requested. Headers we can't parse are ignored and the whole asset
+/// is sent. If `max_span` is set, no more than that many bytes are sent at once: longer ranges are cut short, and
+/// content longer than it is sent as a partial response even when the whole thing was asked for.
+///
+/// `read` is never called for a HEAD request, so probing a large file doesn't read it.
+fn serve_span(
+    request: &Request<Vec<u8>>,
+    response: ResponseBuilder,
+    total: u64,
+    max_span: Option<u64>,
+    read: impl FnOnce(Option<(u64, u64)>) -> std::io::Result<Vec<u8>>,
+) -> Result<Response<Vec<u8>>> {
+    let response = response.header("Accept-Ranges", "bytes");
+
+    let head = request.method() == Method::HEAD;
+    let read = |span| {
+        if head {
+            Ok(Vec::new())
+        } else {
+            read(span)
+        }
+    };
+
+    let range = request
+        .headers()
+        .get("Range")
+        .and_then(|header| header.to_str().ok())
+        .map(|header| parse_range(header, total));
+
+    let span = match range {
+        Some(Ok(ranges)) => Some(ranges[0]),
+        Some(Err(RangeError::Unsatisfiable)) => {
+            return response
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header("Content-Range", format!("bytes */{}", total))
+                .body(Vec::new())
+                .map_err(From::from)
+        }
+        _ => match max_span {
+            Some(max) if total > max => Some((0, total - 1)),
+            _ => None,
+        },
+    };
+
+    match span {
+        Some((start, end)) => {
+            // The webview asks for whatever is left over in its next request
+            let end = match max_span {
+                Some(max) => end.min(start + max.max(1) - 1),
+                None => end,
+            };
+
+            response
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(
+                    "Content-Range",
+                    format!("bytes {}-{}/{}", start, end, total),
+                )
+                .header("Content-Length", end - start + 1)
+                .body(read(Some((start, end)))?)
+                .map_err(From::from)
+        }
+        None => response
+            .header("Content-Length", total)
+            .body(read(None)?)
+            .map_err(From::from),
+    }
+}
+
+/// How deeply includes may nest before we assume they're including each other
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// Read an HTML file, replacing its `<!--#include file="..." -->` directives with the files they point to
+fn expand_includes(asset_root: &Path, file: &Path, depth: usize) -> std::io::Result<String> {
+    let contents = std::fs::read_to_string(file)?;
+    let dir = file.parent().unwrap_or(asset_root);
+
+    let mut out = String::with_capacity(contents.len());
+    let mut rest = contents.as_str();
+
+    while let Some(start) = rest.find("<!--#include") {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        // An unterminated directive is just text
+        let end = match rest.find("-->") {
+            Some(end) => end + "-->".len(),
+            None => break,
+        };
+
+        let (directive, after) = rest.split_at(end);
+        match include_directive(asset_root, dir, directive, depth) {
+            Ok(included) => out.push_str(&included),
+            Err(err) => {
+                log::warn!("Failed to expand {}: {}", directive, err);
+                out.push_str("<!-- include failed -->");
+            }
+        }
+        rest = after;
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn include_directive(
+    asset_root: &Path,
+    dir: &Path,
+    directive: &str,
+    depth: usize,
+) -> std::io::Result<String> {
+    let invalid =
+        |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg.to_string());
+
+    let (_, path) = directive
+        .split_once("file=\"")
+        .ok_or_else(|| invalid("only file includes are supported"))?;
+    let (path, _) = path
+        .split_once('"')
+        .ok_or_else(|| invalid("unterminated file path"))?;
+
+    if depth >= MAX_INCLUDE_DEPTH {
+        return Err(invalid("includes are nested too deeply"));
+    }
+
+    let included = dir.join(path).canonicalize()?;
+    if !included.starts_with(asset_root) {
+        return Err(invalid("included file is outside the asset directory"));
+    }
+
+    expand_includes(asset_root, &included, depth + 1)
+}
+
+/// Run an asset through its transform, reusing the last result if the file hasn't been modified since
+fn transform_asset(
+    asset: &Path,
+    transform: &AssetTransform,
+    cache: &RefCell<HashMap<PathBuf, TransformedAsset>>,
+) -> std::io::Result<(Vec<u8>, &'static str)> {
+    let modified = std::fs::metadata(asset)?.modified()?;
+
+    if let Some(cached) = cache.borrow().get(asset) {
+        if cached.modified == modified {
+            return Ok((cached.bytes.clone(), cached.mime));
+        }
+    }
+
+    let (bytes, mime) = transform(&std::fs::read(asset)?)?;
+
+    cache.borrow_mut().insert(
+        asset.to_path_buf(),
+        TransformedAsset {
+            modified,
+            bytes: bytes.clone(),
+            mime,
+        },
+    );
+
+    Ok((bytes, mime))
+}
+
+/// Find the variant of a path whose flag is enabled
+fn flagged_variant<'a>(options: &'a ProtocolOptions, path: &str) -> Option<&'a str> {
+    options
+        .flagged_assets
+        .get(path)?
+        .iter()
+        .find(|flagged| options.asset_flags.is_enabled(&flagged.flag))
+        .map(|flagged| flagged.variant.as_str())
+}
+
+/// Resolve the variant of an asset for a theme, like `images/logo.dark.svg` for `images/logo.svg`.
+///
+/// Returns `None` if the variant doesn't exist or would escape the asset root.
+fn themed_variant(asset_root: &Path, relative: &str, theme: &str) -> Option<PathBuf> {
+    let (dir, file_name) = relative.rsplit_once('/').unwrap_or(("", relative));
+    let variant_name = match file_name.rsplit_once('.') {
+        Some((stem, ext)) => format!("{}.{}.{}", stem, theme, ext),
+        None => format!("{}.{}", file_name, theme),
+    };
+
+    let variant = asset_root
+        .join(dir)
+        .join(variant_name)
+        .canonicalize()
+        .ok()?;
+
+    variant.starts_with(asset_root).then(|| variant)
+}
+
+/// Find the mount whose prefix matches the path, along with the rest of the path relative to the mount's root.
+///
+/// If the prefixes of several mounts match, the longest one wins.
+fn find_mount<'a, 'b>(
+    mounts: &'a [AssetMount],
+    path: &'b str,
+) -> Option<(&'a AssetMount, &'b str)> {
+    mounts
+        .iter()
+        .filter_map(|mount| {
+            let relative = path
+                .strip_prefix(mount.prefix.as_str())?
+                .strip_prefix('/')?;
+            Some((mount, relative))
+        })
+        .max_by_key(|(mount, _)| mount.prefix.len())
+}
+
+#[allow(unreachable_code)]
+fn get_asset_root() -> Option<PathBuf> {
+    /*
+    We're matching exactly how cargo-bundle works.
+
+    - [x] macOS
+    - [x] Windows
+    - [x] Linux (rpm)
+    - [x] Linux (deb)
+    - [ ] iOS
+    - [ ] Android
+
+    */
+
+    if std::env::var_os("CARGO").is_some() {
+        return None;
+    }
+
+    // TODO: support for other platforms
+    #[cfg(target_os = "macos")]
+    {
+        let bundle = core_foundation::bundle::CFBundle::main_bundle();
+        let bundle_path = bundle.path()?;
+        let resources_path = bundle.resources_path()?;
+        let absolute_resources_root = bundle_path.join(resources_path);
+        let canonical_resources_root = dunce::canonicalize(absolute_resources_root).ok()?;
+
+        return Some(canonical_resources_root);
+    }
+
+    // Bundled Windows apps keep their resources next to the executable
+    #[cfg(target_os = "windows")]
+    {
+        let exe = std::env::current_exe().ok()?;
+        let assets = exe.parent()?.join("assets");
+        let canonical_assets = dunce::canonicalize(assets).ok()?;
+
+        return canonical_assets.is_dir().then(|| canonical_assets);
+    }
+
+    // Packaged Linux apps install their resources under the share or lib directory of their prefix
+    #[cfg(target_os = "linux")]
+    {
+        let exe = std::env::current_exe().ok()?;
+        let name = exe.file_stem()?;
+        let exe_dir = exe.parent()?;
+
+        // The prefix is wherever the binary was installed, like `/usr` for `/usr/bin/app`
+        let mut candidates = Vec::new();
+        if let Some(prefix) = exe_dir.parent() {
+            candidates.push(prefix.join("share").join(name));
+            candidates.push(prefix.join("lib").join(name));
+        }
+        candidates.push(Path::new("/usr/share").join(name));
+        candidates.push(Path::new("/usr/lib").join(name));
+        candidates.push(exe_dir.to_path_buf());
+
+        return candidates
+            .into_iter()
+            .filter_map(|candidate| dunce::canonicalize(candidate).ok())
+            .find(|candidate| candidate.is_dir());
+    }
+
+    None
+}
+
+/// Get the mime type from a path-like string, sniffing the contents of the resolved asset if needed
+fn get_mime_from_path(trimmed: &str, asset: &Path, unknown_as_html: bool) -> &'static str {
+    if let Some(mime) = get_trusted_mime(trimmed) {
+        return mime;
+    }
+
+    // The trimmed path is relative to the asset root, not the CWD, so we need to sniff the resolved asset instead
+    match infer::get_from_path(asset).map(|f| f.map(|f| f.mime_type())) {
+        Ok(Some(t)) if t != "text/plain" => with_charset(t),
+
+        // If the contents didn't tell us anything or the file couldn't be read, fall back to the extension
+        _ => get_mime_by_ext(trimmed, unknown_as_html),
+    }
+}
+
+#[test]
+fn common_binary_formats_have_mimes() {
+    assert_eq!(
+        get_mime_by_ext("pkg/app_bg.wasm", false),
+        "application/wasm"
+    );
+    assert_eq!(get_mime_by_ext("fonts/inter.woff2", false), "font/woff2");
+    assert_eq!(get_mime_by_ext("photo.jpeg", false), "image/jpeg");
+    assert_eq!(get_mime_by_ext("sounds/click.mp3", false), "audio/mpeg");
+}
+
+#[test]
+fn source_maps_are_served_as_json() {
+    let root = TestDir::new("source-maps");
+    root.write("index.js.map", r#"{"version":3}"#);
+
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        ..Default::default()
+    };
+    let response = get(&options, "index.js.map");
+
+    // The map sits next to the built-in interpreter, but is still read from the asset root
+    assert_eq!(
+        response.headers()["Content-Type"],
+        "application/json; charset=utf-8"
+    );
+    assert_eq!(response.body(), br#"{"version":3}"#);
+}
+
+#[test]
+fn cache_busters_are_stripped_from_asset_paths() {
+    let root = TestDir::new("cache-busters");
+    root.write("app.js", "main()");
+
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        ..Default::default()
+    };
+
+    for path in ["app.js?v=abcd1234", "app.js#main", "app.js?v=1#main"] {
+        let response = get(&options, path);
+        assert_eq!(response.body(), b"main()");
+        assert_eq!(
+            response.headers()["Content-Type"],
+            "text/javascript; charset=utf-8"
+        );
+    }
+}
+
+#[test]
+fn head_requests_get_headers_without_a_body() {
+    let root = TestDir::new("head-requests");
+    root.write("app.js", "main()");
+
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        ..Default::default()
+    };
+    let head = |path: &str| {
+        let request = Request::builder()
+            .method(Method::HEAD)
+            .uri(format!("dioxus://index.html/{}", path))
+            .body(Vec::new())
+            .unwrap();
+        desktop_handler(&request, &options).unwrap()
+    };
+
+    let response = head("app.js");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["Content-Type"],
+        "text/javascript; charset=utf-8"
+    );
+    assert!(response.headers().contains_key("ETag"));
+    assert!(response.body().is_empty());
+
+    let response = head("missing.js");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(response.body().is_empty());
+
+    let response = head("");
+    assert_eq!(
+        response.headers()["Content-Type"],
+        "text/html; charset=utf-8"
+    );
+    assert!(response.body().is_empty());
+}
+
+#[test]
+fn dev_cors_answers_preflights() {
+    let assets = crate::assets::MemoryAssets::new().with_asset("app.js", &b"main()"[..]);
+    let options = |dev_cors| ProtocolOptions {
+        asset_providers: vec![Rc::new(assets.clone())],
+        dev_cors,
+        ..Default::default()
+    };
+    let send = |method: Method, options: &ProtocolOptions| {
+        let request = Request::builder()
+            .method(method)
+            .uri("dioxus://index.html/app.js")
+            .header("Access-Control-Request-Headers", "x-custom")
+            .body(Vec::new())
+            .unwrap();
+        desktop_handler(&request, options).unwrap()
+    };
+
+    let enabled = options(true);
+    let preflight = send(Method::OPTIONS, &enabled);
+    assert_eq!(preflight.status(), StatusCode::NO_CONTENT);
+    assert_eq!(preflight.headers()["Access-Control-Allow-Origin"], "*");
+    assert_eq!(
+        preflight.headers()["Access-Control-Allow-Headers"],
+        "x-custom"
+    );
+
+    let response = send(Method::GET, &enabled);
+    assert_eq!(response.body(), b"main()");
+    assert_eq!(response.headers()["Access-Control-Allow-Origin"], "*");
+
+    // Nothing changes unless it's turned on
+    let response = send(Method::GET, &options(false));
+    assert!(!response
+        .headers()
+        .contains_key("Access-Control-Allow-Origin"));
+}
+
+#[test]
+fn loader_scripts_run_around_the_interpreter() {
+    let plain = module_loader(
+        "main",
+        &Map::new(),
+        INTERPRETER_JS,
+        &LoaderScripts::default(),
+        None,
+    );
+    assert!(plain.starts_with("\n<script>\n    "));
+    assert!(plain.ends_with("    }\n</script>\n"));
+
+    let scripts = LoaderScripts {
+        before: Some("installPolyfills();".to_string()),
+        after: Some("reportStartup();".to_string()),
+    };
+    let loader = module_loader("main", &Map::new(), INTERPRETER_JS, &scripts, None);
+
+    let before = loader.find("installPolyfills();").unwrap();
+    let initialize = loader.find(r#"serializeIpcMessage("initialize""#).unwrap();
+    let after = loader.find("reportStartup();").unwrap();
+    assert!(before < loader.find("class Interpreter").unwrap());
+    assert!(initialize < after);
+
+    // Taking the scripts back out leaves the default loader untouched
+    assert_eq!(
+        loader
+            .replace("installPolyfills();\n    ", "")
+            .replace("    reportStartup();\n", ""),
+        plain
+    );
+}
+
+#[test]
+fn wasm_is_served_by_extension() {
+    let root = TestDir::new("wasm-mime");
+    root.write("module.wasm", b"\0asm\x01\0\0\0");
+    // Contents that sniff as something else entirely
+    root.write("packed.wasm", b"PK\x03\x04");
+
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        ..Default::default()
+    };
+
+    assert_eq!(
+        get(&options, "module.wasm").headers()["Content-Type"],
+        "application/wasm"
+    );
+    assert_eq!(
+        get(&options, "packed.wasm").headers()["Content-Type"],
+        "application/wasm"
+    );
+    assert_eq!(
+        get_mime_from_bytes("app_bg.wasm", b"PK\x03\x04", false),
+        "application/wasm"
+    );
+}
+
+#[test]
+fn accept_encoding_is_parsed() {
+    assert!(accepts_encoding("gzip, deflate, br", "br"));
+    assert!(accepts_encoding("GZIP", "gzip"));
+    assert!(accepts_encoding("*", "gzip"));
+    assert!(!accepts_encoding("gzip;q=0, br", "gzip"));
+    assert!(accepts_encoding("gzip;q=0.5", "gzip"));
+    assert!(!accepts_encoding("deflate", "gzip"));
+}
+
+#[test]
+fn precompressed_siblings_are_preferred() {
+    let root = TestDir::new("precompressed");
+    root.write("public/app.js", "main()");
+    root.write("public/app.js.br", "brotli");
+    root.write("public/app.js.gz", "gzip");
+    let public = root.join("public");
+
+    let options = ProtocolOptions {
+        asset_roots: vec![public],
+        ..Default::default()
+    };
+    let get = |accept_encoding: Option<&str>| {
+        let mut request = Request::builder().uri("dioxus://index.html/app.js");
+        if let Some(accept_encoding) = accept_encoding {
+            request = request.header("Accept-Encoding", accept_encoding);
+        }
+        desktop_handler(&request.body(Vec::new()).unwrap(), &options).unwrap()
+    };
+
+    let response = get(Some("gzip, br"));
+    assert_eq!(response.body(), b"brotli");
+    assert_eq!(response.headers()["Content-Encoding"], "br");
+    assert_eq!(
+        response.headers()["Content-Type"],
+        "text/javascript; charset=utf-8"
+    );
+
+    let response = get(Some("gzip"));
+    assert_eq!(response.body(), b"gzip");
+    assert_eq!(response.headers()["Content-Encoding"], "gzip");
+
+    let response = get(None);
+    assert_eq!(response.body(), b"main()");
+    assert!(!response.headers().contains_key("Content-Encoding"));
+}
+
+#[test]
+fn interpreter_can_be_replaced() {
+    let options = ProtocolOptions {
+        interpreter_js: Some("class Interpreter { /* patched */ }".to_string()),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        get(&options, "index.js").body(),
+        b"class Interpreter { /* patched */ }"
+    );
+
+    let index = String::from_utf8(get(&options, "").body().clone()).unwrap();
+    assert!(index.contains("class Interpreter { /* patched */ }"));
+    assert!(!index.contains(INTERPRETER_JS));
+    assert!(index.contains(r#"serializeIpcMessage("initialize""#));
+}
+
+#[test]
+fn routes_fall_back_to_the_index() {
+    let root = TestDir::new("spa-fallback");
+
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        spa_fallback: true,
+        ..Default::default()
+    };
+
+    let response = get(&options, "users/42");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["Content-Type"],
+        "text/html; charset=utf-8"
+    );
+    assert!(String::from_utf8_lossy(response.body()).contains("<script"));
+
+    // Missing assets are still reported
+    assert_eq!(
+        get(&options, "images/avatar.png").status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[test]
+fn missing_assets_get_the_custom_404_page() {
+    let root = TestDir::new("not-found");
+
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        not_found: Some("<h1>Lost?</h1>".to_string()),
+        ..Default::default()
+    };
+    let response = get(&options, "missing.png");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(
+        response.headers()["Content-Type"],
+        "text/html; charset=utf-8"
+    );
+    assert_eq!(response.body(), b"<h1>Lost?</h1>");
+}
+
+#[test]
+fn escaping_paths_get_the_custom_forbidden_body() {
+    let options = ProtocolOptions {
+        forbidden_body: Some(r#"{"error":"forbidden"}"#.to_string()),
+        forbidden_content_type: Some("application/json".to_string()),
+        ..Default::default()
+    };
+    let response = get(&options, "../secret.txt");
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(response.headers()["Content-Type"], "application/json");
+    assert_eq!(response.body(), br#"{"error":"forbidden"}"#);
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks_out_of_the_root_are_opt_in() {
+    let dir = TestDir::new("dev-symlinks");
+    std::fs::create_dir_all(dir.join("assets")).unwrap();
+    dir.write("shared/theme.css", "body {}");
+    let _ = std::os::unix::fs::symlink(dir.join("shared"), dir.join("assets/shared"));
+
+    let roots = [dir.join("assets")];
+    assert!(matches!(
+        find_asset(&roots, "shared/theme.css", None, false).unwrap(),
+        AssetLookup::Escaped
+    ));
+    assert!(matches!(
+        find_asset(&roots, "shared/theme.css", None, true).unwrap(),
+        AssetLookup::Found { .. }
+    ));
+}
+
+#[test]
+fn directories_can_be_listed() {
+    let root = TestDir::new("directory-listing");
+    std::fs::create_dir_all(root.join("images/icons")).unwrap();
+    root.write("images/logo.png", "png");
+
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        directory_listing: true,
+        ..Default::default()
+    };
+    let response = get(&options, "images");
+    let body = String::from_utf8_lossy(response.body());
+    assert_eq!(
+        response.headers()["Content-Type"],
+        "text/html; charset=utf-8"
+    );
+    assert!(body.contains(r#"<a href="dioxus://index.html/images/icons/">icons/</a>"#));
+    assert!(body.contains(r#"<a href="dioxus://index.html/images/logo.png">logo.png</a>"#));
+}
+
+#[test]
+fn unmodified_files_are_not_sent_again() {
+    let root = TestDir::new("last-modified");
+    root.write("app.css", "body {}");
+
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        ..Default::default()
+    };
+    let get = |since: Option<&str>| {
+        let mut request = Request::builder().uri("dioxus://index.html/app.css");
+        if let Some(since) = since {
+            request = request.header("If-Modified-Since", since);
+        }
+        desktop_handler(&request.body(Vec::new()).unwrap(), &options).unwrap()
+    };
+
+    let response = get(None);
+    let last_modified = response.headers()["Last-Modified"].to_str().unwrap();
+    assert!(last_modified.ends_with(" GMT"));
+
+    let response = get(Some(last_modified));
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert!(response.body().is_empty());
+
+    let response = get(Some("Thu, 01 Jan 1970 00:00:00 GMT"));
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[test]
+fn async_resolver_runs_before_the_filesystem() {
+    let options = crate::Config::new()
+        .with_async_asset_resolver(|path| async move {
+            (path == "db/42").then(|| Response::new(b"row 42".to_vec()))
+        })
+        .protocol_options();
+    let get = |path: &str| {
+        let request = Request::builder()
+            .uri(format!("dioxus://index.html/{}", path))
+            .body(Vec::new())
+            .unwrap();
+        block_on(desktop_handler_async(&request, &options)).unwrap()
+    };
+
+    assert_eq!(get("db/42").body(), b"row 42");
+    assert_eq!(get("db/43.png").status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn observer_sees_how_each_request_was_served() {
+    let root = TestDir::new("request-observer");
+    root.write("app.css", "body {}");
+
+    let served = Rc::new(RefCell::new(Vec::new()));
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        request_observer: Some(Rc::new({
+            let served = served.clone();
+            move |request: &ServedRequest| served.borrow_mut().push(request.clone())
+        })),
+        ..Default::default()
+    };
+
+    for path in ["", "index.js", "app.css", "missing.png", "../secret.txt"] {
+        get(&options, path);
+    }
+
+    let served = served.borrow();
+    let sources: Vec<_> = served.iter().map(|request| request.source).collect();
+    assert_eq!(
+        sources,
+        [
+            ResponseSource::Index,
+            ResponseSource::Interpreter,
+            ResponseSource::Filesystem,
+            ResponseSource::NotFound,
+            ResponseSource::Forbidden,
+        ]
+    );
+    assert_eq!(served[2].path, "app.css");
+    assert_eq!(served[2].status, 200);
+    assert_eq!(served[2].bytes, 7);
+    assert_eq!(served[3].status, 404);
+}
+
+#[test]
+fn custom_head_is_injected_into_custom_indexes() {
+    let index = |custom_index: &str| {
+        let options = ProtocolOptions {
+            custom_index: Some(custom_index.to_string()),
+            custom_head: Some(r#"<link rel="stylesheet" href="theme.css">"#.to_string()),
+            ..Default::default()
+        };
+        let response = get(&options, "");
+        String::from_utf8(response.body().clone()).unwrap()
+    };
+
+    let body = index("<html><head><title>App</title></head><body></body></html>");
+    assert!(body.contains(r#"<title>App</title><link rel="stylesheet" href="theme.css"></head>"#));
+
+    let body =
+        index("<html><head><!-- CUSTOM HEAD --><title>App</title></head><body></body></html>");
+    assert!(body.contains(r#"<head><link rel="stylesheet" href="theme.css"><title>App</title>"#));
+}
+
+#[test]
+fn module_loader_goes_where_the_custom_index_asks() {
+    let index = |custom_index: &str| {
+        let options = ProtocolOptions {
+            custom_index: Some(custom_index.to_string()),
+            ..Default::default()
+        };
+        let response = get(&options, "");
+        String::from_utf8(response.body().clone()).unwrap()
+    };
+
+    let body = index(
+        "<html><body><div id=\"main\"></div><!-- MODULE LOADER --><footer></footer></body></html>",
+    );
+    assert!(body.contains("<div id=\"main\"></div>\n<script"));
+    assert!(body.ends_with("</script>\n<footer></footer></body></html>"));
+
+    // Without the marker, only the last closing body tag gets the loader
+    let body = index("<html><body><script>const end = \"</body>\";</script></body></html>");
+    assert!(body.starts_with("<html><body><script>const end = \"</body>\";</script>\n<script"));
+    assert!(body.ends_with("</script>\n</body></html>"));
+
+    // Fragments without a body get it at the end
+    let body = index("<div id=\"main\"></div>");
+    assert!(body.starts_with("<div id=\"main\"></div>\n<script"));
+    assert!(body.ends_with("</script>\n"));
+}
+
+#[test]
+fn responses_carry_their_length() {
+    let root = TestDir::new("content-length");
+    root.write("clip.mp4", "0123456789");
+
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        ..Default::default()
+    };
+    let request = |method: Method, path: &str, range: Option<&str>| {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(format!("dioxus://index.html/{}", path));
+        if let Some(range) = range {
+            request = request.header("Range", range);
+        }
+        desktop_handler(&request.body(Vec::new()).unwrap(), &options).unwrap()
+    };
+    let length = |response: &Response<Vec<u8>>| response.headers()["Content-Length"].clone();
+
+    let index = request(Method::GET, "", None);
+    assert_eq!(length(&index), index.body().len().to_string().as_str());
+    assert_eq!(length(&request(Method::GET, "clip.mp4", None)), "10");
+    assert_eq!(
+        length(&request(Method::GET, "clip.mp4", Some("bytes=2-5"))),
+        "4"
+    );
+    assert_eq!(length(&request(Method::GET, "missing.png", None)), "9");
+    assert_eq!(length(&request(Method::GET, "../secret.txt", None)), "9");
+
+    // HEAD responses say how much a GET would send
+    assert_eq!(length(&request(Method::HEAD, "clip.mp4", None)), "10");
+}
+
+#[test]
+fn favicons_are_always_served() {
+    let root = TestDir::new("favicon");
+    let empty = TestDir::new("favicon-empty");
+    root.write("favicon.ico", "from disk");
+
+    let request = |options: &ProtocolOptions| get(options, "favicon.ico");
+
+    let configured = request(&ProtocolOptions {
+        favicon: Some(b"configured".to_vec()),
+        asset_roots: vec![root.to_path_buf()],
+        ..Default::default()
+    });
+    assert_eq!(configured.body(), b"configured");
+    assert_eq!(
+        configured.headers()["Content-Type"],
+        "image/vnd.microsoft.icon"
+    );
+
+    let on_disk = request(&ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        ..Default::default()
+    });
+    assert_eq!(on_disk.body(), b"from disk");
+
+    // Apps that don't ship an icon get the default one instead of a 404
+    let default = request(&ProtocolOptions {
+        asset_roots: vec![empty.to_path_buf()],
+        ..Default::default()
+    });
+    assert_eq!(default.status(), StatusCode::OK);
+    assert_eq!(
+        default.headers()["Content-Type"],
+        "image/vnd.microsoft.icon"
+    );
+    assert!(default.body().starts_with(&[0, 0, 1, 0, 1, 0]));
+    assert_eq!(&default.body()[22..30], b"\x89PNG\r\n\x1a\n");
+}
+
+#[test]
+fn files_used_as_directories_are_missing_assets() {
+    let root = TestDir::new("io-errors");
+    root.write("notes.txt", "notes");
+    root.write("fallback/notes.txt/inner.css", "inner");
+    let fallback = root.join("fallback");
+
+    let request = |roots: Vec<PathBuf>, path: &str| {
+        let options = ProtocolOptions {
+            asset_roots: roots,
+            ..Default::default()
+        };
+        get(&options, path)
+    };
+
+    // Looking inside a file can't find anything, so it's missing like any other path
+    let response = request(vec![root.to_path_buf()], "notes.txt/inner.css");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // ...and the search carries on to the next root
+    let response = request(vec![root, fallback], "notes.txt/inner.css");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.body(), b"inner");
+}
+
+#[test]
+fn embedded_assets_are_served_precompressed() {
+    use crate::embed::{EmbeddedAsset, EmbeddedAssets};
+
+    static ASSETS: &[EmbeddedAsset] = &[EmbeddedAsset {
+        path: "app.js",
+        bytes: b"main()",
+        mime: "text/javascript; charset=utf-8",
+        etag: "\"1\"",
+        brotli: Some(b"brotli" as &[u8]),
+        gzip: Some(b"gzip" as &[u8]),
+    }];
+
+    let options = ProtocolOptions {
+        asset_providers: vec![Rc::new(EmbeddedAssets::new(ASSETS))],
+        ..Default::default()
+    };
+    let get = |accepted: Option<&str>| {
+        let mut request = Request::builder().uri("dioxus://index.html/app.js");
+        if let Some(accepted) = accepted {
+            request = request.header("Accept-Encoding", accepted);
+        }
+        desktop_handler(&request.body(Vec::new()).unwrap(), &options).unwrap()
+    };
+
+    let response = get(Some("gzip, br"));
+    assert_eq!(response.headers()["Content-Encoding"], "br");
+    assert_eq!(
+        response.headers()["Content-Type"],
+        "text/javascript; charset=utf-8"
+    );
+    assert_eq!(response.body(), b"brotli");
+
+    let response = get(Some("gzip"));
+    assert_eq!(response.headers()["Content-Encoding"], "gzip");
+    assert_eq!(response.body(), b"gzip");
+
+    let response = get(None);
+    assert!(!response.headers().contains_key("Content-Encoding"));
+    assert_eq!(response.body(), b"main()");
+}
+
+#[test]
+fn root_name_is_escaped() {
+    let loader = module_loader(
+        "main\"</script><script>alert(1)//",
+        &Map::new(),
+        INTERPRETER_JS,
+        &LoaderScripts::default(),
+        None,
+    );
+    assert!(loader.contains(r#"let rootname = "main\"<\/script><script>alert(1)//";"#));
+}
+
+#[test]
+fn initialize_params_are_escaped() {
+    let mut params = Map::new();
+    params.insert("window".into(), "</script><script>alert(1)".into());
+
+    let loader = module_loader(
+        "main",
+        &params,
+        INTERPRETER_JS,
+        &LoaderScripts::default(),
+        None,
+    );
+    assert!(loader
+        .contains(r#"serializeIpcMessage("initialize", {"window":"<\/script><script>alert(1)"})"#));
+}
+
+#[test]
+fn mime_is_inferred_from_resolved_asset() {
+    let root = TestDir::new("mime-inference");
+
+    // A png with an extension we don't know about
+    root.write(
+        "image.dat",
+        [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A],
+    );
+
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        ..Default::default()
+    };
+    let response = get(&options, "image.dat");
+
+    assert_eq!(response.headers()["Content-Type"], "image/png");
+}
+
+#[test]
+fn mounts_resolve_against_their_own_root() {
+    let root = TestDir::new("asset-mounts");
+    root.write("shared/style.css", "body {}");
+    root.write("secret.txt", "secret");
+    let shared = root.join("shared");
+
+    let mount = |prefix: &str| AssetMount {
+        prefix: prefix.to_string(),
+        root: shared.clone(),
+    };
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        asset_mounts: vec![mount("app/assets"), mount("widget/assets")],
+        ..Default::default()
+    };
+
+    assert_eq!(get(&options, "app/assets/style.css").body(), b"body {}");
+    assert_eq!(get(&options, "widget/assets/style.css").body(), b"body {}");
+
+    // Each mount is guarded against its own root, not the default one
+    assert_eq!(
+        get(&options, "widget/assets/../secret.txt").status(),
+        StatusCode::FORBIDDEN
+    );
+}
+
+#[test]
+fn provided_assets_use_explicit_or_guessed_mime() {
+    use crate::assets::MemoryAssets;
+
+    let png = vec![0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+    let assets = MemoryAssets::new()
+        .with_asset("styles/main.css", &b"body {}"[..])
+        .with_asset("logo.dat", png)
+        .with_asset_and_mime("data.bin", &b"{}"[..], "application/json");
+    let options = ProtocolOptions {
+        asset_providers: vec![Rc::new(assets)],
+        ..Default::default()
+    };
+
+    let content_type = |path: &str| {
+        get(&options, path).headers()["Content-Type"]
+            .to_str()
+            .unwrap()
+            .to_string()
+    };
+
+    // Without an explicit mime we sniff the contents, then fall back to the extension
+    assert_eq!(content_type("styles/main.css"), "text/css; charset=utf-8");
+    assert_eq!(content_type("logo.dat"), "image/png");
+
+    // An explicit mime always wins
+    assert_eq!(content_type("data.bin"), "application/json");
+}
+
+#[test]
+fn transforms_are_applied_and_cached() {
+    use std::cell::Cell;
+
+    let root = TestDir::new("asset-transforms");
+    root.write("greeting.upper", "hello");
+    root.write("broken.fail", "");
+
+    let runs = Rc::new(Cell::new(0));
+    let counter = runs.clone();
+    let upper: AssetTransform = Rc::new(move |bytes: &[u8]| {
+        counter.set(counter.get() + 1);
+        Ok((bytes.to_ascii_uppercase(), "text/plain"))
+    });
+    let fail: AssetTransform = Rc::new(|_: &[u8]| {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "syntax error",
+        ))
+    });
+
+    let mut asset_transforms = HashMap::new();
+    asset_transforms.insert("upper".to_string(), upper);
+    asset_transforms.insert("fail".to_string(), fail);
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        asset_transforms,
+        ..Default::default()
+    };
+
+    for _ in 0..2 {
+        let response = get(&options, "greeting.upper");
+        assert_eq!(response.body(), b"HELLO");
+        assert_eq!(response.headers()["Content-Type"], "text/plain");
+    }
+
+    // The second request was served from the cache since the file didn't change
+    assert_eq!(runs.get(), 1);
+
+    let response = get(&options, "broken.fail");
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(response.body(), b"syntax error");
+}
+
+#[test]
+fn redirects_point_at_the_dioxus_scheme() {
+    let mut redirects = HashMap::new();
+    let redirect = |to: &str, permanent| Redirect {
+        to: to.to_string(),
+        permanent,
+    };
+    redirects.insert("old-name.js".to_string(), redirect("new-name.js", true));
+    redirects.insert("latest.css".to_string(), redirect("v2/style.css", false));
+    let options = ProtocolOptions {
+        redirects,
+        ..Default::default()
+    };
+
+    let permanent = get(&options, "old-name.js");
+    assert_eq!(permanent.status(), StatusCode::MOVED_PERMANENTLY);
+    assert_eq!(
+        permanent.headers()["Location"],
+        "dioxus://index.html/new-name.js"
+    );
+
+    let temporary = get(&options, "latest.css");
+    assert_eq!(temporary.status(), StatusCode::FOUND);
+    assert_eq!(
+        temporary.headers()["Location"],
+        "dioxus://index.html/v2/style.css"
+    );
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn stats_route_serves_latest_stats() {
+    let options = ProtocolOptions::default();
+    options.dom_stats.lock().unwrap().scopes = 3;
+
+    let response = get(&options, "__dioxus/stats");
+
+    assert_eq!(response.headers()["Content-Type"], "application/json");
+    let stats: Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(stats["scopes"], 3);
+}
+
+#[test]
+fn default_index_renders_configured_root() {
+    let options = ProtocolOptions {
+        root_name: "app".to_string(),
+        root_element: RootElement {
+            tag: "main".to_string(),
+            class: Some("layout dark".to_string()),
+            attributes: vec![("aria-label".to_string(), "\"Main\" content".to_string())],
+        },
+        ..Default::default()
+    };
+
+    let response = get(&options, "");
+    let body = String::from_utf8(response.body().clone()).unwrap();
+
+    assert!(body.contains(
+        r#"<main id="app" class="layout dark" aria-label="&quot;Main&quot; content"></main>"#
+    ));
+}
+
+#[test]
+fn extensionless_text_files_are_plain_text() {
+    for name in ["LICENSE", "README", "CHANGELOG", "docs/LICENSE"] {
+        assert_eq!(get_mime_by_ext(name, false), "text/plain; charset=utf-8");
+    }
+
+    // Other files without an extension are still treated as binary
+    assert_eq!(
+        get_mime_by_ext("bin/tool", false),
+        "application/octet-stream"
+    );
+}
+
+#[test]
+fn unknown_extensions_are_binary_unless_opted_in() {
+    assert_eq!(
+        get_mime_by_ext("data.xyz", false),
+        "application/octet-stream"
+    );
+    assert_eq!(
+        get_mime_by_ext("hello.com", true),
+        "text/html; charset=utf-8"
+    );
+
+    // Known extensions don't change either way
+    assert_eq!(get_mime_by_ext("app.css", true), "text/css; charset=utf-8");
+    assert_eq!(get_mime_by_ext("tool", true), "application/octet-stream");
+}
+
+#[test]
+fn themed_variants_fall_back_to_base_assets() {
+    let root = TestDir::new("asset-themes");
+    root.write("images/logo.svg", "light logo");
+    root.write("images/logo.dark.svg", "dark logo");
+    root.write("images/icon.svg", "icon");
+
+    let theme = AssetTheme::new();
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        asset_theme: Some(theme.clone()),
+        ..Default::default()
+    };
+
+    assert_eq!(get(&options, "images/logo.svg").body(), b"light logo");
+
+    theme.set("dark");
+    let response = get(&options, "images/logo.svg");
+    assert_eq!(response.body(), b"dark logo");
+    assert_eq!(response.headers()["Content-Type"], "image/svg+xml");
+
+    // Assets without a variant are served as-is
+    assert_eq!(get(&options, "images/icon.svg").body(), b"icon");
+
+    // A theme can't be used to escape the asset root
+    theme.set("/../../secret");
+    assert_eq!(get(&options, "images/logo.svg").body(), b"light logo");
+
+    theme.clear();
+    assert_eq!(get(&options, "images/logo.svg").body(), b"light logo");
+}
+
+#[test]
+fn custom_headers_override_defaults() {
+    let assets = crate::assets::MemoryAssets::new()
+        .with_asset_and_mime("app.wasm", &b"\0asm"[..], "application/wasm")
+        .with_asset_and_mime("other.wasm", &b"\0asm"[..], "application/wasm");
+
+    let mut asset_headers = HashMap::new();
+    asset_headers.insert(
+        "app.wasm".to_string(),
+        vec![
+            (
+                "Cross-Origin-Resource-Policy".to_string(),
+                "same-origin".to_string(),
+            ),
+            (
+                "Content-Type".to_string(),
+                "application/x-custom".to_string(),
+            ),
+        ],
+    );
+    let options = ProtocolOptions {
+        asset_providers: vec![Rc::new(assets)],
+        asset_headers,
+        ..Default::default()
+    };
+
+    let response = get(&options, "app.wasm");
+    assert_eq!(
+        response.headers()["Cross-Origin-Resource-Policy"],
+        "same-origin"
+    );
+    assert_eq!(response.headers()["Content-Type"], "application/x-custom");
+
+    // Other paths are left alone
+    let response = get(&options, "other.wasm");
+    assert!(!response
+        .headers()
+        .contains_key("Cross-Origin-Resource-Policy"));
+    assert_eq!(response.headers()["Content-Type"], "application/wasm");
+}
+
+#[test]
+fn flagged_assets_follow_the_active_flags() {
+    let assets = crate::assets::MemoryAssets::new()
+        .with_asset("old-ui.css", &b"old"[..])
+        .with_asset("new-ui.css", &b"new"[..]);
+
+    let flags = AssetFlags::new();
+    let mut flagged_assets = HashMap::new();
+    flagged_assets.insert(
+        "old-ui.css".to_string(),
+        vec![FlaggedAsset {
+            flag: "new-ui".to_string(),
+            variant: "new-ui.css".to_string(),
+        }],
+    );
+    let options = ProtocolOptions {
+        asset_providers: vec![Rc::new(assets)],
+        asset_flags: flags.clone(),
+        flagged_assets,
+        ..Default::default()
+    };
+
+    assert_eq!(get(&options, "old-ui.css").body(), b"old");
+
+    flags.enable("new-ui");
+    assert_eq!(get(&options, "old-ui.css").body(), b"new");
+
+    flags.disable("new-ui");
+    assert_eq!(get(&options, "old-ui.css").body(), b"old");
+}
+
+#[test]
+fn server_side_includes_are_expanded() {
+    let root = TestDir::new("server-side-includes");
+    root.write("header.html", "<h1>Title</h1>");
+    root.write(
+        "pages/about.html",
+        "<!--#include file=\"../header.html\" --><p>About</p>",
+    );
+    root.write("loop.html", "<!--#include file=\"loop.html\" -->");
+    root.write(
+        "escape.html",
+        "<!--#include file=\"../../../../../../etc/hostname\" -->",
+    );
+
+    // Includes are left alone unless they're enabled
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        ..Default::default()
+    };
+    assert_eq!(
+        get(&options, "pages/about.html").body(),
+        b"<!--#include file=\"../header.html\" --><p>About</p>"
+    );
+
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        server_side_includes: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        get(&options, "pages/about.html").body(),
+        b"<h1>Title</h1><p>About</p>"
+    );
+
+    // Loops stop at the depth limit and files outside the asset root are never read
+    assert_eq!(
+        get(&options, "loop.html").body(),
+        b"<!-- include failed -->"
+    );
+    assert_eq!(
+        get(&options, "escape.html").body(),
+        b"<!-- include failed -->"
+    );
+}
+
+#[cfg(feature = "protocol-stats")]
+#[test]
+fn requests_are_timed_and_counted() {
+    let assets = crate::assets::MemoryAssets::new().with_asset("app.js", &b"main()"[..]);
+    let options = ProtocolOptions {
+        asset_providers: vec![Rc::new(assets)],
+        ..Default::default()
+    };
+
+    get(&options, "app.js");
+    get(&options, "app.js");
+    get(&options, "index.js");
+
+    let stats = options.protocol_metrics.lock().unwrap().stats();
+    assert_eq!(stats.requests, 3);
+    assert_eq!(stats.status_codes[&200], 3);
+    assert_eq!(
+        stats.bytes_served,
+        12 + dioxus_interpreter_js::INTERPRETER_JS.len() as u64
+    );
+    assert!(stats.resolve.p95 <= stats.total.p95);
+}
+
+#[test]
+fn static_assets_advertise_and_serve_ranges() {
+    let assets = crate::assets::MemoryAssets::new().with_asset("clip.mp4", &b"0123456789"[..]);
+    let options = ProtocolOptions {
+        asset_providers: vec![Rc::new(assets)],
+        ..Default::default()
+    };
+
+    let get = |path: &str, range: Option<&str>| {
+        let mut request = Request::builder().uri(format!("dioxus://index.html/{}", path));
+        if let Some(range) = range {
+            request = request.header("Range", range);
+        }
+        desktop_handler(&request.body(Vec::new()).unwrap(), &options).unwrap()
+    };
+
+    let response = get("clip.mp4", None);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["Accept-Ranges"], "bytes");
+    assert_eq!(response.body(), b"0123456789");
+
+    // Only the first of several ranges is served
+    let response = get("clip.mp4", Some("bytes=2-4,8-"));
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()["Content-Range"], "bytes 2-4/10");
+    assert_eq!(response.body(), b"234");
+
+    let response = get("clip.mp4", Some("bytes=20-"));
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(response.headers()["Content-Range"], "bytes */10");
+
+    // Generated content can't be served in parts
+    let response = get("index.js", Some("bytes=0-9"));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["Accept-Ranges"], "none");
+}
+
+#[test]
+fn files_are_read_in_ranges() {
+    let root = TestDir::new("file-ranges");
+    root.write("clip.mp4", b"0123456789");
+
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        ..Default::default()
+    };
+
+    let get = |range: Option<&str>| {
+        let mut request = Request::builder().uri("dioxus://index.html/clip.mp4");
+        if let Some(range) = range {
+            request = request.header("Range", range);
+        }
+        desktop_handler(&request.body(Vec::new()).unwrap(), &options).unwrap()
+    };
+
+    let response = get(None);
+    assert_eq!(response.headers()["Accept-Ranges"], "bytes");
+    assert_eq!(response.body(), b"0123456789");
+
+    let response = get(Some("bytes=-3"));
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()["Content-Range"], "bytes 7-9/10");
+    assert_eq!(response.body(), b"789");
+
+    let response = get(Some("bytes=10-"));
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+}
+
+#[test]
+fn files_are_revalidated_with_etags() {
+    let root = TestDir::new("etags");
+    root.write("logo.svg", "<svg></svg>");
+
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        cache_max_age: Some(Duration::from_secs(60)),
+        ..Default::default()
+    };
+
+    let get = |if_none_match: Option<&str>| {
+        let mut request = Request::builder().uri("dioxus://index.html/logo.svg");
+        if let Some(tag) = if_none_match {
+            request = request.header("If-None-Match", tag);
+        }
+        desktop_handler(&request.body(Vec::new()).unwrap(), &options).unwrap()
+    };
+
+    let response = get(None);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["Cache-Control"], "max-age=60");
+    let etag = response.headers()["ETag"].to_str().unwrap().to_string();
+    assert!(etag.starts_with("W/\""));
+
+    let response = get(Some(&format!("\"other\", {}", etag)));
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert!(response.body().is_empty());
+
+    assert_eq!(get(Some("W/\"other\"")).status(), StatusCode::OK);
+}
+
+#[test]
+fn resolvers_answer_before_the_filesystem() {
+    let root = TestDir::new("asset-resolver");
+    root.write("style.css", "body {}");
+
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        asset_resolver: Some(Rc::new(|path: &str| {
+            let key = path.strip_prefix("uploads/")?;
+            Response::builder()
+                .header("Content-Type", "image/png")
+                .body(key.as_bytes().to_vec())
+                .ok()
+        })),
+        ..Default::default()
+    };
+
+    let response = get(&options, "uploads/42");
+    assert_eq!(response.headers()["Content-Type"], "image/png");
+    assert_eq!(response.body(), b"42");
+
+    assert_eq!(get(&options, "style.css").body(), b"body {}");
+}
+
+#[test]
+fn provided_assets_only_never_touches_the_filesystem() {
+    let root = TestDir::new("provided-only");
+    root.write("style.css", "body {}");
+
+    let assets = crate::assets::MemoryAssets::new().with_asset("app.js", &b"main()"[..]);
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        asset_providers: vec![Rc::new(assets)],
+        provided_assets_only: true,
+        ..Default::default()
+    };
+
+    assert_eq!(get(&options, "app.js").body(), b"main()");
+    assert_eq!(get(&options, "style.css").status(), StatusCode::NOT_FOUND);
+    assert_eq!(get(&options, "../app.js").status(), StatusCode::FORBIDDEN);
+}
+
+#[test]
+fn content_security_policy_allows_the_module_loader() {
+    assert_eq!(
+        policy_with_nonce("default-src 'self'; script-src 'self';", "abc"),
+        "default-src 'self'; script-src 'self' 'nonce-abc'"
+    );
+    assert_eq!(
+        policy_with_nonce("default-src 'self'", "abc"),
+        "default-src 'self'; script-src 'nonce-abc'"
+    );
+
+    let options = ProtocolOptions {
+        content_security_policy: Some("default-src 'self'".to_string()),
+        ..Default::default()
+    };
+    let response = get(&options, "");
+
+    let policy = response.headers()["Content-Security-Policy"]
+        .to_str()
+        .unwrap();
+    let nonce = policy
+        .split("'nonce-")
+        .nth(1)
+        .unwrap()
+        .trim_end_matches('\'');
+    let body = String::from_utf8(response.body().clone()).unwrap();
+    assert!(body.contains(&format!("<script nonce=\"{}\">", nonce)));
+}
+
+#[test]
+fn asset_paths_are_percent_decoded() {
+    assert_eq!(
+        percent_decode("my%20image.png").as_deref(),
+        Some("my image.png")
+    );
+    assert_eq!(
+        percent_decode("%2e%2E/secret").as_deref(),
+        Some("../secret")
+    );
+    assert_eq!(percent_decode("100%"), None);
+    assert_eq!(percent_decode("%zz"), None);
+    assert_eq!(percent_decode("%+f"), None);
+
+    let root = TestDir::new("percent-decoding");
+    root.write("public/my image.png", "png");
+    root.write("secret.txt", "secret");
+    let public = root.join("public");
+
+    let options = ProtocolOptions {
+        asset_roots: vec![public],
+        ..Default::default()
+    };
+
+    assert_eq!(get(&options, "my%20image.png").body(), b"png");
+    assert_eq!(
+        get(&options, "my%20image.png?dioxus-reload=1").body(),
+        b"png"
+    );
+    assert_eq!(
+        get(&options, "%2e%2e/secret.txt").status(),
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        get(&options, "%2e%2e%2fsecret.txt").status(),
+        StatusCode::FORBIDDEN
+    );
+}
+
+#[test]
+fn asset_roots_are_searched_in_order() {
+    let root = TestDir::new("asset-roots");
+    root.write("theme/style.css", "themed");
+    root.write("builtin/style.css", "builtin");
+    root.write("builtin/app.js", "main()");
+
+    let options = ProtocolOptions {
+        asset_roots: vec![
+            root.join("missing"),
+            root.join("theme"),
+            root.join("builtin"),
+        ],
+        ..Default::default()
+    };
+
+    assert_eq!(get(&options, "style.css").body(), b"themed");
+    assert_eq!(get(&options, "app.js").body(), b"main()");
+    assert_eq!(get(&options, "logo.png").status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn large_media_is_sent_in_parts() {
+    let root = TestDir::new("max-response-size");
+    root.write("clip.mp4", b"0123456789");
+    root.write("app.js", b"0123456789");
+
+    let options = ProtocolOptions {
+        asset_roots: vec![root.to_path_buf()],
+        max_response_size: Some(4),
+        ..Default::default()
+    };
+    let get = |path: &str, range: Option<&str>| {
+        let mut request = Request::builder().uri(format!("dioxus://index.html/{}", path));
+        if let Some(range) = range {
+            request = request.header("Range", range);
+        }
+        desktop_handler(&request.body(Vec::new()).unwrap(), &options).unwrap()
+    };
+
+    let response = get("clip.mp4", None);
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()["Content-Range"], "bytes 0-3/10");
+    assert_eq!(response.body(), b"0123");
+
+    let response = get("clip.mp4", Some("bytes=4-"));
+    assert_eq!(response.headers()["Content-Range"], "bytes 4-7/10");
+    assert_eq!(response.body(), b"4567");
+
+    // Scripts can't be loaded in parts, so they're always sent whole
+    let response = get("app.js", None);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.body(), b"0123456789");
+}
+
+#[test]
+fn preloads_are_sent_as_link_headers() {
+    let assets = crate::assets::MemoryAssets::new().with_asset("app.js", &b"main()"[..]);
+
+    let mut preloads = HashMap::new();
+    preloads.insert(
+        "app.js".to_string(),
+        vec![
+            Preload {
+                path: "app.css".to_string(),
+                destination: "style".to_string(),
+            },
+            Preload {
+                path: "fonts/inter.woff2".to_string(),
+                destination: "font".to_string(),
+            },
+        ],
+    );
+    let options = ProtocolOptions {
+        asset_providers: vec![Rc::new(assets)],
+        preloads,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        get(&options, "app.js").headers()["Link"],
+        "</app.css>; rel=preload; as=style, </fonts/inter.woff2>; rel=preload; as=font"
+    );
+    assert!(!get(&options, "index.js").headers().contains_key("Link"));
+}
+
+#[test]
+fn custom_schemes_are_stripped_from_requests() {
+    let assets = crate::assets::MemoryAssets::new().with_asset("app.js", &b"main()"[..]);
+
+    let mut redirects = HashMap::new();
+    redirects.insert(
+        "old.js".to_string(),
+        Redirect {
+            to: "app.js".to_string(),
+            permanent: false,
+        },
+    );
+    let options = ProtocolOptions {
+        scheme: Some("myapp".to_string()),
+        asset_providers: vec![Rc::new(assets)],
+        redirects,
+        ..Default::default()
+    };
+
+    let get = |uri: &str| {
+        let request = Request::builder().uri(uri).body(Vec::new()).unwrap();
+        desktop_handler(&request, &options).unwrap()
+    };
+
+    assert_eq!(get("myapp://index.html/app.js").body(), b"main()");
+    assert_eq!(
+        get("myapp://index.html/old.js").headers()["Location"],
+        "myapp://index.html/app.js"
+    );
 }
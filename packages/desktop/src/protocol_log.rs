@@ -0,0 +1,55 @@
+use std::rc::Rc;
+
+/// Gets told about every request the `dioxus://` protocol answers
+pub(crate) type RequestObserver = Rc<dyn Fn(&ServedRequest)>;
+
+/// A request answered by the `dioxus://` protocol, passed to the observer from
+/// [`crate::Config::with_request_observer`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServedRequest {
+    /// The path that was requested, relative to the root of the scheme, like `images/logo.png`
+    pub path: String,
+
+    /// The status code of the response. Requests that failed before a response could be built count as a `500`.
+    pub status: u16,
+
+    /// The size of the response body, after compression. `HEAD` requests always have an empty body.
+    pub bytes: usize,
+
+    /// Where the response came from
+    pub source: ResponseSource,
+}
+
+/// Where the `dioxus://` protocol found the response to a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseSource {
+    /// The index page, either the default one or the one from [`crate::Config::with_custom_index`]
+    Index,
+
+    /// The interpreter script served as `index.js`
+    Interpreter,
+
+    /// A redirect from [`crate::Config::with_redirect`]
+    Redirect,
+
+    /// One of the [`crate::AssetProvider`]s
+    Provider,
+
+    /// The resolver from [`crate::Config::with_asset_resolver`]
+    Resolver,
+
+    /// The resolver from [`crate::Config::with_async_asset_resolver`]
+    AsyncResolver,
+
+    /// A file or directory listing from one of the asset directories
+    Filesystem,
+
+    /// Nothing could serve the path
+    NotFound,
+
+    /// The path tried to leave the asset directories
+    Forbidden,
+
+    /// A response the protocol builds itself, like a CORS preflight, the debug stats, or the favicon
+    Internal,
+}
@@ -0,0 +1,151 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// How many recent requests the latency percentiles are calculated over
+const MAX_SAMPLES: usize = 1024;
+
+/// Aggregate timings for the requests served by the `dioxus://` protocol, from [`crate::DesktopContext::protocol_stats`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProtocolStats {
+    /// The number of requests served
+    pub requests: usize,
+
+    /// The total size of every response body
+    pub bytes_served: u64,
+
+    /// How many responses were sent with each status code
+    pub status_codes: BTreeMap<u16, usize>,
+
+    /// The time from receiving a request to sending its response
+    pub total: Latency,
+
+    /// The time spent finding the asset, including checking providers, mounts, and themed variants
+    pub resolve: Latency,
+
+    /// The time spent reading and transforming the asset once it was found
+    pub read: Latency,
+}
+
+/// Latency percentiles over the most recent requests
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Latency {
+    /// The median latency
+    pub p50: Duration,
+
+    /// The latency that 95% of requests were faster than
+    pub p95: Duration,
+}
+
+/// Times the stages of a single request
+pub(crate) struct RequestTimer {
+    start: Instant,
+    resolved: Option<Instant>,
+}
+
+impl RequestTimer {
+    pub fn start() -> Self {
+        Self {
+            start: Instant::now(),
+            resolved: None,
+        }
+    }
+
+    /// Mark the asset as found. Requests that never find an asset count their whole time as resolving.
+    pub fn resolved(&mut self) {
+        self.resolved = Some(Instant::now());
+    }
+
+    fn finish(self) -> Sample {
+        let end = Instant::now();
+        let resolved = self.resolved.unwrap_or(end);
+        Sample {
+            total: end - self.start,
+            resolve: resolved - self.start,
+            read: end - resolved,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Sample {
+    total: Duration,
+    resolve: Duration,
+    read: Duration,
+}
+
+/// Collects the timings of every request served by every webview
+#[derive(Default)]
+pub(crate) struct ProtocolMetrics {
+    requests: usize,
+    bytes_served: u64,
+    status_codes: BTreeMap<u16, usize>,
+    samples: VecDeque<Sample>,
+}
+
+impl ProtocolMetrics {
+    pub fn record(&mut self, timer: RequestTimer, status: u16, bytes: usize) {
+        self.requests += 1;
+        self.bytes_served += bytes as u64;
+        *self.status_codes.entry(status).or_default() += 1;
+
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(timer.finish());
+    }
+
+    pub fn stats(&self) -> ProtocolStats {
+        ProtocolStats {
+            requests: self.requests,
+            bytes_served: self.bytes_served,
+            status_codes: self.status_codes.clone(),
+            total: self.latency(|sample| sample.total),
+            resolve: self.latency(|sample| sample.resolve),
+            read: self.latency(|sample| sample.read),
+        }
+    }
+
+    fn latency(&self, stage: impl Fn(&Sample) -> Duration) -> Latency {
+        let mut durations: Vec<_> = self.samples.iter().map(stage).collect();
+        durations.sort_unstable();
+
+        Latency {
+            p50: percentile(&durations, 50),
+            p95: percentile(&durations, 95),
+        }
+    }
+}
+
+/// Get the nearest-rank percentile of a sorted list
+fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+
+    let rank = (sorted.len() * percent + 99) / 100;
+    sorted[rank.max(1) - 1]
+}
+
+#[test]
+fn percentiles_use_nearest_rank() {
+    let durations: Vec<_> = (1..=100).map(Duration::from_millis).collect();
+    assert_eq!(percentile(&durations, 50), Duration::from_millis(50));
+    assert_eq!(percentile(&durations, 95), Duration::from_millis(95));
+
+    assert_eq!(percentile(&durations[..1], 95), Duration::from_millis(1));
+    assert_eq!(percentile(&[], 50), Duration::ZERO);
+}
+
+#[test]
+fn metrics_count_requests_by_status() {
+    let mut metrics = ProtocolMetrics::default();
+    metrics.record(RequestTimer::start(), 200, 10);
+    metrics.record(RequestTimer::start(), 200, 5);
+    metrics.record(RequestTimer::start(), 404, 9);
+
+    let stats = metrics.stats();
+    assert_eq!(stats.requests, 3);
+    assert_eq!(stats.bytes_served, 24);
+    assert_eq!(stats.status_codes[&200], 2);
+    assert_eq!(stats.status_codes[&404], 1);
+}
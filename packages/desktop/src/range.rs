@@ -0,0 +1,163 @@
+use std::fmt;
+
+/// Why a `Range` header couldn't be turned into byte ranges
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeError {
+    /// The header isn't a valid `Range` header
+    Malformed,
+
+    /// The ranges are in a unit other than `bytes`
+    UnsupportedUnit,
+
+    /// None of the ranges overlap the content, which should be answered with a `416 Range Not Satisfiable`
+    Unsatisfiable,
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RangeError::Malformed => "malformed range header",
+            RangeError::UnsupportedUnit => "range unit is not bytes",
+            RangeError::Unsatisfiable => "range is not satisfiable",
+        })
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+/// Parse the value of a `Range` header into inclusive `(start, end)` byte ranges for content that's `total` bytes long.
+///
+/// This supports every form of byte range: `bytes=0-499`, open-ended ranges like `bytes=500-`, suffix ranges like
+/// `bytes=-500`, and lists of them like `bytes=0-99, 200-299`. Ends past the content are clamped to the last byte.
+///
+/// Ranges that start past the end of the content are skipped, and if no ranges are left this returns
+/// [`RangeError::Unsatisfiable`]. The ranges are returned in the order they were requested and aren't merged, so they
+/// may overlap.
+pub fn parse_range(header: &str, total: u64) -> Result<Vec<(u64, u64)>, RangeError> {
+    let (unit, specs) = header.split_once('=').ok_or(RangeError::Malformed)?;
+
+    if unit.trim() != "bytes" {
+        return Err(RangeError::UnsupportedUnit);
+    }
+
+    let mut ranges = Vec::new();
+
+    for spec in specs.split(',') {
+        let (start, end) = spec.trim().split_once('-').ok_or(RangeError::Malformed)?;
+
+        let range = match (start.is_empty(), end.is_empty()) {
+            // `-500` is the last 500 bytes
+            (true, false) => {
+                let len = parse_position(end)?;
+                match len.min(total) {
+                    0 => None,
+                    len => Some((total - len, total - 1)),
+                }
+            }
+
+            // `500-` is everything from byte 500 onwards
+            (false, true) => {
+                let start = parse_position(start)?;
+                (start < total).then(|| (start, total - 1))
+            }
+
+            // `0-499` is the first 500 bytes
+            (false, false) => {
+                let (start, end) = (parse_position(start)?, parse_position(end)?);
+                if end < start {
+                    return Err(RangeError::Malformed);
+                }
+                (start < total).then(|| (start, end.min(total - 1)))
+            }
+
+            (true, true) => return Err(RangeError::Malformed),
+        };
+
+        ranges.extend(range);
+    }
+
+    if ranges.is_empty() {
+        return Err(RangeError::Unsatisfiable);
+    }
+
+    Ok(ranges)
+}
+
+fn parse_position(position: &str) -> Result<u64, RangeError> {
+    // `u64::from_str` accepts a leading `+`, but the header only allows digits
+    if !position.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(RangeError::Malformed);
+    }
+
+    position.parse().map_err(|_| RangeError::Malformed)
+}
+
+#[test]
+fn closed_ranges() {
+    assert_eq!(parse_range("bytes=0-499", 1000), Ok(vec![(0, 499)]));
+    assert_eq!(parse_range("bytes=500-500", 1000), Ok(vec![(500, 500)]));
+
+    // The end is clamped to the content
+    assert_eq!(parse_range("bytes=900-2000", 1000), Ok(vec![(900, 999)]));
+}
+
+#[test]
+fn open_ended_ranges() {
+    assert_eq!(parse_range("bytes=500-", 1000), Ok(vec![(500, 999)]));
+    assert_eq!(parse_range("bytes=0-", 1), Ok(vec![(0, 0)]));
+}
+
+#[test]
+fn suffix_ranges() {
+    assert_eq!(parse_range("bytes=-500", 1000), Ok(vec![(500, 999)]));
+
+    // Asking for more than there is gives back the whole content
+    assert_eq!(parse_range("bytes=-5000", 1000), Ok(vec![(0, 999)]));
+
+    assert_eq!(
+        parse_range("bytes=-0", 1000),
+        Err(RangeError::Unsatisfiable)
+    );
+}
+
+#[test]
+fn multiple_and_overlapping_ranges() {
+    assert_eq!(
+        parse_range("bytes=0-99, 200-299,-100", 1000),
+        Ok(vec![(0, 99), (200, 299), (900, 999)])
+    );
+
+    assert_eq!(
+        parse_range("bytes=0-499,250-749", 1000),
+        Ok(vec![(0, 499), (250, 749)])
+    );
+}
+
+#[test]
+fn out_of_bounds_ranges() {
+    assert_eq!(
+        parse_range("bytes=1000-1999", 1000),
+        Err(RangeError::Unsatisfiable)
+    );
+    assert_eq!(parse_range("bytes=0-", 0), Err(RangeError::Unsatisfiable));
+
+    // Satisfiable ranges are kept even if others aren't
+    assert_eq!(parse_range("bytes=2000-,0-9", 1000), Ok(vec![(0, 9)]));
+}
+
+#[test]
+fn malformed_ranges() {
+    assert_eq!(parse_range("0-499", 1000), Err(RangeError::Malformed));
+    assert_eq!(parse_range("bytes=", 1000), Err(RangeError::Malformed));
+    assert_eq!(parse_range("bytes=-", 1000), Err(RangeError::Malformed));
+    assert_eq!(
+        parse_range("bytes=500-100", 1000),
+        Err(RangeError::Malformed)
+    );
+    assert_eq!(parse_range("bytes=a-b", 1000), Err(RangeError::Malformed));
+    assert_eq!(parse_range("bytes=+1-2", 1000), Err(RangeError::Malformed));
+    assert_eq!(
+        parse_range("items=0-10", 1000),
+        Err(RangeError::UnsupportedUnit)
+    );
+}
@@ -0,0 +1,60 @@
+//! Compare the default frame capacity heuristic against low-memory mode.
+//!
+//! By default, each scope's frames keep the memory they grew to on earlier renders. With
+//! `VirtualDom::with_fixed_frame_capacity`, they're reallocated at a small fixed size before every render instead.
+//!
+//! The app alternates between a large and a small list, so the default heuristic holds onto the memory of the large
+//! list while the small one is shown. The memory held while the small list is shown is printed alongside the timings.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use dioxus::prelude::*;
+
+criterion_group!(mbenches, rerender_rows);
+criterion_main!(mbenches);
+
+fn app(cx: Scope) -> Element {
+    let renders = cx.use_hook(|| 0_usize);
+    *renders += 1;
+
+    // One large render followed by three small ones
+    let rows = if *renders % 4 == 1 { 1_000 } else { 10 };
+
+    render!(
+        table {
+            tbody {
+                (0..rows).map(|i| rsx!( tr { td { "row {i}" } } ))
+            }
+        }
+    )
+}
+
+fn rerender_rows(c: &mut Criterion) {
+    let configs: [(&str, fn() -> VirtualDom); 2] = [
+        ("default capacity", || VirtualDom::new(app)),
+        ("fixed capacity", || {
+            VirtualDom::new(app).with_fixed_frame_capacity(1024)
+        }),
+    ];
+
+    for (name, new_dom) in configs {
+        // Render the large list, then a small list into each frame
+        let mut dom = new_dom();
+        let _ = dom.rebuild();
+        for _ in 0..2 {
+            dom.mark_dirty(ScopeId(0));
+            let _ = dom.render_immediate();
+        }
+        println!(
+            "{}: {} bytes held after shrinking",
+            name,
+            dom.stats().allocated_bytes
+        );
+
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                dom.mark_dirty(ScopeId(0));
+                let _ = dom.render_immediate();
+            })
+        });
+    }
+}
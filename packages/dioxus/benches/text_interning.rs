@@ -0,0 +1,47 @@
+//! Compare rendering formatted text with and without `VirtualDom::with_text_interning`.
+//!
+//! The app re-renders a table whose cells are formatted text that comes out the same every render, so with interning
+//! each cell is only copied once. The bytes each configuration holds are printed alongside the timings.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use dioxus::prelude::*;
+
+criterion_group!(mbenches, rerender_text);
+criterion_main!(mbenches);
+
+fn app(cx: Scope) -> Element {
+    render!(
+        table {
+            tbody {
+                (0..1_000).map(|i| rsx!( tr { td { "row {i} of the table" } } ))
+            }
+        }
+    )
+}
+
+fn rerender_text(c: &mut Criterion) {
+    let configs: [(&str, fn() -> VirtualDom); 2] = [
+        ("copied text", || VirtualDom::new(app)),
+        ("interned text", || {
+            VirtualDom::new(app).with_text_interning(64 * 1024)
+        }),
+    ];
+
+    for (name, new_dom) in configs {
+        let mut dom = new_dom();
+        let _ = dom.rebuild();
+        println!(
+            "{}: {} bytes held, {} bytes interned",
+            name,
+            dom.stats().allocated_bytes,
+            dom.base_scope().interned_text_bytes()
+        );
+
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                dom.mark_dirty(ScopeId(0));
+                let _ = dom.render_immediate();
+            })
+        });
+    }
+}